@@ -0,0 +1,175 @@
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+#[doc = "Uniform `Domain` over the variants of a fieldless enum"]
+#[proc_macro_derive(Domain)]
+pub fn derive_domain(input: TokenStream) -> TokenStream {
+    let (name, variants) = match parse(input) {
+        Ok(parsed) => parsed,
+        Err(message) => return error(message),
+    };
+    format!(
+        "impl ::probs::Domain for {name} {{
+            type Iter = ::std::iter::Map<::std::iter::Repeat<()>, fn(()) -> Self>;
+            fn random() -> Self::Iter {{
+                let draw: fn(()) -> Self = |_| {{
+                    <Self as ::probs::Domain>::random_with(&mut ::probs::__private::rand::thread_rng())
+                }};
+                ::std::iter::repeat(()).map(draw)
+            }}
+            fn random_with<R: ::probs::__private::rand::Rng + ?Sized>(gen: &mut R) -> Self {{
+                match ::probs::__private::rand::Rng::gen_range(gen, 0..{n}) {{
+                    {arms}
+                    _ => unreachable!(),
+                }}
+            }}
+        }}",
+        n = variants.len(),
+        arms = from_index(&name, &variants),
+        name = name,
+    )
+    .parse()
+    .unwrap()
+}
+
+#[doc = "`Discrete` enumerating the variants of a fieldless enum in declaration order"]
+#[proc_macro_derive(Discrete)]
+pub fn derive_discrete(input: TokenStream) -> TokenStream {
+    let (name, variants) = match parse(input) {
+        Ok(parsed) => parsed,
+        Err(message) => return error(message),
+    };
+    let list: Vec<String> = (variants.iter())
+        .map(|variant| format!("{}::{}", name, variant))
+        .collect();
+    format!(
+        "impl ::probs::Discrete for {name} {{
+            type Iter = ::std::array::IntoIter<Self, {n}>;
+            fn iter() -> Self::Iter {{
+                ::std::iter::IntoIterator::into_iter([{list}])
+            }}
+        }}",
+        name = name,
+        n = variants.len(),
+        list = list.join(", "),
+    )
+    .parse()
+    .unwrap()
+}
+
+#[doc = "`Coordinates` of a fieldless enum as its variant index"]
+#[proc_macro_derive(Coordinates)]
+pub fn derive_coordinates(input: TokenStream) -> TokenStream {
+    let (name, variants) = match parse(input) {
+        Ok(parsed) => parsed,
+        Err(message) => return error(message),
+    };
+    format!(
+        "impl ::probs::Coordinates for {name} {{
+            fn coordinates(&self) -> ::std::vec::Vec<f64> {{
+                let k: usize = match self {{ {arms} }};
+                ::std::vec![k as f64]
+            }}
+        }}",
+        arms = to_index(&name, &variants),
+        name = name,
+    )
+    .parse()
+    .unwrap()
+}
+
+#[doc = "`Perturb` of a fieldless enum stepping its variant index by at most `ceil(scale)`, wrapping around"]
+#[proc_macro_derive(Perturb)]
+pub fn derive_perturb(input: TokenStream) -> TokenStream {
+    let (name, variants) = match parse(input) {
+        Ok(parsed) => parsed,
+        Err(message) => return error(message),
+    };
+    format!(
+        "impl ::probs::Perturb for {name} {{
+            fn perturb<R: ::probs::__private::rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {{
+                let k: usize = match self {{ {to} }};
+                let half: usize = {n} / 2;
+                let reach = (scale.ceil() as usize).max(1).min(half);
+                let step = ::probs::__private::rand::Rng::gen_range(gen, 0..=2 * reach);
+                match (k + {n} - reach + step) % {n} {{
+                    {from}
+                    _ => unreachable!(),
+                }}
+            }}
+        }}",
+        n = variants.len(),
+        to = to_index(&name, &variants),
+        from = from_index(&name, &variants),
+        name = name,
+    )
+    .parse()
+    .unwrap()
+}
+
+// match arms from each variant to its index
+fn to_index(name: &str, variants: &[String]) -> String {
+    (variants.iter().enumerate())
+        .map(|(k, variant)| format!("{}::{} => {},", name, variant, k))
+        .collect()
+}
+
+// match arms from each index to its variant
+fn from_index(name: &str, variants: &[String]) -> String {
+    (variants.iter().enumerate())
+        .map(|(k, variant)| format!("{} => {}::{},", k, name, variant))
+        .collect()
+}
+
+// name and variants of a fieldless enum without generics
+fn parse(input: TokenStream) -> Result<(String, Vec<String>), &'static str> {
+    let mut tokens = input.into_iter();
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "enum" => break,
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {
+                return Err("only enums are supported")
+            }
+            Some(_) => continue,
+            None => return Err("only enums are supported"),
+        }
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("enum isn't named"),
+    };
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        _ => return Err("generics aren't supported"),
+    };
+
+    let (mut variants, mut expect) = (vec![], true);
+    let mut body = body.into_iter().peekable();
+    while let Some(token) = body.next() {
+        match token {
+            // attributes and doc comments on a variant
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                body.next();
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ',' => expect = true,
+            TokenTree::Ident(ident) if expect => {
+                if let Some(TokenTree::Group(_)) = body.peek() {
+                    return Err("variants can't carry fields");
+                }
+                variants.push(ident.to_string());
+                expect = false;
+            }
+            // explicit discriminants
+            _ => {}
+        }
+    }
+    if variants.is_empty() {
+        return Err("enum has no variants");
+    }
+    Ok((name, variants))
+}
+
+fn error(message: &str) -> TokenStream {
+    format!("compile_error!({:?});", message).parse().unwrap()
+}
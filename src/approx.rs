@@ -0,0 +1,108 @@
+use super::*;
+
+#[doc = "Family identified by the expectation of its sufficient statistics"]
+pub trait Moments<X>: Sized {
+    fn statistics(x: &X) -> Vec<f64>;
+    fn from_moments(eta: &[f64]) -> Self;
+}
+
+impl<F: family::ExponentialFamily> Moments<F::Domain> for F {
+    fn statistics(x: &F::Domain) -> Vec<f64> {
+        F::statistics(x)
+    }
+    fn from_moments(eta: &[f64]) -> Self {
+        F::from_expectation(eta)
+    }
+}
+
+#[doc = "Multivariate gaussian with dense covariance"]
+#[derive(Clone, Debug)]
+pub struct Gaussian {
+    pub mean: nd::Array1<f64>,
+    pub cov: nd::Array2<f64>,
+}
+impl Gaussian {
+    #[allow(unused)]
+    pub fn new(mean: nd::Array1<f64>, cov: nd::Array2<f64>) -> Self {
+        assert_eq!(cov.dim(), (mean.len(), mean.len()), "covariance mismatch");
+        Gaussian { mean, cov }
+    }
+
+    pub fn pdf<D: num::ToPrimitive>(&self) -> impl Fn(&nd::Array1<D>) -> f64 {
+        let n = self.mean.len();
+        let inv = ns::ToNalgebra::into_nalgebra(self.cov.clone())
+            .try_inverse()
+            .expect("covariance isn't invertible");
+        let precision = nd::Array2::from_shape_fn((n, n), |(i, j)| inv[(i, j)]);
+        let mean = self.mean.clone();
+        move |xs| {
+            let xs = xs.map(|x| x.to_f64().unwrap()) - &mean;
+            (-xs.dot(&precision.dot(&xs)) / 2.0).exp()
+        }
+    }
+}
+impl<D: num::ToPrimitive> Moments<nd::Array1<D>> for Gaussian {
+    fn statistics(xs: &nd::Array1<D>) -> Vec<f64> {
+        let xs: Vec<f64> = xs.iter().map(|x| x.to_f64().unwrap()).collect();
+        let second: Vec<f64> = (xs.iter())
+            .flat_map(|x| xs.iter().map(move |y| x * y))
+            .collect();
+        xs.into_iter().chain(second).collect()
+    }
+
+    fn from_moments(eta: &[f64]) -> Self {
+        let n = ((1.0 + 4.0 * eta.len() as f64).sqrt() as usize - 1) / 2;
+        assert_eq!(n + n * n, eta.len(), "moments mismatch");
+        let mean = nd::Array1::from(eta[..n].to_vec());
+        let cov =
+            nd::Array2::from_shape_fn((n, n), |(i, j)| eta[n + i * n + j] - mean[i] * mean[j]);
+        Gaussian { mean, cov }
+    }
+}
+
+#[doc = "Project weighted samples onto a family by matching moments"]
+pub fn project_weighted<X, T: Moments<X>, I: IntoIterator<Item = (X, f64)>>(samples: I) -> T {
+    let (mut sum, mut total) = (vec![], 0.0);
+    for (x, w) in samples {
+        let stats = T::statistics(&x);
+        if sum.is_empty() {
+            sum = vec![0.0; stats.len()];
+        }
+        sum.iter_mut().zip(stats).for_each(|(s, t)| *s += w * t);
+        total += w;
+    }
+    assert!(total > 0.0, "no samples");
+    let eta: Vec<f64> = sum.iter().map(|s| s / total).collect();
+    T::from_moments(&eta)
+}
+
+#[doc = "Project samples onto a family by matching moments"]
+pub fn project<X, T: Moments<X>, I: IntoIterator<Item = X>>(samples: I) -> T {
+    project_weighted(samples.into_iter().map(|x| (x, 1.0)))
+}
+
+#[doc = "Gaussian with the sample mean and covariance"]
+pub fn moment_match<D: num::ToPrimitive, I: IntoIterator<Item = nd::Array1<D>>>(
+    samples: I,
+) -> Gaussian {
+    project(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gaussian() {
+        let samples = vec![
+            nd::Array1::from(vec![1.0, 2.0]),
+            nd::Array1::from(vec![3.0, 4.0]),
+            nd::Array1::from(vec![5.0, 0.0]),
+        ];
+        let Gaussian { mean, cov } = moment_match(samples);
+        assert_eq!(mean, nd::Array1::from(vec![3.0, 2.0]));
+        assert!((cov[[0, 0]] - 8.0 / 3.0).abs() < 1e-12);
+        assert!((cov[[0, 1]] + 4.0 / 3.0).abs() < 1e-12);
+        assert!((cov[[1, 1]] - 8.0 / 3.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,386 @@
+use super::*;
+
+#[doc = "Autocorrelation up to `max_lag`, via FFT of the zero-padded chain"]
+pub fn acf(chain: &[f64], max_lag: usize) -> Vec<f64> {
+    let n = chain.len();
+    assert!(n > 1, "chain is too short");
+    let mean = chain.iter().sum::<f64>() / n as f64;
+    let mut xs = vec![(0.0, 0.0); (2 * n).next_power_of_two()];
+    (xs.iter_mut().zip(chain)).for_each(|(x, y)| x.0 = y - mean);
+    dist::fft(&mut xs, false);
+    xs.iter_mut()
+        .for_each(|x| *x = (x.0 * x.0 + x.1 * x.1, 0.0));
+    dist::fft(&mut xs, true);
+    let var = xs[0].0;
+    (xs.iter().take(max_lag.min(n - 1) + 1))
+        .map(|x| if var > 0.0 { x.0 / var } else { 0.0 })
+        .collect()
+}
+
+#[doc = "Effective sample size by Geyer's initial monotone sequence"]
+pub fn ess(chain: &[f64]) -> f64 {
+    let n = chain.len();
+    assert!(n > 3, "chain is too short");
+    let rho = acf(chain, n - 1);
+    if rho[0] == 0.0 {
+        return n as f64;
+    }
+
+    // positive pair sums ρ(2k) + ρ(2k+1), forced to be non-increasing
+    let (mut sum, mut last) = (0.0, f64::INFINITY);
+    for pair in rho.chunks_exact(2).map(|p| p[0] + p[1]) {
+        if pair <= 0.0 {
+            break;
+        }
+        last = pair.min(last);
+        sum += last;
+    }
+    n as f64 / (2.0 * sum - 1.0).max(1.0 / (n as f64).log10())
+}
+
+#[doc = "Integrated autocorrelation time, summing the autocorrelation over Sokal's adaptive window"]
+pub fn iat(chain: &[f64]) -> f64 {
+    let n = chain.len();
+    assert!(n > 1, "chain is too short");
+    let rho = acf(chain, n - 1);
+    let mut tau = 1.0;
+    for (m, r) in rho.iter().enumerate().skip(1) {
+        tau += 2.0 * r;
+        if m as f64 >= 5.0 * tau {
+            break;
+        }
+    }
+    tau
+}
+
+#[doc = "Split potential scale reduction, near 1 once chains have mixed"]
+pub fn rhat(chains: &[Vec<f64>]) -> f64 {
+    let halves: Vec<&[f64]> = (chains.iter())
+        .flat_map(|chain| {
+            let n = chain.len() / 2;
+            [&chain[..n], &chain[chain.len() - n..]]
+        })
+        .collect();
+    let (m, n) = (halves.len() as f64, halves[0].len() as f64);
+    assert!(n > 1.0, "chains are too short");
+    assert!(
+        halves.iter().all(|h| h.len() == halves[0].len()),
+        "chains mismatch"
+    );
+    let means: Vec<f64> = halves.iter().map(|h| h.iter().sum::<f64>() / n).collect();
+    let mean = means.iter().sum::<f64>() / m;
+    let between = n / (m - 1.0) * means.iter().map(|x| (x - mean).powi(2)).sum::<f64>();
+    let within = (halves.iter().zip(means.iter()))
+        .map(|(h, x)| h.iter().map(|y| (y - x).powi(2)).sum::<f64>() / (n - 1.0))
+        .sum::<f64>()
+        / m;
+    ((n - 1.0) / n + between / (within * n)).sqrt()
+}
+
+#[doc = "Rank-normalized split potential scale reduction, the worse of bulk and tail (Vehtari et al.)"]
+pub fn rank_rhat(chains: &[Vec<f64>]) -> f64 {
+    let mut pooled: Vec<f64> = chains.iter().flatten().cloned().collect();
+    pooled.sort_by(|a, b| a.partial_cmp(b).expect("draw is nan"));
+    let median = pooled[pooled.len() / 2];
+    let folded: Vec<Vec<f64>> = (chains.iter())
+        .map(|chain| chain.iter().map(|x| (x - median).abs()).collect())
+        .collect();
+    let bulk = rhat(&normalize(chains));
+    let tail = rhat(&normalize(&folded));
+    bulk.max(tail)
+}
+
+#[doc = "Outcome of the Heidelberger-Welch stationarity and half-width tests"]
+#[derive(Clone, Debug)]
+pub struct HeidelbergerWelch {
+    pub stationary: bool,
+    pub start: usize,
+    pub statistic: f64,
+    pub mean: f64,
+    pub halfwidth: f64,
+    pub accurate: bool,
+}
+
+#[doc = "Cramér-von Mises test discarding up to half the chain, then the relative half-width of the mean"]
+pub fn heidelberger_welch(chain: &[f64]) -> HeidelbergerWelch {
+    let n = chain.len();
+    assert!(n > 20, "chain is too short");
+    // spectral density at zero, from the latter half
+    let tail = &chain[n / 2..];
+    let spectrum = variance(tail) * tail.len() as f64 / ess(tail);
+
+    let mut outcome = None;
+    for start in (0..=5).map(|k| k * n / 10) {
+        let ys = &chain[start..];
+        let m = ys.len() as f64;
+        let mean = ys.iter().sum::<f64>() / m;
+        let statistic = (ys.iter())
+            .scan(0.0, |sum, y| {
+                *sum += y - mean;
+                Some(*sum * *sum)
+            })
+            .sum::<f64>()
+            / (m * m * spectrum);
+        outcome = Some((start, statistic));
+        if statistic < 0.461 {
+            break;
+        }
+    }
+    let (start, statistic) = outcome.unwrap();
+
+    let ys = &chain[start..];
+    let mean = ys.iter().sum::<f64>() / ys.len() as f64;
+    let halfwidth = 1.96 * (variance(ys) / ess(ys)).sqrt();
+    HeidelbergerWelch {
+        stationary: statistic < 0.461,
+        start,
+        statistic,
+        mean,
+        halfwidth,
+        accurate: halfwidth < 0.1 * mean.abs(),
+    }
+}
+
+fn variance(chain: &[f64]) -> f64 {
+    let n = chain.len() as f64;
+    let mean = chain.iter().sum::<f64>() / n;
+    chain.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+}
+
+#[doc = "Posterior summary of one coordinate"]
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    pub mean: f64,
+    pub sd: f64,
+    pub mcse: f64,
+    pub q5: f64,
+    pub median: f64,
+    pub q95: f64,
+    pub ess: f64,
+    pub rhat: f64,
+}
+
+#[doc = "Posterior summary of every coordinate across chains"]
+#[derive(Clone, Debug)]
+pub struct Summary {
+    pub parameters: Vec<Parameter>,
+}
+impl Summary {
+    pub fn from_chains<D: Coordinates>(chains: &[Vec<D>]) -> Self {
+        assert!(!chains.is_empty(), "no chains");
+        let draws: Vec<Vec<Vec<f64>>> = (chains.iter())
+            .map(|chain| chain.iter().map(D::coordinates).collect())
+            .collect();
+        let d = draws[0].first().expect("chain is empty").len();
+        let parameters = (0..d)
+            .map(|k| {
+                let chains: Vec<Vec<f64>> = (draws.iter())
+                    .map(|chain| chain.iter().map(|x| x[k]).collect())
+                    .collect();
+                let mut pooled: Vec<f64> = chains.iter().flatten().cloned().collect();
+                pooled.sort_by(|a, b| a.partial_cmp(b).expect("draw is nan"));
+                let n = pooled.len();
+                let quantile = |p: f64| pooled[((p * n as f64) as usize).min(n - 1)];
+                let (mean, sd) = (
+                    pooled.iter().sum::<f64>() / n as f64,
+                    variance(&pooled).sqrt(),
+                );
+                let ess = chains.iter().map(|chain| ess(chain)).sum::<f64>();
+                Parameter {
+                    mean,
+                    sd,
+                    mcse: sd / ess.sqrt(),
+                    q5: quantile(0.05),
+                    median: quantile(0.5),
+                    q95: quantile(0.95),
+                    ess,
+                    rhat: rank_rhat(&chains),
+                }
+            })
+            .collect();
+        Summary { parameters }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>8} {:>6}",
+            "", "mean", "sd", "mcse", "5%", "50%", "95%", "ess", "rhat"
+        )?;
+        for (k, p) in self.parameters.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>8} {:>10.4} {:>10.4} {:>10.4} {:>10.4} {:>10.4} {:>10.4} {:>8.0} {:>6.3}",
+                format!("x[{}]", k),
+                p.mean,
+                p.sd,
+                p.mcse,
+                p.q5,
+                p.median,
+                p.q95,
+                p.ess,
+                p.rhat
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[doc = "Posterior predictive p-value, the share of replicated data whose `statistic` reaches the observed one"]
+pub fn ppc<P, Y, S: FnMut(&P) -> Y, T: Fn(&Y) -> f64>(
+    posterior: &[P],
+    observed: &Y,
+    mut simulate: S,
+    statistic: T,
+) -> f64 {
+    assert!(!posterior.is_empty(), "no posterior samples");
+    let t = statistic(observed);
+    let extreme = (posterior.iter())
+        .filter(|theta| statistic(&simulate(theta)) >= t)
+        .count();
+    extreme as f64 / posterior.len() as f64
+}
+
+// normal scores of the pooled ranks, averaging ties
+fn normalize(chains: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let mut pooled: Vec<f64> = chains.iter().flatten().cloned().collect();
+    pooled.sort_by(|a, b| a.partial_cmp(b).expect("draw is nan"));
+    let s = pooled.len() as f64;
+    let rank = |x: &f64| {
+        let lower = pooled.partition_point(|y| y < x) as f64;
+        let upper = pooled.partition_point(|y| y <= x) as f64;
+        (lower + upper + 1.0) / 2.0
+    };
+    (chains.iter())
+        .map(|chain| {
+            (chain.iter())
+                .map(|x| dist::probit((rank(x) - 0.375) / (s + 0.25)))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn geyer() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let iid: Vec<f64> = (0..5000).map(|_| dist::normal(&mut gen)).collect();
+        assert!((ess(&iid) / 5000.0 - 1.0).abs() < 0.15);
+        let mut x = 0.0;
+        let ar: Vec<f64> = (0..20000)
+            .map(|_| {
+                x = 0.5 * x + dist::normal(&mut gen);
+                x
+            })
+            .collect();
+        assert!((ess(&ar) / 20000.0 - 1.0 / 3.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn sokal() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let mut x = 0.0;
+        let ar: Vec<f64> = (0..50000)
+            .map(|_| {
+                x = 0.8 * x + dist::normal(&mut gen);
+                x
+            })
+            .collect();
+        assert!((iat(&ar) - 9.0).abs() < 1.5);
+    }
+
+    #[test]
+    fn autocorrelation() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let mut x = 0.0;
+        let ar: Vec<f64> = (0..20000)
+            .map(|_| {
+                x = 0.8 * x + dist::normal(&mut gen);
+                x
+            })
+            .collect();
+        let rho = acf(&ar, 5);
+        assert_eq!(rho.len(), 6);
+        assert!((rho[0] - 1.0).abs() < 1e-12);
+        for (lag, r) in rho.iter().enumerate() {
+            assert!((r - 0.8f64.powi(lag as i32)).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn stationarity() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let iid: Vec<f64> = (0..2000).map(|_| 5.0 + dist::normal(&mut gen)).collect();
+        let outcome = heidelberger_welch(&iid);
+        assert!(outcome.stationary && outcome.accurate);
+        assert!((outcome.mean - 5.0).abs() < outcome.halfwidth * 2.0);
+        let transient: Vec<f64> = (0..2000)
+            .map(|i| 20.0 * (-(i as f64) / 400.0).exp() + dist::normal(&mut gen))
+            .collect();
+        let outcome = heidelberger_welch(&transient);
+        assert!(!outcome.stationary || outcome.start > 0);
+    }
+
+    #[test]
+    fn summary() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let mut chain = || -> Vec<[f64; 2]> {
+            (0..1000)
+                .map(|_| [dist::normal(&mut gen), 3.0 + 2.0 * dist::normal(&mut gen)])
+                .collect()
+        };
+        let summary = Summary::from_chains(&[chain(), chain()]);
+        let [x, y] = [&summary.parameters[0], &summary.parameters[1]];
+        assert!(x.mean.abs() < 0.2 && (y.mean - 3.0).abs() < 0.4);
+        assert!((x.sd - 1.0).abs() < 0.1 && (y.sd - 2.0).abs() < 0.2);
+        assert!(x.q5 < x.median && x.median < x.q95);
+        assert!((x.mcse - x.sd / x.ess.sqrt()).abs() < 1e-12);
+        assert!((x.rhat - 1.0).abs() < 0.02);
+        assert_eq!(summary.to_string().lines().count(), 3);
+    }
+
+    #[test]
+    fn predictive() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let mut observed: Vec<f64> = (0..50).map(|_| dist::normal(&mut gen)).collect();
+        observed[0] = 10.0;
+        let ybar = observed.iter().sum::<f64>() / 50.0;
+        let posterior: Vec<f64> = (0..2000)
+            .map(|_| ybar + dist::normal(&mut gen) / 50f64.sqrt())
+            .collect();
+        let mut noise = rand::rngs::SmallRng::seed_from_u64(1);
+        let mut simulate =
+            |mu: &f64| -> Vec<f64> { (0..50).map(|_| mu + dist::normal(&mut noise)).collect() };
+        let mean = |ys: &Vec<f64>| ys.iter().sum::<f64>() / ys.len() as f64;
+        let max = |ys: &Vec<f64>| ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let p = ppc(&posterior, &observed, &mut simulate, mean);
+        assert!((0.3..0.7).contains(&p));
+        assert!(ppc(&posterior, &observed, &mut simulate, max) < 0.01);
+    }
+
+    #[test]
+    fn gelman_rubin() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let mut chain = |shift: f64| -> Vec<f64> {
+            (0..1000).map(|_| shift + dist::normal(&mut gen)).collect()
+        };
+        let mixed = [chain(0.0), chain(0.0), chain(0.0), chain(0.0)];
+        assert!((rhat(&mixed) - 1.0).abs() < 0.01);
+        assert!((rank_rhat(&mixed) - 1.0).abs() < 0.01);
+        let stuck = [chain(0.0), chain(0.0), chain(0.0), chain(2.0)];
+        assert!(rhat(&stuck) > 1.1);
+        assert!(rank_rhat(&stuck) > 1.1);
+    }
+}
@@ -1,14 +1,2126 @@
 use super::*;
 
+pub use kde::Estimate as Kde;
+
+pub(crate) fn normal<R: rand::Rng + ?Sized>(gen: &mut R) -> f64 {
+    loop {
+        let (u, v) = (gen.gen_range(-1.0..1.0), gen.gen_range(-1.0..1.0));
+        let s: f64 = u * u + v * v;
+        if s > 0.0 && s < 1.0 {
+            return u * (-2.0 * s.ln() / s).sqrt();
+        }
+    }
+}
+
+pub(crate) fn gamma<R: rand::Rng + ?Sized>(gen: &mut R, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let u: f64 = 1.0 - gen.gen_range(0.0..1.0);
+        return gamma(gen, shape + 1.0) * u.powf(1.0 / shape);
+    }
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = normal(gen);
+        let v = (1.0 + c * x).powi(3);
+        let u: f64 = gen.gen_range(0.0..1.0);
+        if v > 0.0 && u.ln() < x * x / 2.0 + d - d * v + d * v.ln() {
+            return d * v;
+        }
+    }
+}
+
+// counting for few trials, otherwise splitting at a beta order statistic (Knuth)
+pub(crate) fn binomial<R: rand::Rng + ?Sized>(gen: &mut R, n: u64, p: f64) -> u64 {
+    if n < 40 {
+        return (0..n).filter(|_| gen.gen_range(0.0..1.0) < p).count() as u64;
+    }
+    let a = 1 + n / 2;
+    let b = n + 1 - a;
+    let (x, y) = (gamma(gen, a as f64), gamma(gen, b as f64));
+    let x = x / (x + y);
+    if x >= p {
+        binomial(gen, a - 1, p / x)
+    } else {
+        a + binomial(gen, b - 1, (p - x) / (1.0 - x))
+    }
+}
+
+// complementary error function with fractional error below 1.2e-7
+pub(crate) fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + x.abs() / 2.0);
+    let poly = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ];
+    let y = t * (poly.iter().rev().fold(0.0, |acc, c| acc * t + c) - x * x).exp();
+    if x >= 0.0 {
+        y
+    } else {
+        2.0 - y
+    }
+}
+
+// standard normal quantile by Acklam's rational approximation, polished by one halley step
+pub(crate) fn probit(p: f64) -> f64 {
+    assert!(0.0 < p && p < 1.0, "probability isn't in (0, 1)");
+    let a = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    let b = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    let c = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    let d = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    let poly = |cs: &[f64], x: f64| cs.iter().fold(0.0, |acc, c| acc * x + c);
+    let tail = |q: f64| {
+        let r = (-2.0 * q.ln()).sqrt();
+        poly(&c, r) / (poly(&d, r) * r + 1.0)
+    };
+    let x = if p < 0.02425 {
+        tail(p)
+    } else if p > 1.0 - 0.02425 {
+        -tail(1.0 - p)
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        poly(&a, r) * q / (poly(&b, r) * r + 1.0)
+    };
+    let e = erfc(-x / 2f64.sqrt()) / 2.0 - p;
+    let u = e * (2.0 * std::f64::consts::PI).sqrt() * (x * x / 2.0).exp();
+    x - u / (1.0 + x * u / 2.0)
+}
+
+// log gamma function by the Lanczos approximation
+pub(crate) fn ln_gamma(x: f64) -> f64 {
+    if x < 0.5 {
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let lanczos = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let a = (1..lanczos.len()).fold(lanczos[0], |a, i| a + lanczos[i] / (x + i as f64));
+    let t = x + 7.5;
+    (2.0 * std::f64::consts::PI).sqrt().ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+// log binomial coefficient, finite far beyond where factorials overflow
+pub(crate) fn ln_choose(n: f64, k: f64) -> f64 {
+    ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0)
+}
+
+pub(crate) fn ln_beta(a: f64, b: f64) -> f64 {
+    ln_gamma(a) + ln_gamma(b) - ln_gamma(a + b)
+}
+
+// x ln y, taking 0 ln 0 as 0 so degenerate probabilities stay finite
+pub(crate) fn xlogy(x: f64, y: f64) -> f64 {
+    if x == 0.0 {
+        0.0
+    } else {
+        x * y.ln()
+    }
+}
+
+// logarithm of the modified bessel function I0, by power series or its asymptotic expansion
+pub(crate) fn ln_bessel_i0(x: f64) -> f64 {
+    let x = x.abs();
+    if x > 50.0 {
+        let (mut term, mut series) = (1.0, 1.0);
+        for k in 1..10 {
+            term *= (2.0 * k as f64 - 1.0).powi(2) / (8.0 * k as f64 * x);
+            series += term;
+        }
+        return x - (2.0 * std::f64::consts::PI * x).ln() / 2.0 + f64::ln(series);
+    }
+    let (q, mut term, mut sum) = (x * x / 4.0, 1.0, 1.0);
+    for k in 1..200 {
+        term *= q / (k * k) as f64;
+        sum += term;
+        if term < 1e-17 * sum {
+            break;
+        }
+    }
+    sum.ln()
+}
+
+// regularized incomplete beta function by Lentz's continued fraction
+pub(crate) fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 || x >= 1.0 {
+        return x.clamp(0.0, 1.0);
+    }
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - beta_inc(b, a, 1.0 - x);
+    }
+    let tiny = 1e-300;
+    let front = (a * x.ln() + b * (1.0 - x).ln() - ln_beta(a, b)).exp() / a;
+    let (mut c, mut d) = (1.0, 1.0 - (a + b) * x / (a + 1.0));
+    d = 1.0 / if d.abs() < tiny { tiny } else { d };
+    let mut f = d;
+    for i in 2..300 {
+        let m = (i / 2) as f64;
+        let num = if i % 2 == 0 {
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m))
+        } else {
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0))
+        };
+        d = 1.0 + num * d;
+        d = 1.0 / if d.abs() < tiny { tiny } else { d };
+        c = 1.0 + num / c;
+        c = if c.abs() < tiny { tiny } else { c };
+        f *= c * d;
+        if (c * d - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    front * f
+}
+
+// regularized lower incomplete gamma function, by series below `a + 1` and continued fraction above
+pub(crate) fn gamma_inc(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    let front = (a * x.ln() - x - ln_gamma(a)).exp();
+    if x < a + 1.0 {
+        let (mut term, mut sum) = (1.0 / a, 1.0 / a);
+        for n in 1..500 {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term.abs() < sum.abs() * 1e-15 {
+                break;
+            }
+        }
+        return front * sum;
+    }
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let (mut c, mut d) = (1.0 / tiny, 1.0 / b);
+    let mut f = d;
+    for i in 1..500 {
+        let num = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = num * d + b;
+        d = 1.0 / if d.abs() < tiny { tiny } else { d };
+        c = b + num / c;
+        c = if c.abs() < tiny { tiny } else { c };
+        f *= c * d;
+        if (c * d - 1.0).abs() < 1e-15 {
+            break;
+        }
+    }
+    1.0 - front * f
+}
+
+// digamma function by recurrence and asymptotic expansion
+pub(crate) fn digamma(mut x: f64) -> f64 {
+    let mut r = 0.0;
+    while x < 6.0 {
+        r -= 1.0 / x;
+        x += 1.0;
+    }
+    let f = 1.0 / (x * x);
+    r + x.ln() - 0.5 / x - f * (1.0 / 12.0 - f * (1.0 / 120.0 - f / 252.0))
+}
+
+// trigamma function by recurrence and asymptotic expansion
+pub(crate) fn trigamma(mut x: f64) -> f64 {
+    let mut r = 0.0;
+    while x < 6.0 {
+        r += 1.0 / (x * x);
+        x += 1.0;
+    }
+    let f = 1.0 / (x * x);
+    r + 1.0 / x + f / 2.0 + f / x * (1.0 / 6.0 - f * (1.0 / 30.0 - f * (1.0 / 42.0 - f / 30.0)))
+}
+
+#[doc = "Probability distribution over `D`, normalized unlike the bare pdf closures"]
+pub trait Distribution<D> {
+    fn log_pdf(&self, x: &D) -> f64;
+
+    fn pdf(&self, x: &D) -> f64 {
+        self.log_pdf(x).exp()
+    }
+
+    fn cdf(&self, _: &D) -> Option<f64> {
+        None
+    }
+
+    fn quantile(&self, _: f64) -> Option<D> {
+        None
+    }
+
+    fn sample<R: rand::Rng + ?Sized>(&self, _: &mut R) -> Option<D> {
+        None
+    }
+
+    #[doc = "Endless i.i.d. draws"]
+    fn sample_iter<'a, R: rand::Rng + ?Sized>(&'a self, gen: &'a mut R) -> Draws<'a, Self, D, R>
+    where
+        Self: Sized,
+    {
+        Draws {
+            dist: self,
+            gen,
+            pd: std::marker::PhantomData,
+        }
+    }
+
+    #[doc = "Array of i.i.d. draws"]
+    fn sample_array<S: nd::ShapeBuilder, R: rand::Rng + ?Sized>(
+        &self,
+        shape: S,
+        gen: &mut R,
+    ) -> nd::Array<D, S::Dim> {
+        nd::Array::from_shape_fn(shape, |_| {
+            self.sample(gen).expect("distribution can't sample")
+        })
+    }
+
+    #[doc = "Matrix with one i.i.d. vector draw per row"]
+    fn sample_matrix<R: rand::Rng + ?Sized>(&self, n: usize, gen: &mut R) -> na::DMatrix<f64>
+    where
+        D: IntoIterator<Item = f64>,
+    {
+        let rows: Vec<Vec<f64>> = (0..n)
+            .map(|_| {
+                let x = self.sample(gen).expect("distribution can't sample");
+                x.into_iter().collect()
+            })
+            .collect();
+        let d = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|x| x.len() == d), "draws mismatch");
+        na::DMatrix::from_fn(n, d, |i, j| rows[i][j])
+    }
+}
+
+#[doc = "Iterator of i.i.d. draws from a distribution"]
+pub struct Draws<'a, T, D, R: ?Sized> {
+    dist: &'a T,
+    gen: &'a mut R,
+    pd: std::marker::PhantomData<D>,
+}
+impl<'a, T: Distribution<D>, D, R: rand::Rng + ?Sized> Iterator for Draws<'a, T, D, R> {
+    type Item = D;
+    fn next(&mut self) -> Option<D> {
+        self.dist.sample(self.gen)
+    }
+}
+
+#[doc = "Density closure of a distribution, as sampler input"]
+pub fn density<D, T: Distribution<D>>(dist: T) -> impl Fn(&D) -> f64 {
+    move |x| dist.pdf(x)
+}
+
+#[doc = "Density closure of a distribution on the given scale"]
+pub fn scaled_density<L: scale::Scale, D, T: Distribution<D>>(dist: T) -> impl Fn(&D) -> f64 {
+    move |x| L::from_ln(dist.log_pdf(x))
+}
+
+#[doc = "Log-density of a distribution, as input to samplers on `scale::Log`"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Log<T>(pub T);
+impl<T> Log<T> {
+    pub fn density<D>(self) -> impl Fn(&D) -> f64
+    where
+        T: Distribution<D>,
+    {
+        scaled_density::<scale::Log, D, T>(self.0)
+    }
+}
+
+#[doc = "Improper flat density over any domain"]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Flat;
+impl<D> Distribution<D> for Flat {
+    fn log_pdf(&self, _: &D) -> f64 {
+        0.0
+    }
+}
+
+#[doc = "Joint density restricted to the states satisfying `predicate`"]
+pub fn condition<D, F: Fn(&D) -> f64, P: Fn(&D) -> bool>(
+    joint: F,
+    predicate: P,
+) -> impl Fn(&D) -> f64 {
+    scaled_condition::<scale::Linear, D, F, P>(joint, predicate)
+}
+
+#[doc = "Joint density on the given scale restricted to the states satisfying `predicate`"]
+pub fn scaled_condition<L: scale::Scale, D, F: Fn(&D) -> f64, P: Fn(&D) -> bool>(
+    joint: F,
+    predicate: P,
+) -> impl Fn(&D) -> f64 {
+    move |x| if predicate(x) { joint(x) } else { L::zero() }
+}
+
+#[doc = "Joint density over the free coordinates, with `fixed` `(index, value)` coordinates held"]
+pub fn slice_condition<D: Clone, F: Fn(&nd::Array1<D>) -> f64>(
+    joint: F,
+    fixed: &[(usize, D)],
+) -> impl Fn(&nd::Array1<D>) -> f64 {
+    let mut fixed = fixed.to_vec();
+    fixed.sort_by_key(|(i, _)| *i);
+    assert!(
+        fixed.windows(2).all(|w| w[0].0 < w[1].0),
+        "coordinate is fixed twice"
+    );
+    move |free| joint(&insert(free, &fixed))
+}
+
+// full state from the free coordinates and sorted `(index, value)` pairs
+fn insert<D: Clone>(free: &nd::Array1<D>, fixed: &[(usize, D)]) -> nd::Array1<D> {
+    let (mut free, mut fixed) = (free.iter(), fixed.iter().peekable());
+    (0..free.len() + fixed.len())
+        .map(|i| match fixed.next_if(|(j, _)| *j == i) {
+            Some((_, x)) => x.clone(),
+            None => free.next().unwrap().clone(),
+        })
+        .collect()
+}
+
+#[doc = "Marginal density summing the joint over every value of the coordinates `axes`"]
+pub fn marginalize<D: Discrete, F: Fn(&nd::Array1<D>) -> f64>(
+    joint: F,
+    axes: &[usize],
+) -> impl Fn(&nd::Array1<D>) -> f64 {
+    integrate(joint, axes, D::iter().map(|x| (x, 1.0)).collect())
+}
+
+#[doc = "Marginal density integrating the joint over `axes` within `[lower, upper]`, by Simpson's rule on `n` intervals"]
+pub fn marginalize_quadrature<F: Fn(&nd::Array1<f64>) -> f64>(
+    joint: F,
+    axes: &[usize],
+    lower: f64,
+    upper: f64,
+    n: usize,
+) -> impl Fn(&nd::Array1<f64>) -> f64 {
+    assert!(lower < upper, "bounds are empty");
+    assert!(n > 0 && n % 2 == 0, "intervals aren't even");
+    let h = (upper - lower) / n as f64;
+    let nodes = (0..=n)
+        .map(|i| {
+            let simpson = if i == 0 || i == n {
+                1.0
+            } else {
+                2.0 + 2.0 * (i % 2) as f64
+            };
+            (lower + i as f64 * h, simpson * h / 3.0)
+        })
+        .collect();
+    integrate(joint, axes, nodes)
+}
+
+// weighted sum of the joint over the tensor grid of `nodes` along `axes`
+fn integrate<D: Clone, F: Fn(&nd::Array1<D>) -> f64>(
+    joint: F,
+    axes: &[usize],
+    nodes: Vec<(D, f64)>,
+) -> impl Fn(&nd::Array1<D>) -> f64 {
+    let mut axes = axes.to_vec();
+    axes.sort_unstable();
+    axes.dedup();
+    move |free| {
+        let (m, k) = (nodes.len(), axes.len());
+        (0..m.pow(k as u32))
+            .map(|mut c| {
+                let mut weight = 1.0;
+                let fixed: Vec<(usize, D)> = (axes.iter())
+                    .map(|axis| {
+                        let (x, w) = &nodes[c % m];
+                        c /= m;
+                        weight *= w;
+                        (*axis, x.clone())
+                    })
+                    .collect();
+                weight * joint(&insert(free, &fixed))
+            })
+            .sum()
+    }
+}
+
+#[doc = "Distribution of the sum of independent variables on `Z<N>` over `Z<M>`, by FFT"]
+pub fn convolve<const N: usize, const M: usize, A, B>(a: &A, b: &B) -> univar::Categorical<M>
+where
+    A: Distribution<modular::Z<N>>,
+    B: Distribution<modular::Z<N>>,
+{
+    let len = (2 * N - 1).next_power_of_two();
+    let spectrum = |d: &dyn Fn(&modular::Z<N>) -> f64| {
+        let mut xs = vec![(0.0, 0.0); len];
+        (0..N).for_each(|k| xs[k].0 = d(&modular::Z(k)));
+        fft(&mut xs, false);
+        xs
+    };
+    let (xs, ys) = (spectrum(&|x| a.pdf(x)), spectrum(&|x| b.pdf(x)));
+    let mut zs: Vec<(f64, f64)> = (xs.iter().zip(ys.iter()))
+        .map(|((a, b), (c, d))| (a * c - b * d, a * d + b * c))
+        .collect();
+    fft(&mut zs, true);
+    let weights: Vec<f64> = zs.iter().map(|z| z.0.max(0.0)).collect();
+    let (kept, rest) = weights[..2 * N - 1].split_at((2 * N - 1).min(M));
+    let total: f64 = weights.iter().sum();
+    assert!(
+        rest.iter().sum::<f64>() <= 1e-9 * total,
+        "sum exceeds the support"
+    );
+    let mut kept = kept.to_vec();
+    kept.resize(M, 0.0);
+    univar::categorical::<M>(&kept)
+}
+
+#[doc = "Empirical distribution of the sum of independent variables, from `n` draws"]
+pub fn convolve_sampled<D, A, B>(a: &A, b: &B, n: usize) -> Empirical<D>
+where
+    D: Clone + std::ops::Add<Output = D>,
+    A: Distribution<D>,
+    B: Distribution<D>,
+{
+    convolve_sampled_with_rng(a, b, n, &mut rand::thread_rng())
+}
+
+#[doc = "Empirical distribution of the sum of independent variables, from `n` draws of `gen`"]
+pub fn convolve_sampled_with_rng<D, A, B, R>(a: &A, b: &B, n: usize, gen: &mut R) -> Empirical<D>
+where
+    D: Clone + std::ops::Add<Output = D>,
+    A: Distribution<D>,
+    B: Distribution<D>,
+    R: rand::Rng + ?Sized,
+{
+    let samples: Vec<D> = (0..n)
+        .map(|_| {
+            let x = a.sample(gen).expect("distribution can't sample");
+            x + b.sample(gen).expect("distribution can't sample")
+        })
+        .collect();
+    empirical(&samples)
+}
+
+// in-place radix-2 cooley-tukey on (re, im) pairs, normalized when inverse
+pub(crate) fn fft(xs: &mut [(f64, f64)], inverse: bool) {
+    let n = xs.len();
+    assert!(n.is_power_of_two(), "length isn't a power of two");
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            xs.swap(i, j);
+        }
+    }
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut width = 2;
+    while width <= n {
+        let angle = sign * 2.0 * std::f64::consts::PI / width as f64;
+        for start in (0..n).step_by(width) {
+            for k in 0..width / 2 {
+                let (s, c) = (angle * k as f64).sin_cos();
+                let (a, b) = (xs[start + k], xs[start + k + width / 2]);
+                let t = (b.0 * c - b.1 * s, b.0 * s + b.1 * c);
+                xs[start + k] = (a.0 + t.0, a.1 + t.1);
+                xs[start + k + width / 2] = (a.0 - t.0, a.1 - t.1);
+            }
+        }
+        width <<= 1;
+    }
+    if inverse {
+        xs.iter_mut()
+            .for_each(|x| *x = (x.0 / n as f64, x.1 / n as f64));
+    }
+}
+
+#[doc = "Exact sampler of a distribution that can draw directly"]
+pub fn exact<D: na::Scalar, T: Distribution<D>>(
+    dist: T,
+) -> sampler::univar::Exact<D, impl Fn(&mut dyn rand::RngCore) -> D> {
+    sampler::univar::Exact::new(move |gen: &mut dyn rand::RngCore| {
+        dist.sample(gen).expect("distribution can't sample")
+    })
+}
+
+#[doc = "Exact sampler of a `rand` distribution, such as those of `rand_distr`"]
+pub fn from_rand<D: na::Scalar, T: rand::distributions::Distribution<D>>(
+    dist: T,
+) -> sampler::univar::Exact<D, impl Fn(&mut dyn rand::RngCore) -> D> {
+    sampler::univar::Exact::new(move |gen: &mut dyn rand::RngCore| dist.sample(gen))
+}
+
+#[doc = "Weighted mixture of distributions"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mixture<T> {
+    pub weights: Vec<f64>,
+    pub components: Vec<T>,
+}
+impl<D, T: Distribution<D>> Distribution<D> for Mixture<T> {
+    fn log_pdf(&self, x: &D) -> f64 {
+        let logs: Vec<f64> = (self.weights.iter())
+            .zip(self.components.iter())
+            .map(|(w, c)| w.ln() + c.log_pdf(x))
+            .collect();
+        let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if max == f64::NEG_INFINITY {
+            return max;
+        }
+        max + logs.iter().map(|l| (l - max).exp()).sum::<f64>().ln()
+    }
+    fn cdf(&self, x: &D) -> Option<f64> {
+        (self.weights.iter())
+            .zip(self.components.iter())
+            .map(|(w, c)| c.cdf(x).map(|p| w * p))
+            .sum()
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+        let mut u = gen.gen_range(0.0..1.0);
+        let k = (self.weights.iter())
+            .position(|w| {
+                u -= w;
+                u < 0.0
+            })
+            .unwrap_or(self.weights.len() - 1);
+        self.components[k].sample(gen)
+    }
+}
+
+#[doc = "Mixture of `components`, drawing component `k` with probability proportional to `weights[k]`"]
+pub fn mixture<T>(weights: &[f64], components: Vec<T>) -> Mixture<T> {
+    assert_eq!(weights.len(), components.len(), "weights mismatch");
+    assert!(weights.iter().all(|w| *w >= 0.0), "weight is negative");
+    let total: f64 = weights.iter().sum();
+    assert!(total > 0.0, "total weight isn't positive");
+    Mixture {
+        weights: weights.iter().map(|w| w / total).collect(),
+        components,
+    }
+}
+
+#[doc = "Joint of independent marginals, over tuples or arrays"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Product<T>(pub T);
+
+macro_rules! impl_product {
+    [$(($T: ident, $D: ident, $i: tt)), *] => {
+        impl<$($D, $T: Distribution<$D>), *> Distribution<($($D,)*)> for Product<($($T,)*)> {
+            fn log_pdf(&self, x: &($($D,)*)) -> f64 {
+                0.0 $(+ self.0.$i.log_pdf(&x.$i))*
+            }
+            fn cdf(&self, x: &($($D,)*)) -> Option<f64> {
+                Some(1.0 $(* self.0.$i.cdf(&x.$i)?)*)
+            }
+            fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<($($D,)*)> {
+                Some(($(self.0.$i.sample(gen)?,)*))
+            }
+        }
+    };
+}
+impl_product![(A, X, 0), (B, Y, 1)];
+impl_product![(A, X, 0), (B, Y, 1), (C, Z, 2)];
+impl_product![(A, X, 0), (B, Y, 1), (C, Z, 2), (E, W, 3)];
+
+impl<T: Distribution<f64>> Distribution<nd::Array1<f64>> for Product<Vec<T>> {
+    fn log_pdf(&self, xs: &nd::Array1<f64>) -> f64 {
+        assert_eq!(xs.len(), self.0.len(), "dimension mismatch");
+        self.0
+            .iter()
+            .zip(xs.iter())
+            .map(|(d, x)| d.log_pdf(x))
+            .sum()
+    }
+    fn cdf(&self, xs: &nd::Array1<f64>) -> Option<f64> {
+        self.0
+            .iter()
+            .zip(xs.iter())
+            .map(|(d, x)| d.cdf(x))
+            .product()
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array1<f64>> {
+        self.0.iter().map(|d| d.sample(gen)).collect()
+    }
+}
+
+#[doc = "Independent joint of a tuple of marginals, or of a vector of them over arrays"]
+pub fn product<T>(marginals: T) -> Product<T> {
+    Product(marginals)
+}
+
+#[doc = "Empirical distribution of observed samples"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Empirical<D> {
+    pub samples: Vec<D>,
+    pub alpha: f64,
+    support: Vec<D>,
+}
+impl<D: Discrete> Empirical<D> {
+    #[doc = "Add `alpha` pseudo-counts to every point of the domain"]
+    pub fn smoothing(self, alpha: f64) -> Self {
+        assert!(alpha >= 0.0, "alpha is negative");
+        Empirical {
+            alpha,
+            support: D::iter().collect(),
+            ..self
+        }
+    }
+}
+impl<D: PartialOrd + Clone> Distribution<D> for Empirical<D> {
+    fn log_pdf(&self, x: &D) -> f64 {
+        let count = self.samples.iter().filter(|y| *y == x).count() as f64;
+        let total = self.samples.len() as f64 + self.alpha * self.support.len() as f64;
+        let prior = if self.support.contains(x) {
+            self.alpha
+        } else {
+            0.0
+        };
+        ((count + prior) / total).ln()
+    }
+    fn cdf(&self, x: &D) -> Option<f64> {
+        let below = |xs: &[D]| xs.iter().filter(|y| *y <= x).count() as f64;
+        let total = self.samples.len() as f64 + self.alpha * self.support.len() as f64;
+        Some((below(&self.samples) + self.alpha * below(&self.support)) / total)
+    }
+    // bootstrap resampling, falling back on the pseudo-counts
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+        let n = self.samples.len() as f64;
+        let u = gen.gen_range(0.0..n + self.alpha * self.support.len() as f64);
+        if u < n {
+            Some(self.samples[u as usize].clone())
+        } else {
+            let k = ((u - n) / self.alpha) as usize;
+            Some(self.support[k.min(self.support.len() - 1)].clone())
+        }
+    }
+}
+
+#[doc = "Empirical distribution of `samples`, e.g. a sampler's output"]
+pub fn empirical<D: Clone>(samples: &[D]) -> Empirical<D> {
+    assert!(!samples.is_empty(), "no samples");
+    Empirical {
+        samples: samples.to_vec(),
+        alpha: 0.0,
+        support: vec![],
+    }
+}
+
+impl Distribution<f64> for family::Gaussian {
+    fn log_pdf(&self, x: &f64) -> f64 {
+        family::ExponentialFamily::log_pdf(self, x)
+    }
+    fn cdf(&self, x: &f64) -> Option<f64> {
+        Some(erfc(-(x - self.mu) / (self.sigma * 2f64.sqrt())) / 2.0)
+    }
+    fn quantile(&self, p: f64) -> Option<f64> {
+        Some(self.mu + self.sigma * probit(p))
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+        Some(family::ExponentialFamily::sample(self, gen))
+    }
+}
+
+impl Distribution<f64> for family::Exponential {
+    fn log_pdf(&self, x: &f64) -> f64 {
+        if *x < 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        family::ExponentialFamily::log_pdf(self, x)
+    }
+    fn cdf(&self, x: &f64) -> Option<f64> {
+        Some(1.0 - (-self.rate * x.max(0.0)).exp())
+    }
+    fn quantile(&self, p: f64) -> Option<f64> {
+        Some(-(1.0 - p).ln() / self.rate)
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+        self.quantile(gen.gen_range(0.0..1.0))
+    }
+}
+
+impl Distribution<bool> for family::Bernoulli {
+    fn log_pdf(&self, x: &bool) -> f64 {
+        if *x { self.p } else { 1.0 - self.p }.ln()
+    }
+    fn cdf(&self, x: &bool) -> Option<f64> {
+        Some(if *x { 1.0 } else { 1.0 - self.p })
+    }
+    fn quantile(&self, p: f64) -> Option<bool> {
+        Some(p > 1.0 - self.p)
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<bool> {
+        Some(family::ExponentialFamily::sample(self, gen))
+    }
+}
+
+impl<D: num::ToPrimitive + num::FromPrimitive> Distribution<D> for family::Poisson {
+    fn log_pdf(&self, x: &D) -> f64 {
+        match x.to_usize() {
+            Some(k) => family::ExponentialFamily::log_pdf(self, &k),
+            None => f64::NEG_INFINITY,
+        }
+    }
+    fn cdf(&self, x: &D) -> Option<f64> {
+        let x = x.to_f64()?;
+        if x < 0.0 {
+            return Some(0.0);
+        }
+        Some(1.0 - gamma_inc(x.floor() + 1.0, self.lambda))
+    }
+    fn quantile(&self, p: f64) -> Option<D> {
+        let (mut k, mut pmf) = (0, (-self.lambda).exp());
+        let mut cdf = pmf;
+        while cdf < p && (pmf > 0.0 || k as f64 <= self.lambda) {
+            k += 1;
+            pmf *= self.lambda / k as f64;
+            cdf += pmf;
+        }
+        D::from_u64(k)
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+        D::from_usize(family::ExponentialFamily::sample(self, gen))
+    }
+}
+
+impl Distribution<f64> for family::Gamma {
+    fn log_pdf(&self, x: &f64) -> f64 {
+        if *x <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        family::ExponentialFamily::log_pdf(self, x)
+    }
+    fn cdf(&self, x: &f64) -> Option<f64> {
+        Some(gamma_inc(self.shape, self.rate * x.max(0.0)))
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+        Some(family::ExponentialFamily::sample(self, gen))
+    }
+}
+
+#[doc = "Kernel density estimation over univariate samples"]
+pub mod kde {
+    use super::*;
+
+    #[doc = "Smoothing kernel, standardized to unit variance"]
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Kernel {
+        Gaussian,
+        Epanechnikov,
+    }
+    impl Kernel {
+        fn pdf(&self, u: f64) -> f64 {
+            match self {
+                Kernel::Gaussian => (-u * u / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt(),
+                Kernel::Epanechnikov => {
+                    let v = u / 5f64.sqrt();
+                    0.75 * (1.0 - v * v).max(0.0) / 5f64.sqrt()
+                }
+            }
+        }
+        fn cdf(&self, u: f64) -> f64 {
+            match self {
+                Kernel::Gaussian => erfc(-u / 2f64.sqrt()) / 2.0,
+                Kernel::Epanechnikov => {
+                    let v = (u / 5f64.sqrt()).clamp(-1.0, 1.0);
+                    0.5 + 0.75 * (v - v * v * v / 3.0)
+                }
+            }
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> f64 {
+            match self {
+                Kernel::Gaussian => normal(gen),
+                Kernel::Epanechnikov => {
+                    // Devroye's median of three uniforms
+                    let [u1, u2, u3]: [f64; 3] = [(); 3].map(|_| gen.gen_range(-1.0..1.0));
+                    let v = if u3.abs() >= u2.abs() && u3.abs() >= u1.abs() {
+                        u2
+                    } else {
+                        u3
+                    };
+                    v * 5f64.sqrt()
+                }
+            }
+        }
+    }
+
+    #[doc = "Kernel density estimate, with Silverman's bandwidth by default"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Estimate {
+        pub samples: Vec<f64>,
+        pub bandwidth: f64,
+        pub kernel: Kernel,
+    }
+    impl Estimate {
+        #[allow(unused)]
+        pub fn new(samples: &[f64]) -> Self {
+            assert!(samples.len() > 1, "too few samples");
+            let n = samples.len() as f64;
+            let mean = samples.iter().sum::<f64>() / n;
+            let sd = (samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt();
+            let mut sorted = samples.to_vec();
+            sorted.sort_by(|a, b| a.partial_cmp(b).expect("sample is nan"));
+            let quartile = |q: f64| sorted[((n - 1.0) * q).round() as usize];
+            let iqr = (quartile(0.75) - quartile(0.25)) / 1.34;
+            let spread = if iqr > 0.0 { sd.min(iqr) } else { sd };
+            assert!(spread > 0.0, "samples are constant");
+            Estimate {
+                samples: samples.to_vec(),
+                bandwidth: 0.9 * spread * n.powf(-0.2),
+                kernel: Kernel::Gaussian,
+            }
+        }
+
+        pub fn bandwidth(self, bandwidth: f64) -> Self {
+            assert!(bandwidth > 0.0, "bandwidth isn't positive");
+            Estimate { bandwidth, ..self }
+        }
+
+        pub fn kernel(self, kernel: Kernel) -> Self {
+            Estimate { kernel, ..self }
+        }
+    }
+    impl Distribution<f64> for Estimate {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            self.pdf(x).ln()
+        }
+        fn pdf(&self, x: &f64) -> f64 {
+            let h = self.bandwidth;
+            let sum: f64 = self
+                .samples
+                .iter()
+                .map(|y| self.kernel.pdf((x - y) / h))
+                .sum();
+            sum / (self.samples.len() as f64 * h)
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            let h = self.bandwidth;
+            let sum: f64 = self
+                .samples
+                .iter()
+                .map(|y| self.kernel.cdf((x - y) / h))
+                .sum();
+            Some(sum / self.samples.len() as f64)
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            let y = self.samples[gen.gen_range(0..self.samples.len())];
+            Some(y + self.bandwidth * self.kernel.sample(gen))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Kernel;
+        use super::*;
+        use rand::SeedableRng;
+
+        #[test]
+        fn gaussian() {
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let xs: Vec<f64> = (0..2000).map(|_| normal(&mut gen)).collect();
+            for kernel in [Kernel::Gaussian, Kernel::Epanechnikov] {
+                let kde = Kde::new(&xs).kernel(kernel);
+                let h = 0.01;
+                let mass: f64 = (-1000..1000).map(|i| kde.pdf(&(i as f64 * h)) * h).sum();
+                assert!((mass - 1.0).abs() < 1e-3);
+                assert!((kde.pdf(&0.0) - 0.3989).abs() < 0.05);
+                assert!((kde.cdf(&0.0).unwrap() - 0.5).abs() < 0.05);
+                let var = kde
+                    .sample_iter(&mut gen)
+                    .take(20000)
+                    .map(|x| x * x)
+                    .sum::<f64>()
+                    / 20000.0;
+                assert!((var - 1.0 - kde.bandwidth.powi(2)).abs() < 0.1);
+            }
+        }
+    }
+}
+
+#[doc = "Copulas joining univariate marginals into a dependent joint"]
+pub mod copula {
+    use super::*;
+
+    #[doc = "Joint distribution of uniform marginals"]
+    pub trait Copula {
+        fn dim(&self) -> usize;
+        fn log_density(&self, u: &[f64]) -> f64;
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Vec<f64>;
+    }
+
+    #[doc = "Gaussian copula with correlation matrix `corr`"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Gaussian {
+        pub corr: nd::Array2<f64>,
+        pub factor: na::DMatrix<f64>,
+    }
+    impl Copula for Gaussian {
+        fn dim(&self) -> usize {
+            self.corr.nrows()
+        }
+        fn log_density(&self, u: &[f64]) -> f64 {
+            assert_eq!(u.len(), self.dim(), "dimension mismatch");
+            let z = na::DVector::from_iterator(u.len(), u.iter().map(|u| probit(*u)));
+            let y = (self.factor.solve_lower_triangular(&z)).expect("factor is singular");
+            let ln_det: f64 = self.factor.diagonal().iter().map(|l| l.ln()).sum();
+            -(y.norm_squared() - z.norm_squared()) / 2.0 - ln_det
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Vec<f64> {
+            let z = &self.factor * na::DVector::from_fn(self.dim(), |_, _| normal(gen));
+            z.iter().map(|z| erfc(-z / 2f64.sqrt()) / 2.0).collect()
+        }
+    }
+
+    pub fn gaussian(corr: nd::Array2<f64>) -> Gaussian {
+        assert!(corr.is_square(), "correlation isn't square");
+        assert!(
+            corr.diag().iter().all(|c| *c == 1.0),
+            "correlation isn't unit diagonal"
+        );
+        let factor = (ns::ToNalgebra::into_nalgebra(corr.clone()).cholesky())
+            .expect("correlation isn't positive definite")
+            .unpack();
+        Gaussian { corr, factor }
+    }
+
+    #[doc = "Clayton copula, with lower tail dependence"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Clayton {
+        pub theta: f64,
+        pub dim: usize,
+    }
+    impl Copula for Clayton {
+        fn dim(&self) -> usize {
+            self.dim
+        }
+        fn log_density(&self, u: &[f64]) -> f64 {
+            let (theta, d) = (self.theta, u.len() as f64);
+            let s: f64 = u.iter().map(|u| u.powf(-theta)).sum::<f64>() - d + 1.0;
+            (0..u.len())
+                .map(|k| (theta * k as f64).ln_1p())
+                .sum::<f64>()
+                - (theta + 1.0) * u.iter().map(|u| u.ln()).sum::<f64>()
+                - (1.0 / theta + d) * s.ln()
+        }
+        // Marshall-Olkin with a gamma frailty
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Vec<f64> {
+            let v = gamma(gen, 1.0 / self.theta);
+            (0..self.dim)
+                .map(|_| {
+                    let e = -(1.0 - gen.gen_range(0.0..1.0f64)).ln();
+                    (-(e / v).ln_1p() / self.theta).exp()
+                })
+                .collect()
+        }
+    }
+
+    pub fn clayton(theta: f64, dim: usize) -> Clayton {
+        assert!(theta > 0.0, "theta isn't positive");
+        assert!(dim > 1, "too few dimensions");
+        Clayton { theta, dim }
+    }
+
+    #[doc = "Bivariate Gumbel copula, with upper tail dependence"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Gumbel {
+        pub theta: f64,
+        pub dim: usize,
+    }
+    impl Copula for Gumbel {
+        fn dim(&self) -> usize {
+            self.dim
+        }
+        fn log_density(&self, u: &[f64]) -> f64 {
+            assert_eq!(u.len(), 2, "dimension mismatch");
+            let theta = self.theta;
+            let (x, y) = (-u[0].ln(), -u[1].ln());
+            let s = x.powf(theta) + y.powf(theta);
+            let a = s.powf(1.0 / theta);
+            -a + x
+                + y
+                + (theta - 1.0) * (x * y).ln()
+                + (1.0 / theta - 2.0) * s.ln()
+                + (a + theta - 1.0).ln()
+        }
+        // Marshall-Olkin with a positive stable frailty (Chambers-Mallows-Stuck)
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Vec<f64> {
+            let alpha = 1.0 / self.theta;
+            let u = gen.gen_range(0.0..std::f64::consts::PI);
+            let w = -(1.0 - gen.gen_range(0.0..1.0f64)).ln();
+            let v = (alpha * u).sin() / u.sin().powf(1.0 / alpha)
+                * (((1.0 - alpha) * u).sin() / w).powf((1.0 - alpha) / alpha);
+            (0..self.dim)
+                .map(|_| {
+                    let e = -(1.0 - gen.gen_range(0.0..1.0f64)).ln();
+                    (-(e / v).powf(alpha)).exp()
+                })
+                .collect()
+        }
+    }
+
+    pub fn gumbel(theta: f64, dim: usize) -> Gumbel {
+        assert!(theta >= 1.0, "theta is below 1");
+        assert!(dim == 2, "gumbel density is bivariate");
+        Gumbel { theta, dim }
+    }
+
+    #[doc = "Joint of univariate marginals coupled by a copula"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Joint<C, T> {
+        pub copula: C,
+        pub marginals: Vec<T>,
+    }
+    impl<C: Copula, T: Distribution<f64>> Distribution<nd::Array1<f64>> for Joint<C, T> {
+        fn log_pdf(&self, x: &nd::Array1<f64>) -> f64 {
+            assert_eq!(x.len(), self.marginals.len(), "dimension mismatch");
+            let u: Vec<f64> = (self.marginals.iter())
+                .zip(x.iter())
+                .map(|(m, x)| m.cdf(x).expect("marginal has no cdf"))
+                .collect();
+            let marginal: f64 = (self.marginals.iter())
+                .zip(x.iter())
+                .map(|(m, x)| m.log_pdf(x))
+                .sum();
+            self.copula.log_density(&u) + marginal
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array1<f64>> {
+            let u = self.copula.sample(gen);
+            (self.marginals.iter())
+                .zip(u)
+                .map(|(m, u)| m.quantile(u))
+                .collect()
+        }
+    }
+
+    pub fn joint<C: Copula, T: Distribution<f64>>(copula: C, marginals: Vec<T>) -> Joint<C, T> {
+        assert_eq!(copula.dim(), marginals.len(), "marginals mismatch");
+        Joint { copula, marginals }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::SeedableRng;
+
+        fn kendall(xs: &[Vec<f64>]) -> f64 {
+            let n = xs.len();
+            let concordant: f64 = (0..n)
+                .flat_map(|i| (0..i).map(move |j| (i, j)))
+                .map(|(i, j)| ((xs[i][0] - xs[j][0]) * (xs[i][1] - xs[j][1])).signum())
+                .sum();
+            concordant / (n * (n - 1) / 2) as f64
+        }
+
+        #[test]
+        fn archimedean() {
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let clayton = clayton(2.0, 2);
+            let xs: Vec<_> = (0..1000).map(|_| clayton.sample(&mut gen)).collect();
+            assert!((kendall(&xs) - 0.5).abs() < 0.05);
+            let gumbel = gumbel(2.0, 2);
+            let xs: Vec<_> = (0..1000).map(|_| gumbel.sample(&mut gen)).collect();
+            assert!((kendall(&xs) - 0.5).abs() < 0.05);
+
+            let h = 0.005;
+            let grid = || (0..200).map(|i| (i as f64 + 0.5) * h);
+            let mass = |f: &dyn Fn(&[f64]) -> f64| {
+                grid()
+                    .flat_map(|u| grid().map(move |v| [u, v]))
+                    .map(|uv| f(&uv).exp() * h * h)
+                    .sum::<f64>()
+            };
+            assert!((mass(&|u| clayton.log_density(u)) - 1.0).abs() < 0.02);
+            assert!((mass(&|u| gumbel.log_density(u)) - 1.0).abs() < 0.02);
+
+            let joint = joint(
+                clayton,
+                vec![univar::exponential(1.0), univar::exponential(2.0)],
+            );
+            let x = nd::Array1::from(vec![0.5, 0.2]);
+            assert!(joint.log_pdf(&x).is_finite());
+        }
+
+        #[test]
+        fn gaussian() {
+            let rho: f64 = 0.5;
+            let copula = super::gaussian(
+                nd::Array2::from_shape_vec((2, 2), vec![1.0, rho, rho, 1.0]).unwrap(),
+            );
+            let (u, v) = (0.3, 0.8);
+            let (a, b) = (probit(u), probit(v));
+            let expected = -(1.0 - rho * rho).ln() / 2.0
+                - (rho * rho * (a * a + b * b) - 2.0 * rho * a * b) / (2.0 * (1.0 - rho * rho));
+            assert!((copula.log_density(&[u, v]) - expected).abs() < 1e-9);
+        }
+
+        #[test]
+        #[should_panic(expected = "correlation isn't positive definite")]
+        fn singular() {
+            super::gaussian(nd::Array2::from_shape_vec((2, 2), vec![1.0, 2.0, 2.0, 1.0]).unwrap());
+        }
+    }
+}
+
+#[doc = "Gumbel-max and Gumbel-softmax (concrete) sampling"]
+pub mod gumbel {
+    #[doc = "Standard gumbel variate"]
+    pub fn sample<R: rand::Rng + ?Sized>(gen: &mut R) -> f64 {
+        let u: f64 = gen.gen_range(0.0..1.0);
+        -(-(1.0 - u).ln()).ln()
+    }
+
+    #[doc = "Exact categorical draw from unnormalized log-probabilities"]
+    pub fn argmax<R: rand::Rng + ?Sized>(logits: &[f64], gen: &mut R) -> usize {
+        assert!(!logits.is_empty(), "no categories");
+        (logits.iter().enumerate())
+            .map(|(i, l)| (i, l + sample(gen)))
+            .fold((0, f64::NEG_INFINITY), |a, b| if b.1 > a.1 { b } else { a })
+            .0
+    }
+
+    #[doc = "Relaxed one-hot draw on the simplex, approaching `argmax` as temperature vanishes"]
+    pub fn softmax<R: rand::Rng + ?Sized>(
+        logits: &[f64],
+        temperature: f64,
+        gen: &mut R,
+    ) -> Vec<f64> {
+        assert!(temperature > 0.0, "temperature isn't positive");
+        let ys: Vec<f64> = (logits.iter())
+            .map(|l| (l + sample(gen)) / temperature)
+            .collect();
+        let max = ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let es: Vec<f64> = ys.iter().map(|y| (y - max).exp()).collect();
+        let sum: f64 = es.iter().sum();
+        es.iter().map(|e| e / sum).collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::SeedableRng;
+
+        #[test]
+        fn frequencies() {
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let logits = [0.0, 1.0, 2.0];
+            let mut counts = [0.0; 3];
+            (0..30000).for_each(|_| counts[argmax(&logits, &mut gen)] += 1.0 / 30000.0);
+            let sum: f64 = logits.iter().map(|l: &f64| l.exp()).sum();
+            for (c, l) in counts.iter().zip(logits.iter()) {
+                assert!((c - l.exp() / sum).abs() < 0.02);
+            }
+            let relaxed = softmax(&logits, 0.5, &mut gen);
+            assert!((relaxed.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        }
+    }
+}
+
 pub mod univar {
     use super::*;
 
-    pub fn uniform<D: num::ToPrimitive>() -> impl Fn(&D) -> f64 {
-        move |_| 1.0
+    pub fn uniform<D: num::ToPrimitive>() -> impl Fn(&D) -> f64 {
+        density(Flat)
+    }
+
+    pub fn gaussian<D: num::ToPrimitive>(mu: f64, sigma: f64) -> impl Fn(&D) -> f64 {
+        move |x| (-(x.to_f64().unwrap() - mu).powi(2) / (2.0 * sigma.powi(2))).exp()
+    }
+
+    #[doc = "Normalized gaussian density, checking `sigma`"]
+    pub fn gaussian_normalized<D: num::ToPrimitive>(mu: f64, sigma: f64) -> impl Fn(&D) -> f64 {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        let gaussian = family::Gaussian { mu, sigma };
+        move |x| Distribution::pdf(&gaussian, &x.to_f64().unwrap())
+    }
+
+    pub fn uniform_log<D: num::ToPrimitive>() -> impl Fn(&D) -> f64 {
+        Log(Flat).density()
+    }
+
+    pub fn gaussian_log<D: num::ToPrimitive>(mu: f64, sigma: f64) -> impl Fn(&D) -> f64 {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        let gaussian = family::Gaussian { mu, sigma };
+        move |x| Distribution::log_pdf(&gaussian, &x.to_f64().unwrap())
+    }
+
+    #[doc = "Beta distribution on the unit interval"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Beta {
+        pub alpha: f64,
+        pub beta: f64,
+    }
+    impl Distribution<f64> for Beta {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if !(0.0..=1.0).contains(x) {
+                return f64::NEG_INFINITY;
+            }
+            let (a, b) = (self.alpha, self.beta);
+            (a - 1.0) * x.ln() + (b - 1.0) * (1.0 - x).ln() - ln_beta(a, b)
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(beta_inc(self.alpha, self.beta, x.clamp(0.0, 1.0)))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            let (x, y) = (super::gamma(gen, self.alpha), super::gamma(gen, self.beta));
+            Some(x / (x + y))
+        }
+    }
+
+    pub fn beta(alpha: f64, beta: f64) -> Beta {
+        assert!(alpha > 0.0, "alpha isn't positive");
+        assert!(beta > 0.0, "beta isn't positive");
+        Beta { alpha, beta }
+    }
+
+    #[doc = "Binomial distribution of successes in `n` trials"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Binomial {
+        pub n: u64,
+        pub p: f64,
+    }
+    impl<D: num::ToPrimitive + num::FromPrimitive> Distribution<D> for Binomial {
+        fn log_pdf(&self, x: &D) -> f64 {
+            match x.to_u64().filter(|k| *k <= self.n) {
+                Some(k) => {
+                    let (n, k) = (self.n as f64, k as f64);
+                    ln_choose(n, k) + xlogy(k, self.p) + xlogy(n - k, 1.0 - self.p)
+                }
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &D) -> Option<f64> {
+            let x = x.to_f64()?;
+            if x < 0.0 {
+                return Some(0.0);
+            }
+            let k = x.floor();
+            if k >= self.n as f64 {
+                return Some(1.0);
+            }
+            Some(beta_inc(self.n as f64 - k, k + 1.0, 1.0 - self.p))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+            D::from_u64(super::binomial(gen, self.n, self.p))
+        }
+    }
+
+    pub fn binomial(n: u64, p: f64) -> Binomial {
+        assert!((0.0..=1.0).contains(&p), "p isn't in [0, 1]");
+        Binomial { n, p }
+    }
+
+    #[doc = "Negative binomial distribution of failures before the `r`-th success"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct NegativeBinomial {
+        pub r: f64,
+        pub p: f64,
+    }
+    impl<D: num::ToPrimitive + num::FromPrimitive> Distribution<D> for NegativeBinomial {
+        fn log_pdf(&self, x: &D) -> f64 {
+            match x.to_u64() {
+                Some(k) => {
+                    let (r, k) = (self.r, k as f64);
+                    ln_gamma(k + r) - ln_gamma(r) - ln_gamma(k + 1.0)
+                        + r * self.p.ln()
+                        + xlogy(k, 1.0 - self.p)
+                }
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &D) -> Option<f64> {
+            let x = x.to_f64()?;
+            if x < 0.0 {
+                return Some(0.0);
+            }
+            Some(beta_inc(self.r, x.floor() + 1.0, self.p))
+        }
+        // gamma-poisson mixture
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+            let lambda = super::gamma(gen, self.r) * (1.0 - self.p) / self.p;
+            let poisson = family::Poisson { lambda };
+            D::from_usize(family::ExponentialFamily::sample(&poisson, gen))
+        }
+    }
+
+    pub fn negative_binomial(r: f64, p: f64) -> NegativeBinomial {
+        assert!(r > 0.0, "r isn't positive");
+        assert!(0.0 < p && p <= 1.0, "p isn't in (0, 1]");
+        NegativeBinomial { r, p }
+    }
+
+    #[doc = "Geometric distribution of failures before the first success"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Geometric {
+        pub p: f64,
+    }
+    impl<D: num::ToPrimitive + num::FromPrimitive> Distribution<D> for Geometric {
+        fn log_pdf(&self, x: &D) -> f64 {
+            match x.to_u64() {
+                Some(0) => self.p.ln(),
+                Some(k) => self.p.ln() + k as f64 * (-self.p).ln_1p(),
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &D) -> Option<f64> {
+            let x = x.to_f64()?;
+            if x < 0.0 {
+                return Some(0.0);
+            }
+            Some(1.0 - ((x.floor() + 1.0) * (-self.p).ln_1p()).exp())
+        }
+        fn quantile(&self, p: f64) -> Option<D> {
+            if self.p == 1.0 {
+                return D::from_u64(0);
+            }
+            D::from_f64(((-p).ln_1p() / (-self.p).ln_1p()).floor())
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
+    }
+
+    pub fn geometric(p: f64) -> Geometric {
+        assert!(0.0 < p && p <= 1.0, "p isn't in (0, 1]");
+        Geometric { p }
+    }
+
+    #[doc = "Zipf distribution over ranks `1..=n` with exponent `s`"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Zipf {
+        pub s: f64,
+        pub n: u64,
+        norm: f64,
+    }
+    impl Zipf {
+        // inverse cdf of the envelope, flat on [0, 1] and `x^-s` on [1, n]
+        fn envelope(&self, u: f64) -> f64 {
+            let s = self.s;
+            let mass = if s == 1.0 {
+                1.0 + (self.n as f64).ln()
+            } else {
+                ((self.n as f64).powf(1.0 - s) - s) / (1.0 - s)
+            };
+            let x = u * mass;
+            match (x <= 1.0, s == 1.0) {
+                (true, _) => x,
+                (false, true) => (x - 1.0).exp(),
+                (false, false) => (x * (1.0 - s) + s).powf(1.0 / (1.0 - s)),
+            }
+        }
+    }
+    impl<D: num::ToPrimitive + num::FromPrimitive> Distribution<D> for Zipf {
+        fn log_pdf(&self, x: &D) -> f64 {
+            match x.to_u64().filter(|k| (1..=self.n).contains(k)) {
+                Some(k) => -self.s * (k as f64).ln() - self.norm.ln(),
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &D) -> Option<f64> {
+            let k = x.to_f64()?.floor().clamp(0.0, self.n as f64) as u64;
+            Some((1..=k).map(|k| (k as f64).powf(-self.s)).sum::<f64>() / self.norm)
+        }
+        // rejection-inversion from the continuous envelope
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+            loop {
+                let x = self.envelope(gen.gen_range(0.0..1.0));
+                let k = (x + 1.0).floor().min(self.n as f64);
+                let ratio = k.powf(-self.s) * x.max(1.0).powf(self.s);
+                if gen.gen_range(0.0..1.0) < ratio {
+                    return D::from_f64(k);
+                }
+            }
+        }
+    }
+
+    pub fn zipf(s: f64, n: u64) -> Zipf {
+        assert!(s > 0.0, "s isn't positive");
+        assert!(n > 0, "n isn't positive");
+        let norm = (1..=n).map(|k| (k as f64).powf(-s)).sum();
+        Zipf { s, n, norm }
+    }
+
+    #[doc = "Hypergeometric distribution of successes in `n` draws without replacement"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Hypergeometric {
+        pub population: u64,
+        pub successes: u64,
+        pub n: u64,
+    }
+    impl Hypergeometric {
+        fn support(&self) -> std::ops::RangeInclusive<u64> {
+            let failures = self.population - self.successes;
+            self.n.saturating_sub(failures)..=self.n.min(self.successes)
+        }
+
+        fn ln_pmf(&self, k: u64) -> f64 {
+            let (big, good, n, k) = (
+                self.population as f64,
+                self.successes as f64,
+                self.n as f64,
+                k as f64,
+            );
+            ln_choose(good, k) + ln_choose(big - good, n - k) - ln_choose(big, n)
+        }
+    }
+    impl<D: num::ToPrimitive + num::FromPrimitive> Distribution<D> for Hypergeometric {
+        fn log_pdf(&self, x: &D) -> f64 {
+            match x.to_u64().filter(|k| self.support().contains(k)) {
+                Some(k) => self.ln_pmf(k),
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &D) -> Option<f64> {
+            let x = x.to_f64()?;
+            let cdf = (self.support())
+                .take_while(|k| *k as f64 <= x)
+                .map(|k| self.ln_pmf(k).exp())
+                .sum::<f64>();
+            Some(cdf.min(1.0))
+        }
+        // inversion over the support
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<D> {
+            let mut u = gen.gen_range(0.0..1.0);
+            let support = self.support();
+            let last = *support.end();
+            for k in support {
+                u -= self.ln_pmf(k).exp();
+                if u < 0.0 {
+                    return D::from_u64(k);
+                }
+            }
+            D::from_u64(last)
+        }
+    }
+
+    pub fn hypergeometric(population: u64, successes: u64, n: u64) -> Hypergeometric {
+        assert!(successes <= population, "successes exceed population");
+        assert!(n <= population, "draws exceed population");
+        Hypergeometric {
+            population,
+            successes,
+            n,
+        }
+    }
+
+    #[doc = "Categorical distribution over `Z<N>`, drawn from an alias table"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Categorical<const N: usize> {
+        pub probs: Vec<f64>,
+        cutoff: Vec<f64>,
+        alias: Vec<usize>,
+    }
+    impl<const N: usize> Distribution<modular::Z<N>> for Categorical<N> {
+        fn log_pdf(&self, x: &modular::Z<N>) -> f64 {
+            self.probs[x.0].ln()
+        }
+        fn pdf(&self, x: &modular::Z<N>) -> f64 {
+            self.probs[x.0]
+        }
+        fn cdf(&self, x: &modular::Z<N>) -> Option<f64> {
+            Some(self.probs[..=x.0].iter().sum())
+        }
+        fn quantile(&self, p: f64) -> Option<modular::Z<N>> {
+            let mut cdf = 0.0;
+            let k = (self.probs.iter()).position(|q| {
+                cdf += q;
+                cdf >= p
+            });
+            Some(modular::Z(k.unwrap_or(N - 1)))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<modular::Z<N>> {
+            let (i, u) = (gen.gen_range(0..N), gen.gen_range(0.0..1.0));
+            Some(modular::Z(if u < self.cutoff[i] {
+                i
+            } else {
+                self.alias[i]
+            }))
+        }
+    }
+
+    // vose's alias method
+    fn alias(probs: &[f64]) -> (Vec<f64>, Vec<usize>) {
+        let n = probs.len();
+        let mut cutoff: Vec<f64> = probs.iter().map(|p| p * n as f64).collect();
+        let mut alias: Vec<usize> = (0..n).collect();
+        let (mut small, mut large): (Vec<usize>, Vec<usize>) =
+            (0..n).partition(|&i| cutoff[i] < 1.0);
+        while !small.is_empty() && !large.is_empty() {
+            let (s, l) = (small.pop().unwrap(), large[large.len() - 1]);
+            alias[s] = l;
+            cutoff[l] -= 1.0 - cutoff[s];
+            if cutoff[l] < 1.0 {
+                small.push(large.pop().unwrap());
+            }
+        }
+        large.into_iter().chain(small).for_each(|i| cutoff[i] = 1.0);
+        (cutoff, alias)
+    }
+
+    pub fn categorical<const N: usize>(weights: &[f64]) -> Categorical<N> {
+        assert_eq!(weights.len(), N, "weights mismatch");
+        assert!(weights.iter().all(|w| *w >= 0.0), "weight is negative");
+        let sum: f64 = weights.iter().sum();
+        assert!(sum > 0.0, "weights aren't positive");
+        let probs: Vec<f64> = weights.iter().map(|w| w / sum).collect();
+        let (cutoff, alias) = alias(&probs);
+        Categorical {
+            probs,
+            cutoff,
+            alias,
+        }
+    }
+
+    #[doc = "Student's t distribution with location and scale"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct StudentT {
+        pub nu: f64,
+        pub mu: f64,
+        pub sigma: f64,
+    }
+    impl Distribution<f64> for StudentT {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            let (nu, t) = (self.nu, (x - self.mu) / self.sigma);
+            ln_gamma((nu + 1.0) / 2.0)
+                - ln_gamma(nu / 2.0)
+                - (nu * std::f64::consts::PI).ln() / 2.0
+                - self.sigma.ln()
+                - (nu + 1.0) / 2.0 * (t * t / nu).ln_1p()
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            let (nu, t) = (self.nu, (x - self.mu) / self.sigma);
+            let tail = beta_inc(nu / 2.0, 0.5, nu / (nu + t * t)) / 2.0;
+            Some(if t > 0.0 { 1.0 - tail } else { tail })
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            let chi2 = 2.0 * super::gamma(gen, self.nu / 2.0);
+            Some(self.mu + self.sigma * normal(gen) / (chi2 / self.nu).sqrt())
+        }
+    }
+
+    pub fn student_t(nu: f64, mu: f64, sigma: f64) -> StudentT {
+        assert!(nu > 0.0, "nu isn't positive");
+        assert!(sigma > 0.0, "sigma isn't positive");
+        StudentT { nu, mu, sigma }
+    }
+
+    #[doc = "Cauchy distribution with location and scale"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Cauchy {
+        pub mu: f64,
+        pub sigma: f64,
+    }
+    impl Distribution<f64> for Cauchy {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            let t = (x - self.mu) / self.sigma;
+            -(std::f64::consts::PI * self.sigma).ln() - (t * t).ln_1p()
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(0.5 + ((x - self.mu) / self.sigma).atan() / std::f64::consts::PI)
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            Some(self.mu + self.sigma * (std::f64::consts::PI * (p - 0.5)).tan())
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
+    }
+
+    pub fn cauchy(mu: f64, sigma: f64) -> Cauchy {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        Cauchy { mu, sigma }
+    }
+
+    #[doc = "Half-Cauchy distribution on the positive reals, a weakly informative scale prior"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct HalfCauchy {
+        pub sigma: f64,
+    }
+    impl Distribution<f64> for HalfCauchy {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if *x < 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            let t = x / self.sigma;
+            (2.0 / (std::f64::consts::PI * self.sigma)).ln() - (t * t).ln_1p()
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(2.0 * (x.max(0.0) / self.sigma).atan() / std::f64::consts::PI)
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            Some(self.sigma * (std::f64::consts::PI * p / 2.0).tan())
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
+    }
+
+    pub fn half_cauchy(sigma: f64) -> HalfCauchy {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        HalfCauchy { sigma }
+    }
+
+    #[doc = "Log-normal distribution, whose logarithm is gaussian"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct LogNormal {
+        pub mu: f64,
+        pub sigma: f64,
+    }
+    impl Distribution<f64> for LogNormal {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if *x <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            let z = (x.ln() - self.mu) / self.sigma;
+            -z * z / 2.0 - (x * self.sigma).ln() - (2.0 * std::f64::consts::PI).ln() / 2.0
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            if *x <= 0.0 {
+                return Some(0.0);
+            }
+            Some(erfc(-(x.ln() - self.mu) / (self.sigma * 2f64.sqrt())) / 2.0)
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            Some((self.mu + self.sigma * probit(p)).exp())
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            Some((self.mu + self.sigma * normal(gen)).exp())
+        }
+    }
+
+    pub fn lognormal(mu: f64, sigma: f64) -> LogNormal {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        LogNormal { mu, sigma }
+    }
+
+    #[doc = "Von Mises distribution on the circle"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct VonMises {
+        pub mu: f64,
+        pub kappa: f64,
+    }
+    impl Distribution<circular::Angle> for VonMises {
+        fn log_pdf(&self, x: &circular::Angle) -> f64 {
+            self.kappa * (x.0 - self.mu).cos()
+                - (2.0 * std::f64::consts::PI).ln()
+                - ln_bessel_i0(self.kappa)
+        }
+        // Best & Fisher (1979) wrapped cauchy envelope
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<circular::Angle> {
+            use std::f64::consts::PI;
+            if self.kappa < 1e-8 {
+                return Some(circular::Angle(gen.gen_range(-PI..PI)));
+            }
+            let tau = 1.0 + (1.0 + 4.0 * self.kappa * self.kappa).sqrt();
+            let rho = (tau - (2.0 * tau).sqrt()) / (2.0 * self.kappa);
+            let r = (1.0 + rho * rho) / (2.0 * rho);
+            loop {
+                let (u1, u2, u3): (f64, f64, f64) = (
+                    gen.gen_range(0.0..1.0),
+                    gen.gen_range(0.0..1.0),
+                    gen.gen_range(0.0..1.0),
+                );
+                let z = (PI * u1).cos();
+                let f = (1.0 + r * z) / (r + z);
+                let c = self.kappa * (r - f);
+                if c * (2.0 - c) > u2 || (c / u2).ln() + 1.0 >= c {
+                    let theta = f.clamp(-1.0, 1.0).acos();
+                    let theta = if u3 < 0.5 { -theta } else { theta };
+                    return Some(circular::Angle::new(self.mu + theta));
+                }
+            }
+        }
+    }
+
+    pub fn von_mises(mu: f64, kappa: f64) -> VonMises {
+        assert!(kappa >= 0.0, "kappa is negative");
+        VonMises { mu, kappa }
+    }
+
+    #[doc = "Weibull distribution of lifetimes"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Weibull {
+        pub shape: f64,
+        pub scale: f64,
+    }
+    impl Distribution<f64> for Weibull {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if *x < 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            let (k, z) = (self.shape, x / self.scale);
+            // at the origin the density diverges below shape 1, vanishes above and is 1/scale at 1
+            (k / self.scale).ln() + xlogy(k - 1.0, z) - z.powf(k)
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(-(-(x.max(0.0) / self.scale).powf(self.shape)).exp_m1())
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            Some(self.scale * (-(-p).ln_1p()).powf(1.0 / self.shape))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
     }
 
-    pub fn gaussian<D: num::ToPrimitive>(mu: f64, sigma: f64) -> impl Fn(&D) -> f64 {
-        move |x| (-(x.to_f64().unwrap() - mu).powi(2) / (2.0 * sigma.powi(2))).exp()
+    pub fn weibull(shape: f64, scale: f64) -> Weibull {
+        assert!(shape > 0.0, "shape isn't positive");
+        assert!(scale > 0.0, "scale isn't positive");
+        Weibull { shape, scale }
+    }
+
+    #[doc = "Pareto distribution above the minimum `scale`"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Pareto {
+        pub scale: f64,
+        pub alpha: f64,
+    }
+    impl Distribution<f64> for Pareto {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if *x < self.scale {
+                return f64::NEG_INFINITY;
+            }
+            self.alpha.ln() + self.alpha * self.scale.ln() - (self.alpha + 1.0) * x.ln()
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(1.0 - (self.scale / x.max(self.scale)).powf(self.alpha))
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            Some(self.scale * (1.0 - p).powf(-1.0 / self.alpha))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
+    }
+
+    pub fn pareto(scale: f64, alpha: f64) -> Pareto {
+        assert!(scale > 0.0, "scale isn't positive");
+        assert!(alpha > 0.0, "alpha isn't positive");
+        Pareto { scale, alpha }
+    }
+
+    #[doc = "Generalized Pareto distribution of exceedances, with tail index `xi`"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct GeneralizedPareto {
+        pub mu: f64,
+        pub sigma: f64,
+        pub xi: f64,
+    }
+    impl GeneralizedPareto {
+        // standardized excess, if inside the support
+        fn excess(&self, x: f64) -> Option<f64> {
+            let z = (x - self.mu) / self.sigma;
+            Some(z).filter(|z| *z >= 0.0 && (self.xi >= 0.0 || *z <= -1.0 / self.xi))
+        }
+    }
+    impl Distribution<f64> for GeneralizedPareto {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            match self.excess(*x) {
+                Some(z) if self.xi == 0.0 => -self.sigma.ln() - z,
+                Some(z) => -self.sigma.ln() - (1.0 / self.xi + 1.0) * (self.xi * z).ln_1p(),
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(match self.excess(*x) {
+                Some(z) if self.xi == 0.0 => -(-z).exp_m1(),
+                Some(z) => 1.0 - (-(self.xi * z).ln_1p() / self.xi).exp(),
+                None if *x < self.mu => 0.0,
+                None => 1.0,
+            })
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            let z = if self.xi == 0.0 {
+                -(-p).ln_1p()
+            } else {
+                (-self.xi * (-p).ln_1p()).exp_m1() / self.xi
+            };
+            Some(self.mu + self.sigma * z)
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
+    }
+
+    pub fn generalized_pareto(mu: f64, sigma: f64, xi: f64) -> GeneralizedPareto {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        GeneralizedPareto { mu, sigma, xi }
+    }
+
+    #[doc = "Laplace (double exponential) distribution"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Laplace {
+        pub mu: f64,
+        pub b: f64,
+    }
+    impl Distribution<f64> for Laplace {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            -(2.0 * self.b).ln() - (x - self.mu).abs() / self.b
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            let tail = (-(x - self.mu).abs() / self.b).exp() / 2.0;
+            Some(if *x < self.mu { tail } else { 1.0 - tail })
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            Some(if p < 0.5 {
+                self.mu + self.b * (2.0 * p).ln()
+            } else {
+                self.mu - self.b * (2.0 - 2.0 * p).ln()
+            })
+        }
+        // difference of two exponentials
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            let (u, v): (f64, f64) = (gen.gen_range(0.0..1.0), gen.gen_range(0.0..1.0));
+            Some(self.mu + self.b * ((-v).ln_1p() - (-u).ln_1p()))
+        }
+    }
+
+    pub fn laplace(mu: f64, b: f64) -> Laplace {
+        assert!(b > 0.0, "b isn't positive");
+        Laplace { mu, b }
+    }
+
+    #[doc = "Chi-squared distribution with `k` degrees of freedom"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ChiSquared {
+        pub k: f64,
+    }
+    impl ChiSquared {
+        fn gamma(&self) -> family::Gamma {
+            family::Gamma {
+                shape: self.k / 2.0,
+                rate: 0.5,
+            }
+        }
+    }
+    impl Distribution<f64> for ChiSquared {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            self.gamma().log_pdf(x)
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            self.gamma().cdf(x)
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            Some(2.0 * super::gamma(gen, self.k / 2.0))
+        }
+    }
+
+    pub fn chi_squared(k: f64) -> ChiSquared {
+        assert!(k > 0.0, "k isn't positive");
+        ChiSquared { k }
+    }
+
+    #[doc = "Fisher's F distribution, the ratio of scaled chi-squares"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct FisherF {
+        pub d1: f64,
+        pub d2: f64,
+    }
+    impl Distribution<f64> for FisherF {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if *x <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            let (d1, d2) = (self.d1, self.d2);
+            d1 / 2.0 * (d1 / d2).ln() + (d1 / 2.0 - 1.0) * x.ln()
+                - (d1 + d2) / 2.0 * (d1 * x / d2).ln_1p()
+                - ln_beta(d1 / 2.0, d2 / 2.0)
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            let x = self.d1 * x.max(0.0);
+            Some(beta_inc(self.d1 / 2.0, self.d2 / 2.0, x / (x + self.d2)))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            let (u, v) = (
+                super::gamma(gen, self.d1 / 2.0),
+                super::gamma(gen, self.d2 / 2.0),
+            );
+            Some(u / self.d1 / (v / self.d2))
+        }
+    }
+
+    pub fn fisher_f(d1: f64, d2: f64) -> FisherF {
+        assert!(d1 > 0.0, "d1 isn't positive");
+        assert!(d2 > 0.0, "d2 isn't positive");
+        FisherF { d1, d2 }
+    }
+
+    #[doc = "Inverse-gamma distribution, the conjugate prior of a gaussian variance"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct InverseGamma {
+        pub shape: f64,
+        pub scale: f64,
+    }
+    impl Distribution<f64> for InverseGamma {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            if *x <= 0.0 {
+                return f64::NEG_INFINITY;
+            }
+            let (a, b) = (self.shape, self.scale);
+            a * b.ln() - ln_gamma(a) - (a + 1.0) * x.ln() - b / x
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            if *x <= 0.0 {
+                return Some(0.0);
+            }
+            Some(1.0 - gamma_inc(self.shape, self.scale / x))
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            Some(self.scale / super::gamma(gen, self.shape))
+        }
+    }
+
+    pub fn inverse_gamma(shape: f64, scale: f64) -> InverseGamma {
+        assert!(shape > 0.0, "shape isn't positive");
+        assert!(scale > 0.0, "scale isn't positive");
+        InverseGamma { shape, scale }
+    }
+
+    #[doc = "Generalized extreme value distribution: Gumbel, Fréchet or reversed Weibull by the sign of `xi`"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Gev {
+        pub mu: f64,
+        pub sigma: f64,
+        pub xi: f64,
+    }
+    impl Gev {
+        // `-ln F(x)`, if inside the support
+        fn tail(&self, x: f64) -> Option<f64> {
+            let z = (x - self.mu) / self.sigma;
+            if self.xi == 0.0 {
+                return Some((-z).exp());
+            }
+            Some(1.0 + self.xi * z)
+                .filter(|t| *t > 0.0)
+                .map(|t| t.powf(-1.0 / self.xi))
+        }
+    }
+    impl Distribution<f64> for Gev {
+        fn log_pdf(&self, x: &f64) -> f64 {
+            match self.tail(*x) {
+                Some(t) => -self.sigma.ln() + (self.xi + 1.0) * t.ln() - t,
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn cdf(&self, x: &f64) -> Option<f64> {
+            Some(match self.tail(*x) {
+                Some(t) => (-t).exp(),
+                None if self.xi > 0.0 => 0.0,
+                None => 1.0,
+            })
+        }
+        fn quantile(&self, p: f64) -> Option<f64> {
+            let t = -p.ln();
+            Some(if self.xi == 0.0 {
+                self.mu - self.sigma * t.ln()
+            } else {
+                self.mu + self.sigma * (t.powf(-self.xi) - 1.0) / self.xi
+            })
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<f64> {
+            if self.xi == 0.0 {
+                return Some(self.mu + self.sigma * super::gumbel::sample(gen));
+            }
+            self.quantile(gen.gen_range(0.0..1.0))
+        }
+    }
+
+    pub fn gev(mu: f64, sigma: f64, xi: f64) -> Gev {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        Gev { mu, sigma, xi }
+    }
+
+    #[doc = "Gumbel distribution of maxima"]
+    pub fn gumbel(mu: f64, beta: f64) -> Gev {
+        gev(mu, beta, 0.0)
+    }
+
+    #[doc = "Fréchet distribution with shape `alpha`, bounded below by `location`"]
+    pub fn frechet(alpha: f64, location: f64, scale: f64) -> Gev {
+        assert!(alpha > 0.0, "alpha isn't positive");
+        assert!(scale > 0.0, "scale isn't positive");
+        gev(location + scale, scale / alpha, 1.0 / alpha)
+    }
+
+    #[doc = "Reversed Weibull distribution with shape `alpha`, bounded above by `location`"]
+    pub fn weibull_max(alpha: f64, location: f64, scale: f64) -> Gev {
+        assert!(alpha > 0.0, "alpha isn't positive");
+        assert!(scale > 0.0, "scale isn't positive");
+        gev(location - scale, scale / alpha, -1.0 / alpha)
+    }
+
+    pub fn gamma(shape: f64, rate: f64) -> family::Gamma {
+        assert!(shape > 0.0, "shape isn't positive");
+        assert!(rate > 0.0, "rate isn't positive");
+        family::Gamma { shape, rate }
+    }
+
+    pub fn exponential(lambda: f64) -> family::Exponential {
+        assert!(lambda > 0.0, "lambda isn't positive");
+        family::Exponential { rate: lambda }
+    }
+
+    pub fn bernoulli(p: f64) -> family::Bernoulli {
+        assert!((0.0..=1.0).contains(&p), "p isn't in [0, 1]");
+        family::Bernoulli { p }
+    }
+
+    pub fn poisson(lambda: f64) -> family::Poisson {
+        assert!(lambda > 0.0, "lambda isn't positive");
+        family::Poisson { lambda }
+    }
+
+    pub fn gumbel_max<const N: usize>(
+        logits: [f64; N],
+    ) -> sampler::univar::Exact<modular::Z<N>, impl Fn(&mut dyn rand::RngCore) -> modular::Z<N>>
+    {
+        sampler::univar::Exact::new(move |gen: &mut dyn rand::RngCore| {
+            modular::Z(super::gumbel::argmax(&logits, gen))
+        })
+    }
+
+    pub fn gaussian_exact<D: na::Scalar + num::FromPrimitive>(
+        mu: f64,
+        sigma: f64,
+    ) -> sampler::univar::Exact<D, impl Fn(&mut dyn rand::RngCore) -> D> {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        sampler::univar::Exact::new(move |gen: &mut dyn rand::RngCore| {
+            D::from_f64(mu + sigma * super::normal(gen)).unwrap()
+        })
     }
 }
 
@@ -16,7 +2128,7 @@ pub mod multivar {
     use super::*;
 
     pub fn uniform<D: num::ToPrimitive>() -> impl Fn(&nd::Array1<D>) -> f64 {
-        move |_| 1.0
+        density(Flat)
     }
 
     pub fn gaussian<D: num::ToPrimitive, const R: usize>(
@@ -29,4 +2141,937 @@ pub mod multivar {
             (-(σ * xs).dot(&xs) / 2.0).exp()
         }
     }
+
+    #[doc = "Normalized multivariate gaussian density, checking `σ` is positive definite"]
+    pub fn gaussian_normalized<D: num::ToPrimitive, const R: usize>(
+        μ: na::SVector<f64, R>,
+        σ: na::SMatrix<f64, R, R>,
+    ) -> impl Fn(&nd::Array1<D>) -> f64 {
+        let gaussian = Gaussian::new(
+            nd::Array1::from_shape_fn(R, |i| μ[i]),
+            nd::Array2::from_shape_fn((R, R), |(i, j)| σ[(i, j)]),
+        );
+        move |xs| gaussian.pdf(&xs.map(|x| x.to_f64().unwrap()))
+    }
+
+    #[doc = "Multivariate gaussian drawn through its cholesky factor"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Gaussian {
+        pub mean: nd::Array1<f64>,
+        pub factor: na::DMatrix<f64>,
+    }
+    impl Gaussian {
+        #[allow(unused)]
+        pub fn new(mean: nd::Array1<f64>, cov: nd::Array2<f64>) -> Self {
+            assert_eq!(cov.dim(), (mean.len(), mean.len()), "covariance mismatch");
+            let factor = cholesky(&cov)
+                .expect("covariance isn't positive definite")
+                .unpack();
+            Gaussian { mean, factor }
+        }
+    }
+    impl Distribution<nd::Array1<f64>> for Gaussian {
+        fn log_pdf(&self, x: &nd::Array1<f64>) -> f64 {
+            assert_eq!(x.len(), self.mean.len(), "dimension mismatch");
+            let d = ns::ToNalgebra::into_nalgebra(x - &self.mean);
+            let y = (self.factor.solve_lower_triangular(&d)).expect("factor is singular");
+            let ln_det: f64 = self.factor.diagonal().iter().map(|l| l.ln()).sum();
+            -y.norm_squared() / 2.0
+                - ln_det
+                - self.mean.len() as f64 / 2.0 * (2.0 * std::f64::consts::PI).ln()
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array1<f64>> {
+            let z = na::DVector::from_fn(self.mean.len(), |_, _| normal(gen));
+            Some(ns::ToNdarray1::into_ndarray1(&self.factor * z) + &self.mean)
+        }
+    }
+
+    #[doc = "Exact sampler of a multivariate gaussian, factorizing the covariance once"]
+    pub fn gaussian_exact(
+        mean: nd::Array1<f64>,
+        cov: nd::Array2<f64>,
+    ) -> sampler::univar::Exact<nd::Array1<f64>, impl Fn(&mut dyn rand::RngCore) -> nd::Array1<f64>>
+    {
+        exact(Gaussian::new(mean, cov))
+    }
+
+    fn precision(w: &nd::Array2<f64>, alpha: f64, tau: f64) -> nd::Array2<f64> {
+        assert!(w.is_square(), "adjacency isn't square");
+        assert!(*w == w.t(), "adjacency isn't symmetric");
+        assert!(tau > 0.0, "precision isn't positive");
+        let mut q = w.mapv(|w| -alpha * tau * w);
+        w.sum_axis(nd::Axis(1))
+            .iter()
+            .enumerate()
+            .for_each(|(i, d)| q[[i, i]] += tau * d);
+        q
+    }
+
+    // eigenvalue range of the scaled adjacency `D^-1/2 W D^-1/2`
+    fn spectrum(w: &nd::Array2<f64>) -> (f64, f64) {
+        let d = w.sum_axis(nd::Axis(1));
+        assert!(d.iter().all(|d| *d > 0.0), "adjacency has isolated sites");
+        let n = w.nrows();
+        let scaled = na::DMatrix::from_fn(n, n, |i, j| w[[i, j]] / (d[i] * d[j]).sqrt());
+        let eigen = scaled.symmetric_eigen().eigenvalues;
+        (eigen.iter()).fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), l| {
+            (lo.min(*l), hi.max(*l))
+        })
+    }
+
+    pub fn car<D: num::ToPrimitive>(
+        w: nd::Array2<f64>,
+        alpha: f64,
+        tau: f64,
+    ) -> impl Fn(&nd::Array1<D>) -> f64 {
+        let (lo, hi) = spectrum(&w);
+        assert!(1.0 / lo < alpha && alpha < 1.0 / hi, "car isn't proper");
+        let q = precision(&w, alpha, tau);
+        move |xs| {
+            let xs = xs.map(|x| x.to_f64().unwrap());
+            (-xs.dot(&q.dot(&xs)) / 2.0).exp()
+        }
+    }
+
+    pub fn icar<D: num::ToPrimitive>(
+        w: nd::Array2<f64>,
+        tau: f64,
+    ) -> impl Fn(&nd::Array1<D>) -> f64 {
+        let q = precision(&w, 1.0, tau);
+        let eps = 0.001 * w.nrows() as f64; // soft sum-to-zero constraint
+        move |xs| {
+            let xs = xs.map(|x| x.to_f64().unwrap());
+            (-(xs.dot(&q.dot(&xs)) + xs.sum().powi(2) / eps) / 2.0).exp()
+        }
+    }
+
+    #[doc = "Dirichlet distribution over the probability simplex"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Dirichlet {
+        pub alphas: Vec<f64>,
+    }
+    impl Distribution<nd::Array1<f64>> for Dirichlet {
+        fn log_pdf(&self, xs: &nd::Array1<f64>) -> f64 {
+            assert_eq!(xs.len(), self.alphas.len(), "dimension mismatch");
+            if xs.iter().any(|x| *x < 0.0) || (xs.sum() - 1.0).abs() > 1e-9 {
+                return f64::NEG_INFINITY;
+            }
+            let total: f64 = self.alphas.iter().sum();
+            let norm = ln_gamma(total) - self.alphas.iter().map(|a| ln_gamma(*a)).sum::<f64>();
+            (xs.iter().zip(self.alphas.iter()))
+                .map(|(x, a)| xlogy(a - 1.0, *x))
+                .sum::<f64>()
+                + norm
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array1<f64>> {
+            let xs: nd::Array1<f64> = self.alphas.iter().map(|a| gamma(gen, *a)).collect();
+            let sum = xs.sum();
+            Some(xs / sum)
+        }
+    }
+
+    pub fn dirichlet(alphas: &[f64]) -> Dirichlet {
+        assert!(alphas.len() > 1, "too few categories");
+        assert!(alphas.iter().all(|a| *a > 0.0), "alpha isn't positive");
+        Dirichlet {
+            alphas: alphas.to_vec(),
+        }
+    }
+
+    #[doc = "Wishart distribution over positive definite matrices"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Wishart {
+        pub nu: f64,
+        pub scale: nd::Array2<f64>,
+    }
+    impl Distribution<nd::Array2<f64>> for Wishart {
+        fn log_pdf(&self, x: &nd::Array2<f64>) -> f64 {
+            let (nu, p) = (self.nu, self.scale.nrows() as f64);
+            let v = cholesky(&self.scale).expect("scale isn't positive definite");
+            match cholesky(x) {
+                Some(c) => {
+                    let trace = (v.inverse() * ns::ToNalgebra::into_nalgebra(x.clone())).trace();
+                    (nu - p - 1.0) / 2.0 * ln_det(&c)
+                        - trace / 2.0
+                        - nu * p / 2.0 * 2f64.ln()
+                        - nu / 2.0 * ln_det(&v)
+                        - ln_gamma_p(self.scale.nrows(), nu / 2.0)
+                }
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array2<f64>> {
+            let v = cholesky(&self.scale).expect("scale isn't positive definite");
+            let x = bartlett(gen, self.nu, &v.unpack());
+            Some(ns::ToNdarray2::into_ndarray2(x))
+        }
+    }
+
+    pub fn wishart(nu: f64, scale: nd::Array2<f64>) -> Wishart {
+        assert!(scale.is_square(), "scale isn't square");
+        assert!(
+            nu > scale.nrows() as f64 - 1.0,
+            "nu isn't above dimension - 1"
+        );
+        assert!(cholesky(&scale).is_some(), "scale isn't positive definite");
+        Wishart { nu, scale }
+    }
+
+    #[doc = "Inverse-Wishart distribution, the conjugate prior of a gaussian covariance"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct InverseWishart {
+        pub nu: f64,
+        pub scale: nd::Array2<f64>,
+    }
+    impl Distribution<nd::Array2<f64>> for InverseWishart {
+        fn log_pdf(&self, x: &nd::Array2<f64>) -> f64 {
+            let (nu, p) = (self.nu, self.scale.nrows() as f64);
+            let psi = cholesky(&self.scale).expect("scale isn't positive definite");
+            match cholesky(x) {
+                Some(c) => {
+                    let trace =
+                        (ns::ToNalgebra::into_nalgebra(self.scale.clone()) * c.inverse()).trace();
+                    nu / 2.0 * ln_det(&psi)
+                        - nu * p / 2.0 * 2f64.ln()
+                        - ln_gamma_p(self.scale.nrows(), nu / 2.0)
+                        - (nu + p + 1.0) / 2.0 * ln_det(&c)
+                        - trace / 2.0
+                }
+                None => f64::NEG_INFINITY,
+            }
+        }
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array2<f64>> {
+            let psi = cholesky(&self.scale).expect("scale isn't positive definite");
+            let inv = psi
+                .inverse()
+                .cholesky()
+                .expect("scale isn't positive definite");
+            let w = bartlett(gen, self.nu, &inv.unpack());
+            let x = w
+                .cholesky()
+                .expect("draw isn't positive definite")
+                .inverse();
+            Some(ns::ToNdarray2::into_ndarray2(x))
+        }
+    }
+
+    pub fn inverse_wishart(nu: f64, scale: nd::Array2<f64>) -> InverseWishart {
+        assert!(scale.is_square(), "scale isn't square");
+        assert!(
+            nu > scale.nrows() as f64 - 1.0,
+            "nu isn't above dimension - 1"
+        );
+        assert!(cholesky(&scale).is_some(), "scale isn't positive definite");
+        InverseWishart { nu, scale }
+    }
+
+    #[doc = "Multivariate Student's t distribution with location and scale matrix"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct StudentT {
+        pub nu: f64,
+        pub mu: nd::Array1<f64>,
+        pub sigma: nd::Array2<f64>,
+    }
+    impl Distribution<nd::Array1<f64>> for StudentT {
+        fn log_pdf(&self, x: &nd::Array1<f64>) -> f64 {
+            assert_eq!(x.len(), self.mu.len(), "dimension mismatch");
+            let (nu, p) = (self.nu, self.mu.len() as f64);
+            let c = cholesky(&self.sigma).expect("sigma isn't positive definite");
+            let d = ns::ToNalgebra::into_nalgebra(x - &self.mu);
+            let delta = d.dot(&c.solve(&d));
+            ln_gamma((nu + p) / 2.0)
+                - ln_gamma(nu / 2.0)
+                - p / 2.0 * (nu * std::f64::consts::PI).ln()
+                - ln_det(&c) / 2.0
+                - (nu + p) / 2.0 * (delta / nu).ln_1p()
+        }
+        // gaussian scaled by an independent chi
+        fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Option<nd::Array1<f64>> {
+            let l = cholesky(&self.sigma)
+                .expect("sigma isn't positive definite")
+                .unpack();
+            let z = na::DVector::from_fn(self.mu.len(), |_, _| normal(gen));
+            let w = (2.0 * gamma(gen, self.nu / 2.0) / self.nu).sqrt();
+            let x = ns::ToNdarray1::into_ndarray1(l * z / w);
+            Some(x + &self.mu)
+        }
+    }
+
+    pub fn student_t(nu: f64, mu: nd::Array1<f64>, sigma: nd::Array2<f64>) -> StudentT {
+        assert!(nu > 0.0, "nu isn't positive");
+        assert_eq!(sigma.dim(), (mu.len(), mu.len()), "sigma mismatch");
+        assert!(cholesky(&sigma).is_some(), "sigma isn't positive definite");
+        StudentT { nu, mu, sigma }
+    }
+
+    fn cholesky(m: &nd::Array2<f64>) -> Option<na::Cholesky<f64, na::Dynamic>> {
+        ns::ToNalgebra::into_nalgebra(m.clone()).cholesky()
+    }
+
+    fn ln_det(c: &na::Cholesky<f64, na::Dynamic>) -> f64 {
+        2.0 * c.l_dirty().diagonal().iter().map(|l| l.ln()).sum::<f64>()
+    }
+
+    // multivariate gamma function
+    fn ln_gamma_p(p: usize, a: f64) -> f64 {
+        let pi = (p * (p - 1)) as f64 / 4.0 * std::f64::consts::PI.ln();
+        pi + (0..p).map(|j| ln_gamma(a - j as f64 / 2.0)).sum::<f64>()
+    }
+
+    // Bartlett decomposition `L A Aᵀ Lᵀ` with chi distributed diagonal and gaussian lower part
+    fn bartlett<R: rand::Rng + ?Sized>(
+        gen: &mut R,
+        nu: f64,
+        l: &na::DMatrix<f64>,
+    ) -> na::DMatrix<f64> {
+        let p = l.nrows();
+        let a = na::DMatrix::from_fn(p, p, |i, j| match i.cmp(&j) {
+            std::cmp::Ordering::Less => 0.0,
+            std::cmp::Ordering::Equal => (2.0 * gamma(gen, (nu - i as f64) / 2.0)).sqrt(),
+            std::cmp::Ordering::Greater => normal(gen),
+        });
+        let la = l * a;
+        &la * la.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn distribution() {
+        let gaussian = family::Gaussian {
+            mu: 1.0,
+            sigma: 2.0,
+        };
+        for x in [-3.0, 0.0, 1.0, 4.5] {
+            let p = gaussian.cdf(&x).unwrap();
+            assert!((gaussian.quantile(p).unwrap() - x).abs() < 1e-6);
+        }
+        let h = 0.01;
+        let mass: f64 = (-2000..2000)
+            .map(|i| gaussian.pdf(&(i as f64 * h)) * h)
+            .sum();
+        assert!((mass - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn batch() {
+        let gaussian = family::Gaussian {
+            mu: 1.0,
+            sigma: 2.0,
+        };
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = gaussian.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - 1.0).abs() < 0.05);
+        let xs = gaussian.sample_array((3, 4), &mut gen);
+        assert_eq!(xs.dim(), (3, 4));
+    }
+
+    #[test]
+    fn dirichlet() {
+        let dirichlet = multivar::dirichlet(&[2.0, 3.0]);
+        let beta = univar::beta(2.0, 3.0);
+        let xs = nd::Array1::from(vec![0.3, 0.7]);
+        assert!((dirichlet.log_pdf(&xs) - beta.log_pdf(&0.3)).abs() < 1e-9);
+        let draws = dirichlet.sample_matrix(10000, &mut rand::rngs::StdRng::seed_from_u64(0));
+        assert!((draws.column(0).iter().sum::<f64>() / 10000.0 - 0.4).abs() < 0.02);
+        // the edge of the simplex, flat along the first coordinate
+        let edge = nd::Array1::from(vec![0.0, 0.4, 0.6]);
+        let flat = multivar::dirichlet(&[1.0, 2.0, 2.0]);
+        assert!((flat.log_pdf(&edge) - (24.0f64 * 0.4 * 0.6).ln()).abs() < 1e-9);
+        assert_eq!(
+            dirichlet.log_pdf(&nd::Array1::from(vec![0.0, 1.0])),
+            f64::NEG_INFINITY
+        );
+    }
+
+    #[test]
+    fn wishart() {
+        let (nu, s, x) = (5.0, 2.0, 3.0);
+        let wishart = multivar::wishart(nu, nd::Array2::from_elem((1, 1), s));
+        let gamma = univar::gamma(nu / 2.0, 1.0 / (2.0 * s));
+        let xs = nd::Array2::from_elem((1, 1), x);
+        assert!((wishart.log_pdf(&xs) - gamma.log_pdf(&x)).abs() < 1e-9);
+        let inverse = multivar::inverse_wishart(nu, nd::Array2::from_elem((1, 1), s));
+        let (a, b) = (nu / 2.0, s / 2.0);
+        let expected = a * b.ln() - ln_gamma(a) - (a + 1.0) * x.ln() - b / x;
+        assert!((inverse.log_pdf(&xs) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn student_t() {
+        let cauchy = univar::student_t(1.0, 0.0, 1.0);
+        assert!((cauchy.pdf(&0.0) - 1.0 / std::f64::consts::PI).abs() < 1e-12);
+        assert!((cauchy.cdf(&1.0).unwrap() - 0.75).abs() < 1e-9);
+        let t = univar::student_t(5.0, 2.0, 0.5);
+        assert!((t.cdf(&2.0).unwrap() - 0.5).abs() < 1e-12);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = t.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - 2.0).abs() < 0.02);
+    }
+
+    #[test]
+    fn cauchy() {
+        let cauchy = univar::cauchy(1.0, 2.0);
+        let t = univar::student_t(1.0, 1.0, 2.0);
+        for x in [-5.0, 0.0, 1.0, 30.0] {
+            assert!((cauchy.log_pdf(&x) - t.log_pdf(&x)).abs() < 1e-12);
+            let p = cauchy.cdf(&x).unwrap();
+            assert!((cauchy.quantile(p).unwrap() - x).abs() < 1e-9);
+        }
+        let half = univar::half_cauchy(2.0);
+        assert!((half.pdf(&3.0) - 2.0 * cauchy.pdf(&4.0)).abs() < 1e-12);
+        assert!((half.cdf(&2.0).unwrap() - 0.5).abs() < 1e-12);
+
+        // heavy tails against the metropolis acceptance
+        let median = {
+            let mut xs: Vec<f64> = sampler::univar::Metropolis::builder()
+                .proposal(perturb(2.0))
+                .build()
+                .unwrap()
+                .burn(1000)
+                .sample_with_rng(
+                    density(univar::cauchy(0.0, 1.0)),
+                    rand::rngs::StdRng::seed_from_u64(0),
+                )
+                .take(5000)
+                .collect();
+            xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            xs[2500]
+        };
+        assert!(median.abs() < 0.3);
+    }
+
+    #[test]
+    fn lognormal() {
+        let lognormal = univar::lognormal(0.5, 0.8);
+        let gaussian = family::Gaussian {
+            mu: 0.5,
+            sigma: 0.8,
+        };
+        for x in [0.2, 1.0, 4.0] {
+            let jacobian = Distribution::pdf(&gaussian, &f64::ln(x)) / x;
+            assert!((lognormal.pdf(&x) - jacobian).abs() < 1e-12);
+            let p = lognormal.cdf(&x).unwrap();
+            assert!((lognormal.quantile(p).unwrap() - x).abs() < 1e-6);
+        }
+        assert_eq!(lognormal.cdf(&-1.0), Some(0.0));
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = lognormal.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - (0.5f64 + 0.32).exp()).abs() < 0.05);
+    }
+
+    #[test]
+    fn mixture() {
+        let gaussian = |mu| family::Gaussian { mu, sigma: 1.0 };
+        let mixture = super::mixture(&[1.0, 3.0], vec![gaussian(-4.0), gaussian(4.0)]);
+        let x = 0.5;
+        let expected = (gaussian(-4.0).pdf(&x) + 3.0 * gaussian(4.0).pdf(&x)) / 4.0;
+        assert!((Distribution::pdf(&mixture, &x) - expected).abs() < 1e-12);
+        assert!((mixture.cdf(&0.0).unwrap() - 0.25).abs() < 1e-4);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let right = (mixture.sample_iter(&mut gen).take(20000))
+            .filter(|x| *x > 0.0)
+            .count();
+        assert!((right as f64 / 20000.0 - 0.75).abs() < 0.02);
+    }
+
+    #[test]
+    fn von_mises() {
+        use std::f64::consts::PI;
+        assert!((ln_bessel_i0(1.0) - 1.2660658777520082f64.ln()).abs() < 1e-12);
+        assert!((ln_bessel_i0(20.0) - 4.355828255955353e7f64.ln()).abs() < 1e-12);
+        assert!((ln_bessel_i0(60.0) - 57.0359901896551).abs() < 1e-12);
+        for kappa in [0.5, 4.0, 80.0] {
+            let von_mises = univar::von_mises(3.0, kappa);
+            let h = 2.0 * PI / 10000.0;
+            let mass: f64 = (0..10000)
+                .map(|i| von_mises.pdf(&circular::Angle(-PI + i as f64 * h)) * h)
+                .sum();
+            assert!((mass - 1.0).abs() < 1e-9);
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let (s, c) = (von_mises.sample_iter(&mut gen).take(10000))
+                .fold((0.0, 0.0), |(s, c), x| (s + x.0.sin(), c + x.0.cos()));
+            assert!((circular::Angle::new(f64::atan2(s, c) - 3.0).0).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn bernoulli() {
+        let bernoulli = univar::bernoulli(0.3);
+        assert!((bernoulli.pdf(&true) - 0.3).abs() < 1e-12);
+        assert!((bernoulli.cdf(&false).unwrap() - 0.7).abs() < 1e-12);
+        assert_eq!(bernoulli.quantile(0.5), Some(false));
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let hits = bernoulli
+            .sample_iter(&mut gen)
+            .take(20000)
+            .filter(|x| *x)
+            .count();
+        assert!((hits as f64 / 20000.0 - 0.3).abs() < 0.02);
+    }
+
+    #[test]
+    fn negative_binomial() {
+        let negative_binomial = univar::negative_binomial(3.0, 0.4);
+        let pmf = |k: u64| Distribution::<u64>::pdf(&negative_binomial, &k);
+        assert!((pmf(2) - 6.0 * 0.4f64.powi(3) * 0.36).abs() < 1e-12);
+        let cdf: f64 = (0..=5).map(pmf).sum();
+        assert!((Distribution::<u64>::cdf(&negative_binomial, &5).unwrap() - cdf).abs() < 1e-12);
+        let degenerate = univar::negative_binomial(3.0, 1.0);
+        assert!((Distribution::<u64>::pdf(&degenerate, &0) - 1.0).abs() < 1e-12);
+        assert_eq!(Distribution::<u64>::pdf(&degenerate, &2), 0.0);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = Distribution::<u64>::sample_iter(&negative_binomial, &mut gen)
+            .take(20000)
+            .sum::<u64>() as f64
+            / 20000.0;
+        assert!((mean - 4.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn geometric() {
+        let geometric = univar::geometric(0.25);
+        let pmf = |k: u64| Distribution::<u64>::pdf(&geometric, &k);
+        assert!((pmf(2) - 0.25 * 0.75 * 0.75).abs() < 1e-12);
+        for k in 0..10u64 {
+            let cdf: f64 = (0..=k).map(pmf).sum();
+            let p = Distribution::<u64>::cdf(&geometric, &k).unwrap();
+            assert!((p - cdf).abs() < 1e-12);
+            assert_eq!(geometric.quantile(p - 1e-9), Some(k));
+        }
+        let degenerate = univar::geometric(1.0);
+        assert_eq!(Distribution::<u64>::pdf(&degenerate, &0), 1.0);
+        assert_eq!(Distribution::<u64>::pdf(&degenerate, &2), 0.0);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = Distribution::<u64>::sample_iter(&geometric, &mut gen)
+            .take(20000)
+            .sum::<u64>() as f64
+            / 20000.0;
+        assert!((mean - 3.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn weibull() {
+        let weibull = univar::weibull(1.0, 0.5);
+        let exponential = univar::exponential(2.0);
+        for x in [0.0, 0.1, 1.0, 3.0] {
+            assert!((weibull.log_pdf(&x) - exponential.log_pdf(&x)).abs() < 1e-12);
+        }
+        assert_eq!(univar::weibull(0.5, 1.0).log_pdf(&0.0), f64::INFINITY);
+        assert_eq!(univar::weibull(2.5, 1.0).log_pdf(&0.0), f64::NEG_INFINITY);
+        let weibull = univar::weibull(2.5, 3.0);
+        for x in [0.5, 2.0, 6.0] {
+            let p = weibull.cdf(&x).unwrap();
+            assert!((weibull.quantile(p).unwrap() - x).abs() < 1e-9);
+        }
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = weibull.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - 3.0 * ln_gamma(1.4).exp()).abs() < 0.05);
+    }
+
+    #[test]
+    fn pareto() {
+        let pareto = univar::pareto(2.0, 3.0);
+        let generalized = univar::generalized_pareto(2.0, 2.0 / 3.0, 1.0 / 3.0);
+        for x in [2.5, 4.0, 10.0] {
+            assert!((pareto.log_pdf(&x) - generalized.log_pdf(&x)).abs() < 1e-12);
+            let p = pareto.cdf(&x).unwrap();
+            assert!((generalized.cdf(&x).unwrap() - p).abs() < 1e-12);
+            assert!((pareto.quantile(p).unwrap() - x).abs() < 1e-9);
+        }
+        let bounded = univar::generalized_pareto(0.0, 1.0, -0.5);
+        assert_eq!(bounded.pdf(&2.5), 0.0);
+        assert_eq!(bounded.cdf(&2.5), Some(1.0));
+        let exponential = univar::generalized_pareto(0.0, 0.5, 0.0);
+        assert!((exponential.log_pdf(&1.0) - univar::exponential(2.0).log_pdf(&1.0)).abs() < 1e-12);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = pareto.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - 3.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn laplace() {
+        let laplace = univar::laplace(1.0, 2.0);
+        assert!((laplace.pdf(&3.0) - (-1.0f64).exp() / 4.0).abs() < 1e-12);
+        for x in [-4.0, 1.0, 2.5] {
+            let p = laplace.cdf(&x).unwrap();
+            assert!((laplace.quantile(p).unwrap() - x).abs() < 1e-9);
+        }
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let xs: Vec<f64> = laplace.sample_iter(&mut gen).take(20000).collect();
+        let mad = xs.iter().map(|x| (x - 1.0).abs()).sum::<f64>() / 20000.0;
+        assert!((mad - 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn chi_squared() {
+        let chi2 = univar::chi_squared(2.0);
+        assert!((chi2.cdf(&3.0).unwrap() - (1.0 - (-1.5f64).exp())).abs() < 1e-12);
+        assert!((chi2.log_pdf(&3.0) - univar::exponential(0.5).log_pdf(&3.0)).abs() < 1e-12);
+
+        // `t²` with `nu` degrees of freedom is `F(1, nu)`
+        let (f, t) = (univar::fisher_f(1.0, 5.0), univar::student_t(5.0, 0.0, 1.0));
+        let x = 1.7f64;
+        assert!((f.pdf(&(x * x)) - t.pdf(&x) / x).abs() < 1e-12);
+        assert!((f.cdf(&(x * x)).unwrap() - (2.0 * t.cdf(&x).unwrap() - 1.0)).abs() < 1e-9);
+
+        let inverse = univar::inverse_gamma(3.0, 2.0);
+        let gamma = univar::gamma(3.0, 2.0);
+        let y = 0.8f64;
+        assert!((inverse.pdf(&y) - gamma.pdf(&(1.0 / y)) / (y * y)).abs() < 1e-12);
+        assert!((inverse.cdf(&y).unwrap() + gamma.cdf(&(1.0 / y)).unwrap() - 1.0).abs() < 1e-12);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = inverse.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn multivariate_t() {
+        let t = multivar::student_t(
+            4.0,
+            nd::Array1::from(vec![1.0]),
+            nd::Array2::from_elem((1, 1), 9.0),
+        );
+        let x = nd::Array1::from(vec![2.5]);
+        assert!((t.log_pdf(&x) - univar::student_t(4.0, 1.0, 3.0).log_pdf(&2.5)).abs() < 1e-12);
+        let draws = t.sample_matrix(20000, &mut rand::rngs::StdRng::seed_from_u64(0));
+        assert!((draws.column(0).iter().sum::<f64>() / 20000.0 - 1.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn car() {
+        let w = nd::Array2::from_shape_fn((3, 3), |(i, j)| (i != j) as u8 as f64);
+        let x = nd::Array1::from(vec![1.0, 0.0, 0.0]);
+        let car = multivar::car(w.clone(), -1.5, 1.0);
+        assert!((car(&x) - (-1.0f64).exp()).abs() < 1e-12);
+        let icar = multivar::icar(w, 1.0);
+        let x = nd::Array1::from(vec![1.0, -1.0, 0.0]);
+        assert!((icar(&x) - (-3.0f64).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn multivariate_gaussian() {
+        let mean = nd::Array1::from(vec![1.0, -2.0]);
+        let cov = nd::Array2::from_shape_vec((2, 2), vec![2.0, 0.6, 0.6, 1.0]).unwrap();
+        let gaussian = multivar::Gaussian::new(mean.clone(), cov.clone());
+        let approx = approx::Gaussian::new(mean.clone(), cov.clone());
+        let x = nd::Array1::from(vec![0.5, -1.0]);
+        let norm = 2.0 * std::f64::consts::PI * (2.0f64 - 0.36).sqrt();
+        assert!((gaussian.pdf(&x) - approx.pdf()(&x) / norm).abs() < 1e-12);
+        let gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let xs: Vec<_> = multivar::gaussian_exact(mean, cov)
+            .sample_with_rng(|_: &nd::Array1<f64>| 1.0, gen)
+            .take(20000)
+            .collect();
+        let cross = xs.iter().map(|x| (x[0] - 1.0) * (x[1] + 2.0)).sum::<f64>() / 20000.0;
+        assert!((cross - 0.6).abs() < 0.05);
+    }
+
+    #[test]
+    fn empirical() {
+        let xs = [
+            modular::Z::<4>(0),
+            modular::Z(1),
+            modular::Z(1),
+            modular::Z(3),
+        ];
+        let empirical = super::empirical(&xs);
+        assert!((empirical.pdf(&modular::Z(1)) - 0.5).abs() < 1e-12);
+        assert_eq!(empirical.pdf(&modular::Z(2)), 0.0);
+        assert!((empirical.cdf(&modular::Z(1)).unwrap() - 0.75).abs() < 1e-12);
+        let smoothed = empirical.smoothing(1.0);
+        assert!((smoothed.pdf(&modular::Z(2)) - 0.125).abs() < 1e-12);
+        assert!((smoothed.cdf(&modular::Z(2)).unwrap() - 0.75).abs() < 1e-12);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let twos = (smoothed.sample_iter(&mut gen).take(20000))
+            .filter(|x| x.0 == 2)
+            .count();
+        assert!((twos as f64 / 20000.0 - 0.125).abs() < 0.01);
+    }
+
+    #[test]
+    fn zipf() {
+        for s in [1.0, 1.5] {
+            let zipf = univar::zipf(s, 50);
+            let mass: f64 = (0..=60u64).map(|k| zipf.pdf(&k)).sum();
+            assert!((mass - 1.0).abs() < 1e-12);
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let ones = Distribution::<u64>::sample_iter(&zipf, &mut gen)
+                .take(20000)
+                .filter(|k| *k == 1)
+                .count();
+            assert!((ones as f64 / 20000.0 - zipf.pdf(&1u64)).abs() < 0.01);
+            assert!((Distribution::<u64>::cdf(&zipf, &50).unwrap() - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn hypergeometric() {
+        let hypergeometric = univar::hypergeometric(20, 7, 12);
+        let pmf = |k: u64| Distribution::<u64>::pdf(&hypergeometric, &k);
+        assert!((pmf(4) - 35.0 * 1287.0 / 125970.0).abs() < 1e-12);
+        assert_eq!(pmf(8), 0.0);
+        let mass: f64 = (0..=12).map(pmf).sum();
+        assert!((mass - 1.0).abs() < 1e-12);
+        let large = univar::hypergeometric(100000, 40000, 500);
+        assert!(Distribution::<u64>::pdf(&large, &200).is_finite());
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = Distribution::<u64>::sample_iter(&hypergeometric, &mut gen)
+            .take(20000)
+            .sum::<u64>() as f64
+            / 20000.0;
+        assert!((mean - 4.2).abs() < 0.05);
+    }
+
+    #[test]
+    fn extreme_value() {
+        let gumbel = univar::gumbel(1.0, 2.0);
+        assert!((gumbel.cdf(&1.0).unwrap() - (-1.0f64).exp()).abs() < 1e-12);
+        let frechet = univar::frechet(3.0, 0.0, 2.0);
+        let x = 2.5f64;
+        assert!((frechet.cdf(&x).unwrap() - (-(x / 2.0).powf(-3.0)).exp()).abs() < 1e-12);
+        assert_eq!(frechet.cdf(&-1.0), Some(0.0));
+        let weibull = univar::weibull_max(2.0, 1.0, 0.5);
+        let y = 0.7f64;
+        assert!((weibull.cdf(&y).unwrap() - (-((1.0 - y) / 0.5).powi(2)).exp()).abs() < 1e-12);
+        assert_eq!(weibull.pdf(&1.5), 0.0);
+        for gev in [gumbel, frechet, weibull] {
+            for p in [0.1, 0.5, 0.9] {
+                let q = gev.quantile(p).unwrap();
+                assert!((gev.cdf(&q).unwrap() - p).abs() < 1e-9);
+            }
+        }
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let gumbel = univar::gumbel(1.0, 2.0);
+        let mean = gumbel.sample_iter(&mut gen).take(20000).sum::<f64>() / 20000.0;
+        assert!((mean - (1.0 + 2.0 * 0.5772156649)).abs() < 0.1);
+    }
+
+    #[test]
+    fn log_density() {
+        let proposal = perturb::<f64>(0.5);
+        let xs: Vec<f64> = sampler::univar::Metropolis::builder()
+            .proposal(proposal)
+            .build()
+            .unwrap()
+            .scale::<scale::Log>()
+            .burn(5000)
+            .sample_with_rng(
+                univar::gaussian_log(200.0, 1.0),
+                rand::rngs::StdRng::seed_from_u64(0),
+            )
+            .take(5000)
+            .collect();
+        assert!((xs.iter().sum::<f64>() / 5000.0 - 200.0).abs() < 0.2);
+        let gaussian = family::Gaussian {
+            mu: 200.0,
+            sigma: 1.0,
+        };
+        let log = Log(gaussian.clone()).density();
+        assert!((log(&0.0) - Distribution::log_pdf(&gaussian, &0.0)).abs() < 1e-12);
+        assert!(log(&0.0).is_finite());
+    }
+
+    #[test]
+    fn product() {
+        let gaussian = family::Gaussian {
+            mu: 0.0,
+            sigma: 1.0,
+        };
+        let exponential = univar::exponential(2.0);
+        let joint = super::product((gaussian.clone(), exponential.clone()));
+        let x = (0.3, 1.2);
+        let expected = Distribution::log_pdf(&gaussian, &0.3) + exponential.log_pdf(&1.2);
+        assert!((joint.log_pdf(&x) - expected).abs() < 1e-12);
+        assert!((joint.cdf(&(0.0, f64::INFINITY)).unwrap() - 0.5).abs() < 1e-6);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = (joint.sample_iter(&mut gen).take(20000))
+            .map(|(_, y)| y)
+            .sum::<f64>()
+            / 20000.0;
+        assert!((mean - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn condition() {
+        let positive = super::condition(univar::gaussian_normalized(0.0, 1.0), |x: &f64| *x > 0.0);
+        assert_eq!(positive(&-1.0), 0.0);
+        let norm = (2.0 * std::f64::consts::PI).sqrt();
+        assert!((positive(&1.0) - (-0.5f64).exp() / norm).abs() < 1e-12);
+        let positive =
+            scaled_condition::<scale::Log, _, _, _>(univar::gaussian_log(0.0, 1.0), |x: &f64| {
+                *x > 0.0
+            });
+        assert_eq!(positive(&-1.0), f64::NEG_INFINITY);
+        assert!((positive(&1.0) - (-0.5 - norm.ln())).abs() < 1e-12);
+
+        let joint = |xs: &nd::Array1<f64>| xs[0] + 10.0 * xs[1] + 100.0 * xs[2];
+        let slice = slice_condition(joint, &[(1, 2.0)]);
+        assert_eq!(slice(&nd::Array1::from(vec![1.0, 3.0])), 321.0);
+    }
+
+    #[test]
+    fn marginalize() {
+        let joint = |xs: &nd::Array1<modular::Z<3>>| (xs[0].0 + 3 * xs[1].0) as f64;
+        let marginal = super::marginalize(joint, &[1]);
+        assert_eq!(marginal(&nd::Array1::from(vec![modular::Z(2)])), 15.0);
+
+        let gaussian = |xs: &nd::Array1<f64>| (-(xs[0] * xs[0] + xs[1] * xs[1]) / 2.0).exp();
+        let marginal = marginalize_quadrature(gaussian, &[0], -10.0, 10.0, 200);
+        let x = nd::Array1::from(vec![1.0]);
+        let expected = (2.0 * std::f64::consts::PI).sqrt() * (-0.5f64).exp();
+        assert!((marginal(&x) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn convolve() {
+        let die = univar::categorical::<7>(&[0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        let dice: univar::Categorical<13> = super::convolve(&die, &die);
+        for k in 0..13 {
+            let ways = 6.0 - (k as f64 - 7.0).abs();
+            assert!((dice.pdf(&modular::Z(k)) - ways.max(0.0) / 36.0).abs() < 1e-12);
+        }
+        let (a, b) = (univar::exponential(1.0), univar::exponential(1.0));
+        let sum = convolve_sampled(&a, &b, 20000);
+        let erlang = univar::gamma(2.0, 1.0);
+        assert!((sum.cdf(&2.0).unwrap() - erlang.cdf(&2.0).unwrap()).abs() < 0.02);
+    }
+
+    #[test]
+    #[should_panic(expected = "sum exceeds the support")]
+    fn convolve_wrapping() {
+        let die = univar::categorical::<7>(&[0.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0]);
+        let _: univar::Categorical<7> = super::convolve(&die, &die);
+    }
+
+    #[test]
+    fn rand_bridge() {
+        use rand::distributions::Distribution as _;
+        let gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let xs: Vec<f64> = from_rand(rand::distributions::Standard)
+            .sample_with_rng(|_: &f64| 1.0, gen)
+            .take(10000)
+            .collect();
+        assert!(xs.iter().all(|x| (0.0..1.0).contains(x)));
+        assert!((xs.iter().sum::<f64>() / 10000.0 - 0.5).abs() < 0.02);
+
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let target =
+            sampler::univar::Icdf::new().target(|z: &modular::Z<4>| [0.0, 1.0, 0.0, 3.0][z.0]);
+        let zs: Vec<usize> = (0..1000).map(|_| target.sample(&mut gen).0).collect();
+        assert!(zs.iter().all(|z| *z == 1 || *z == 3));
+        assert!(zs.iter().filter(|z| **z == 3).count() > 600);
+    }
+
+    #[test]
+    fn exponential() {
+        let exponential = univar::exponential(2.0);
+        assert!((exponential.pdf(&1.0) - 2.0 * (-2.0f64).exp()).abs() < 1e-12);
+        assert_eq!(exponential.pdf(&-1.0), 0.0);
+        let q = exponential.quantile(0.3).unwrap();
+        assert!((exponential.cdf(&q).unwrap() - 0.3).abs() < 1e-12);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = (0..20000)
+            .map(|_| exponential.sample(&mut gen).unwrap())
+            .sum::<f64>()
+            / 20000.0;
+        assert!((mean - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn poisson() {
+        let poisson = univar::poisson(4.0);
+        let pmf = |k: i32| 4f64.powi(k) * (-4.0f64).exp() / (1..=k).product::<i32>() as f64;
+        assert!(
+            (Distribution::<modular::Z<16>>::pdf(&poisson, &modular::Z(3)) - pmf(3)).abs() < 1e-12
+        );
+        assert!(
+            (Distribution::<u32>::cdf(&poisson, &2).unwrap() - (0..=2).map(pmf).sum::<f64>()).abs()
+                < 1e-9
+        );
+        assert_eq!(Distribution::<u32>::quantile(&poisson, 0.5), Some(4));
+
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        for lambda in [4.0, 40.0] {
+            let poisson = univar::poisson(lambda);
+            let mean = (0..20000)
+                .map(|_| Distribution::<u64>::sample(&poisson, &mut gen).unwrap() as f64)
+                .sum::<f64>()
+                / 20000.0;
+            assert!((mean - lambda).abs() < 0.03 * lambda);
+        }
+    }
+
+    #[test]
+    fn binomial() {
+        let binomial = univar::binomial(10, 0.3);
+        let pmf = |k: u64| (Distribution::<u64>::pdf(&binomial, &k));
+        assert!((pmf(3) - 120.0 * 0.3f64.powi(3) * 0.7f64.powi(7)).abs() < 1e-12);
+        assert!(
+            (Distribution::<u64>::cdf(&binomial, &3).unwrap() - (0..=3).map(pmf).sum::<f64>())
+                .abs()
+                < 1e-9
+        );
+        let large = univar::binomial(1000, 0.5);
+        assert!(Distribution::<u64>::log_pdf(&large, &500).is_finite());
+        let degenerate = |p: f64, k: u64| Distribution::<u64>::pdf(&univar::binomial(10, p), &k);
+        assert!((degenerate(0.0, 0) - 1.0).abs() < 1e-12);
+        assert!((degenerate(1.0, 10) - 1.0).abs() < 1e-12);
+        assert_eq!(degenerate(1.0, 3), 0.0);
+
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = (0..5000)
+            .map(|_| Distribution::<u64>::sample(&large, &mut gen).unwrap() as f64)
+            .sum::<f64>()
+            / 5000.0;
+        assert!((mean - 500.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn categorical() {
+        let categorical = univar::categorical::<4>(&[1.0, 2.0, 3.0, 4.0]);
+        assert!((categorical.pdf(&modular::Z(2)) - 0.3).abs() < 1e-12);
+        assert!((categorical.cdf(&modular::Z(1)).unwrap() - 0.3).abs() < 1e-12);
+        assert_eq!(categorical.quantile(0.5), Some(modular::Z(2)));
+
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mut counts = [0.0; 4];
+        (0..40000).for_each(|_| counts[categorical.sample(&mut gen).unwrap().0] += 1.0 / 40000.0);
+        for (k, c) in counts.iter().enumerate() {
+            assert!((c - (k + 1) as f64 / 10.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn gamma() {
+        let gamma = univar::gamma(3.0, 2.0);
+        assert!((gamma.pdf(&1.0) - 4.0 * (-2.0f64).exp()).abs() < 1e-9);
+        let cdf = |x: f64| 1.0 - (-2.0 * x).exp() * (1.0 + 2.0 * x + 2.0 * x * x);
+        for x in [0.3, 1.0, 4.0] {
+            assert!((gamma.cdf(&x).unwrap() - cdf(x)).abs() < 1e-9);
+        }
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = (0..20000)
+            .map(|_| gamma.sample(&mut gen).unwrap())
+            .sum::<f64>()
+            / 20000.0;
+        assert!((mean - 1.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn beta() {
+        let beta = univar::beta(2.0, 3.0);
+        assert!((beta.pdf(&0.5) - 1.5).abs() < 1e-9);
+        assert!((beta.cdf(&0.5).unwrap() - 11.0 / 16.0).abs() < 1e-9);
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let mean = (0..20000)
+            .map(|_| beta.sample(&mut gen).unwrap())
+            .sum::<f64>()
+            / 20000.0;
+        assert!((mean - 0.4).abs() < 0.01);
+    }
 }
@@ -0,0 +1,193 @@
+use super::*;
+
+#[doc = "Likelihood factor on a linear projection `a·x` of the latent"]
+pub trait Factor {
+    #[doc = "Normalizer, mean and variance of the factor tilted by `N(mu, var)`"]
+    fn moments(&self, mu: f64, var: f64) -> (f64, f64, f64);
+}
+
+#[doc = "Probit factor `Φ(y z)` with label `y` in {-1, 1}"]
+pub struct Probit(pub f64);
+impl Factor for Probit {
+    fn moments(&self, mu: f64, var: f64) -> (f64, f64, f64) {
+        let y = self.0;
+        let scale = (1.0 + var).sqrt();
+        let z = y * mu / scale;
+        let cdf = dist::erfc(-z / 2f64.sqrt()) / 2.0;
+        let ratio = (-z * z / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt() / cdf;
+        (
+            cdf,
+            mu + y * var * ratio / scale,
+            var - var * var * ratio * (z + ratio) / (1.0 + var),
+        )
+    }
+}
+
+#[doc = "Factor given by its (unnormalized) density, tilted by quadrature"]
+pub struct Numeric<F: Fn(f64) -> f64>(pub F);
+impl<F: Fn(f64) -> f64> Factor for Numeric<F> {
+    fn moments(&self, mu: f64, var: f64) -> (f64, f64, f64) {
+        let (n, sigma) = (400, var.sqrt());
+        let h = 16.0 * sigma / n as f64;
+        let (mut z0, mut z1, mut z2) = (0.0, 0.0, 0.0);
+        for i in 0..=n {
+            let z = mu - 8.0 * sigma + i as f64 * h;
+            let simpson = if i == 0 || i == n {
+                1.0
+            } else {
+                2.0 + 2.0 * (i % 2) as f64
+            };
+            let w = simpson * (self.0)(z) * (-(z - mu).powi(2) / (2.0 * var)).exp();
+            z0 += w;
+            z1 += w * z;
+            z2 += w * z * z;
+        }
+        let mean = z1 / z0;
+        let norm = z0 * h / 3.0 / (2.0 * std::f64::consts::PI * var).sqrt();
+        (norm, mean, z2 / z0 - mean * mean)
+    }
+}
+
+#[doc = "Gaussian prior times factors on linear projections"]
+pub struct Model<F: Factor> {
+    pub mean: na::DVector<f64>,
+    pub cov: na::DMatrix<f64>,
+    pub factors: Vec<(na::DVector<f64>, F)>,
+}
+impl<F: Factor> Model<F> {
+    #[allow(unused)]
+    pub fn new(mean: na::DVector<f64>, cov: na::DMatrix<f64>) -> Self {
+        assert_eq!(cov.shape(), (mean.len(), mean.len()), "covariance mismatch");
+        Model {
+            mean,
+            cov,
+            factors: vec![],
+        }
+    }
+
+    pub fn factor(mut self, a: na::DVector<f64>, factor: F) -> Self {
+        assert_eq!(a.len(), self.mean.len(), "projection mismatch");
+        self.factors.push((a, factor));
+        self
+    }
+}
+
+#[doc = "Gaussian approximation with its site parameters `(precision, shift)`"]
+#[derive(Clone, Debug)]
+pub struct Approximation {
+    pub mean: na::DVector<f64>,
+    pub cov: na::DMatrix<f64>,
+    pub sites: Vec<(f64, f64)>,
+}
+
+#[doc = "Expectation propagation with gaussian sites"]
+pub struct Propagation {
+    pub sweeps: usize,
+    pub damping: f64,
+}
+impl Propagation {
+    #[allow(unused)]
+    pub fn new(sweeps: usize, damping: f64) -> Self {
+        assert!(sweeps > 0, "no sweeps");
+        assert!(0.0 < damping && damping <= 1.0, "damping isn't in (0, 1]");
+        Propagation { sweeps, damping }
+    }
+
+    pub fn fit<F: Factor>(&self, model: &Model<F>) -> Approximation {
+        let prior = (model.cov.clone().cholesky())
+            .expect("covariance isn't positive definite")
+            .inverse();
+        let shift = &prior * &model.mean;
+        let mut sites = vec![(0.0, 0.0); model.factors.len()];
+        let (mut mean, mut cov) = (model.mean.clone(), model.cov.clone());
+        for _ in 0..self.sweeps {
+            for (i, (a, factor)) in model.factors.iter().enumerate() {
+                let (tau, nu) = sites[i];
+                let (mu, var) = (a.dot(&mean), a.dot(&(&cov * a)));
+                let (tau_c, nu_c) = (1.0 / var - tau, mu / var - nu);
+                if tau_c <= 0.0 {
+                    continue;
+                }
+
+                let (_, mu_h, var_h) = factor.moments(nu_c / tau_c, 1.0 / tau_c);
+                let (tau_h, nu_h) = (1.0 / var_h - tau_c, mu_h / var_h - nu_c);
+                sites[i] = (
+                    (1.0 - self.damping) * tau + self.damping * tau_h.max(0.0),
+                    (1.0 - self.damping) * nu + self.damping * nu_h,
+                );
+
+                let (precision, shift) = (model.factors.iter().zip(sites.iter())).fold(
+                    (prior.clone(), shift.clone()),
+                    |(p, s), ((a, _), (tau, nu))| (p + a * a.transpose() * *tau, s + a * *nu),
+                );
+                cov = (precision.cholesky())
+                    .expect("precision isn't positive definite")
+                    .inverse();
+                mean = &cov * shift;
+            }
+        }
+        Approximation { mean, cov, sites }
+    }
+}
+
+#[doc = "Assumed density filtering, a single pass of expectation propagation"]
+pub fn adf<F: Factor>(model: &Model<F>) -> Approximation {
+    Propagation::new(1, 1.0).fit(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probit() {
+        let cdf = |z: f64| dist::erfc(-z / 2f64.sqrt()) / 2.0;
+        for &(mu, var) in [(0.0, 1.0), (1.5, 0.5), (-2.0, 3.0)].iter() {
+            let (z, m, v) = Probit(1.0).moments(mu, var);
+            let (zq, mq, vq) = Numeric(cdf).moments(mu, var);
+            assert!((z - zq).abs() < 1e-6);
+            assert!((m - mq).abs() < 1e-6);
+            assert!((v - vq).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn classification() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+        let cdf = |z: f64| dist::erfc(-z / 2f64.sqrt()) / 2.0;
+        let data: Vec<(f64, f64)> = (0..20)
+            .map(|_| {
+                let x = dist::normal(&mut gen);
+                let y = if 1.5 * x + dist::normal(&mut gen) > 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                (x, y)
+            })
+            .collect();
+        let model = (data.iter()).fold(
+            Model::new(na::DVector::zeros(1), na::DMatrix::identity(1, 1)),
+            |model, (x, y)| model.factor(na::DVector::from_element(1, *x), Probit(*y)),
+        );
+
+        // posterior moments of the weight by quadrature
+        let grid = || (0..=1600).map(|i| -8.0 + i as f64 * 0.01);
+        let ln_post =
+            |w: f64| -w * w / 2.0 + data.iter().map(|(x, y)| cdf(y * x * w).ln()).sum::<f64>();
+        let max = grid().map(ln_post).fold(f64::NEG_INFINITY, f64::max);
+        let (z0, z1, z2) = grid().fold((0.0, 0.0, 0.0), |(z0, z1, z2), w| {
+            let p = (ln_post(w) - max).exp();
+            (z0 + p, z1 + p * w, z2 + p * w * w)
+        });
+        let (mean, var) = (z1 / z0, z2 / z0 - (z1 / z0).powi(2));
+
+        let ep = Propagation::new(10, 1.0).fit(&model);
+        assert!((ep.mean[0] - mean).abs() < 0.02);
+        assert!((ep.cov[(0, 0)] / var - 1.0).abs() < 0.1);
+        let adf = adf(&model);
+        assert!((adf.mean[0] - mean).abs() < 0.1);
+        assert!((adf.cov[(0, 0)] / var - 1.0).abs() < 0.2);
+    }
+}
@@ -0,0 +1,23 @@
+#[doc = "Invalid configuration, naming the offending parameter"]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Missing(&'static str),
+    NotPositive(&'static str),
+    OutOfRange(&'static str),
+    Mismatch(&'static str),
+    NotPositiveDefinite(&'static str),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Missing(name) => write!(f, "{} isn't set", name),
+            Error::NotPositive(name) => write!(f, "{} isn't positive", name),
+            Error::OutOfRange(name) => write!(f, "{} is out of range", name),
+            Error::Mismatch(name) => write!(f, "{} mismatch", name),
+            Error::NotPositiveDefinite(name) => write!(f, "{} isn't positive definite", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
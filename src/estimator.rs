@@ -0,0 +1,203 @@
+#[doc = "Ratio `Z1 / Z2` of normalizing constants by iterative bridge sampling"]
+pub fn bridge<D, P1: Fn(&D) -> f64, P2: Fn(&D) -> f64>(
+    pdf1: P1,
+    pdf2: P2,
+    xs1: &[D],
+    xs2: &[D],
+) -> f64 {
+    assert!(!xs1.is_empty() && !xs2.is_empty(), "no samples");
+    let l1: Vec<f64> = xs1.iter().map(|x| pdf1(x) / pdf2(x)).collect();
+    let l2: Vec<f64> = xs2.iter().map(|x| pdf1(x) / pdf2(x)).collect();
+    let (n1, n2) = (l1.len() as f64, l2.len() as f64);
+    let (s1, s2) = (n1 / (n1 + n2), n2 / (n1 + n2));
+
+    let mut r = 1.0;
+    for _ in 0..1000 {
+        let num = l2.iter().map(|l| l / (s1 * l + s2 * r)).sum::<f64>() / n2;
+        let den = l1.iter().map(|l| 1.0 / (s1 * l + s2 * r)).sum::<f64>() / n1;
+        let next = num / den;
+        if (next - r).abs() <= 1e-10 * r {
+            return next;
+        }
+        r = next;
+    }
+    r
+}
+
+#[doc = "Monte Carlo estimate with its standard error"]
+#[derive(Clone, Debug)]
+pub struct Estimate {
+    pub mean: f64,
+    pub mcse: f64,
+    pub draws: usize,
+}
+
+#[doc = "Batch-means estimate with `sqrt(n)` batches of `sqrt(n)` draws"]
+pub fn batch_means(ys: &[f64]) -> Estimate {
+    let n = ys.len();
+    let b = (n as f64).sqrt() as usize;
+    assert!(b > 1, "too few draws");
+    let a = n / b;
+    let mean = ys.iter().sum::<f64>() / n as f64;
+    let batches: Vec<f64> = (ys.chunks_exact(b))
+        .map(|c| c.iter().sum::<f64>() / b as f64)
+        .collect();
+    let var = b as f64 * batches.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / (a - 1) as f64;
+    Estimate {
+        mean,
+        mcse: (var / n as f64).sqrt(),
+        draws: n,
+    }
+}
+
+#[doc = "Draw until the batch-means standard error of `statistic` falls below `tolerance`"]
+pub fn until_precise<D, I: Iterator<Item = D>, S: FnMut(&D) -> f64>(
+    samples: I,
+    mut statistic: S,
+    tolerance: f64,
+    max: usize,
+) -> Estimate {
+    assert!(tolerance > 0.0, "tolerance isn't positive");
+    let mut ys = vec![];
+    let mut check = 100;
+    for x in samples.take(max) {
+        ys.push(statistic(&x));
+        if ys.len() == check {
+            if batch_means(&ys).mcse < tolerance {
+                break;
+            }
+            check += check / 4;
+        }
+    }
+    batch_means(&ys)
+}
+
+#[doc = "Unconstrained least-squares fit of `p_nu / p_de` by gaussian kernels (uLSIF)"]
+#[derive(Clone, Debug)]
+pub struct Ulsif {
+    pub sigma: f64,
+    pub lambda: f64,
+    pub centers: usize,
+}
+impl Ulsif {
+    #[allow(unused)]
+    pub fn new(sigma: f64, lambda: f64) -> Self {
+        assert!(sigma > 0.0, "sigma isn't positive");
+        assert!(lambda > 0.0, "lambda isn't positive");
+        Ulsif {
+            sigma,
+            lambda,
+            centers: 100,
+        }
+    }
+
+    pub fn centers(self, centers: usize) -> Self {
+        assert!(centers > 0, "no centers");
+        Ulsif { centers, ..self }
+    }
+
+    pub fn fit(&self, nu: &[nd::Array1<f64>], de: &[nd::Array1<f64>]) -> Ratio {
+        assert!(!nu.is_empty() && !de.is_empty(), "no samples");
+        let stride = (nu.len() / self.centers).max(1);
+        let centers: Vec<nd::Array1<f64>> = nu.iter().step_by(stride).cloned().collect();
+        let mut ratio = Ratio {
+            centers,
+            alpha: vec![],
+            sigma: self.sigma,
+        };
+
+        let b = ratio.centers.len();
+        let (kde, knu): (Vec<_>, Vec<_>) = (
+            de.iter().map(|x| ratio.features(x)).collect(),
+            nu.iter().map(|x| ratio.features(x)).collect(),
+        );
+        let h = na::DMatrix::from_fn(b, b, |l, m| {
+            kde.iter().map(|k| k[l] * k[m]).sum::<f64>() / de.len() as f64
+                + if l == m { self.lambda } else { 0.0 }
+        });
+        let g = na::DVector::from_fn(b, |l, _| {
+            knu.iter().map(|k| k[l]).sum::<f64>() / nu.len() as f64
+        });
+        let alpha = (h.cholesky())
+            .expect("kernel matrix isn't positive definite")
+            .solve(&g);
+        ratio.alpha = alpha.iter().map(|a| a.max(0.0)).collect();
+        ratio
+    }
+}
+
+#[doc = "Fitted density ratio as a nonnegative kernel expansion"]
+#[derive(Clone, Debug)]
+pub struct Ratio {
+    pub centers: Vec<nd::Array1<f64>>,
+    pub alpha: Vec<f64>,
+    pub sigma: f64,
+}
+impl Ratio {
+    pub fn eval(&self, x: &nd::Array1<f64>) -> f64 {
+        (self.features(x).iter())
+            .zip(self.alpha.iter())
+            .map(|(k, a)| k * a)
+            .sum()
+    }
+
+    #[doc = "Self-normalized importance weights of denominator samples"]
+    pub fn weights(&self, xs: &[nd::Array1<f64>]) -> Vec<f64> {
+        let ws: Vec<f64> = xs.iter().map(|x| self.eval(x)).collect();
+        let sum: f64 = ws.iter().sum();
+        assert!(sum > 0.0, "ratio vanishes on all samples");
+        ws.iter().map(|w| w / sum).collect()
+    }
+
+    fn features(&self, x: &nd::Array1<f64>) -> Vec<f64> {
+        (self.centers.iter())
+            .map(|c| {
+                let d = x - c;
+                (-d.dot(&d) / (2.0 * self.sigma.powi(2))).exp()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dist;
+
+    #[test]
+    fn gaussians() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let xs1: Vec<f64> = (0..5000).map(|_| dist::normal(&mut gen)).collect();
+        let xs2: Vec<f64> = (0..5000).map(|_| 2.0 * dist::normal(&mut gen)).collect();
+        let pdf1 = |x: &f64| (-x * x / 2.0).exp();
+        let pdf2 = |x: &f64| (-x * x / 8.0).exp();
+        assert!((bridge(pdf1, pdf2, &xs1, &xs2) - 0.5).abs() < 0.03);
+    }
+
+    #[test]
+    fn precise() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let draws = std::iter::from_fn(|| Some(dist::normal(&mut gen)));
+        let estimate = until_precise(draws, |x| *x, 0.05, 100000);
+        assert!(estimate.mcse < 0.05 && estimate.draws < 100000);
+        assert!(estimate.mean.abs() < 0.25);
+    }
+
+    #[test]
+    fn ratio() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let nu: Vec<nd::Array1<f64>> = (0..1000)
+            .map(|_| nd::Array1::from(vec![dist::normal(&mut gen)]))
+            .collect();
+        let de: Vec<nd::Array1<f64>> = (0..1000)
+            .map(|_| nd::Array1::from(vec![2.0 * dist::normal(&mut gen)]))
+            .collect();
+        let ratio = Ulsif::new(0.5, 0.01).fit(&nu, &de);
+        let at = |x: f64| ratio.eval(&nd::Array1::from(vec![x]));
+        assert!((at(0.0) - 2.0).abs() < 0.5);
+        assert!(at(3.0) < 0.5);
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::Write;
+
+#[doc = "Run configuration, recorded as a manifest next to the chain output"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Experiment {
+    pub target: String,
+    pub sampler: String,
+    pub settings: Vec<(String, String)>,
+    pub seed: Option<u64>,
+    pub burn: usize,
+    pub thin: usize,
+}
+impl Experiment {
+    #[allow(unused)]
+    pub fn new(target: &str, sampler: &str) -> Self {
+        Experiment {
+            target: target.to_string(),
+            sampler: sampler.to_string(),
+            settings: vec![],
+            seed: None,
+            burn: 0,
+            thin: 1,
+        }
+    }
+
+    pub fn setting<V: std::fmt::Debug>(mut self, key: &str, value: V) -> Self {
+        self.settings
+            .push((key.to_string(), format!("{:?}", value)));
+        self
+    }
+
+    pub fn seed(self, seed: u64) -> Self {
+        Experiment {
+            seed: Some(seed),
+            ..self
+        }
+    }
+
+    pub fn burn(self, burn: usize) -> Self {
+        Experiment { burn, ..self }
+    }
+
+    pub fn thin(self, thin: usize) -> Self {
+        assert!(thin > 0, "thin isn't positive");
+        Experiment { thin, ..self }
+    }
+
+    #[doc = "Manifest as JSON, stamped with the crate version"]
+    pub fn manifest(&self) -> String {
+        let settings: Vec<String> = (self.settings.iter())
+            .map(|(k, v)| format!("{}: {}", quote(k), quote(v)))
+            .collect();
+        let seed = self.seed.map_or("null".to_string(), |s| s.to_string());
+        format!(
+            "{{\"version\": {}, \"target\": {}, \"sampler\": {}, \"settings\": {{{}}}, \"seed\": {}, \"burn\": {}, \"thin\": {}}}",
+            quote(env!("CARGO_PKG_VERSION")),
+            quote(&self.target),
+            quote(&self.sampler),
+            settings.join(", "),
+            seed,
+            self.burn,
+            self.thin,
+        )
+    }
+
+    #[doc = "Write `n` kept samples to `path`, one per line, and the manifest to `path.manifest.json`"]
+    pub fn record<D: std::fmt::Debug, I: Iterator<Item = D>>(
+        &self,
+        samples: I,
+        n: usize,
+        path: &std::path::Path,
+    ) -> std::io::Result<()> {
+        let mut manifest = path.as_os_str().to_owned();
+        manifest.push(".manifest.json");
+        std::fs::write(manifest, self.manifest())?;
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        for x in samples.skip(self.burn).step_by(self.thin).take(n) {
+            writeln!(file, "{:?}", x)?;
+        }
+        file.flush()
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest() {
+        let experiment = Experiment::new("gaussian \"standard\"", "metropolis")
+            .setting("step", 0.5)
+            .seed(42)
+            .burn(10)
+            .thin(2);
+        let manifest = experiment.manifest();
+        assert!(manifest.contains("\"target\": \"gaussian \\\"standard\\\"\""));
+        assert!(manifest.contains("\"settings\": {\"step\": \"0.5\"}"));
+        assert!(manifest.contains("\"seed\": 42, \"burn\": 10, \"thin\": 2"));
+
+        let name = format!("probs.experiment.{}.txt", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        experiment.record(0..100, 5, &path).unwrap();
+        let chain = std::fs::read_to_string(&path).unwrap();
+        let mut manifest = path.as_os_str().to_owned();
+        manifest.push(".manifest.json");
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(manifest).unwrap();
+        assert_eq!(chain, "10\n12\n14\n16\n18\n");
+    }
+}
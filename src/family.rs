@@ -0,0 +1,352 @@
+use super::*;
+
+#[doc = "Exponential family `h(x) exp(η·T(x) - A(η))`"]
+pub trait ExponentialFamily: Sized {
+    type Domain;
+    fn statistics(x: &Self::Domain) -> Vec<f64>;
+    fn log_base(x: &Self::Domain) -> f64;
+    fn natural(&self) -> Vec<f64>;
+    fn from_natural(eta: &[f64]) -> Self;
+    fn log_partition(&self) -> f64;
+
+    #[doc = "Expected sufficient statistics, the gradient of the log-partition"]
+    fn expectation(&self) -> Vec<f64>;
+    fn from_expectation(mu: &[f64]) -> Self;
+
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Self::Domain;
+
+    fn log_pdf(&self, x: &Self::Domain) -> f64 {
+        let dot: f64 = (self.natural().iter())
+            .zip(Self::statistics(x))
+            .map(|(eta, t)| eta * t)
+            .sum();
+        Self::log_base(x) + dot - self.log_partition()
+    }
+
+    fn pdf(&self, x: &Self::Domain) -> f64 {
+        self.log_pdf(x).exp()
+    }
+}
+
+#[doc = "Conjugate prior `exp(η·χ - ν A(η))` over natural parameters"]
+#[derive(Clone, Debug)]
+pub struct Conjugate<F: ExponentialFamily> {
+    pub chi: Vec<f64>,
+    pub nu: f64,
+    family: std::marker::PhantomData<F>,
+}
+impl<F: ExponentialFamily> Conjugate<F> {
+    #[allow(unused)]
+    pub fn new(chi: Vec<f64>, nu: f64) -> Self {
+        assert!(nu > 0.0, "nu isn't positive");
+        Conjugate {
+            chi,
+            nu,
+            family: std::marker::PhantomData,
+        }
+    }
+
+    #[doc = "Posterior after observing `xs`"]
+    pub fn observe(mut self, xs: &[F::Domain]) -> Self {
+        for x in xs {
+            (self.chi.iter_mut())
+                .zip(F::statistics(x))
+                .for_each(|(chi, t)| *chi += t);
+        }
+        self.nu += xs.len() as f64;
+        self
+    }
+
+    #[doc = "Most probable member of the family"]
+    pub fn mode(&self) -> F {
+        let mu: Vec<f64> = self.chi.iter().map(|chi| chi / self.nu).collect();
+        F::from_expectation(&mu)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gaussian {
+    pub mu: f64,
+    pub sigma: f64,
+}
+impl ExponentialFamily for Gaussian {
+    type Domain = f64;
+    fn statistics(x: &f64) -> Vec<f64> {
+        vec![*x, x * x]
+    }
+    fn log_base(_: &f64) -> f64 {
+        -(2.0 * std::f64::consts::PI).ln() / 2.0
+    }
+    fn natural(&self) -> Vec<f64> {
+        let var = self.sigma.powi(2);
+        vec![self.mu / var, -0.5 / var]
+    }
+    fn from_natural(eta: &[f64]) -> Self {
+        assert!(eta[1] < 0.0, "precision isn't positive");
+        let var = -0.5 / eta[1];
+        Gaussian {
+            mu: eta[0] * var,
+            sigma: var.sqrt(),
+        }
+    }
+    fn log_partition(&self) -> f64 {
+        self.mu.powi(2) / (2.0 * self.sigma.powi(2)) + self.sigma.ln()
+    }
+    fn expectation(&self) -> Vec<f64> {
+        vec![self.mu, self.mu.powi(2) + self.sigma.powi(2)]
+    }
+    fn from_expectation(mu: &[f64]) -> Self {
+        let var = mu[1] - mu[0].powi(2);
+        assert!(var > 0.0, "variance isn't positive");
+        Gaussian {
+            mu: mu[0],
+            sigma: var.sqrt(),
+        }
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> f64 {
+        self.mu + self.sigma * dist::normal(gen)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gamma {
+    pub shape: f64,
+    pub rate: f64,
+}
+impl ExponentialFamily for Gamma {
+    type Domain = f64;
+    fn statistics(x: &f64) -> Vec<f64> {
+        vec![x.ln(), *x]
+    }
+    fn log_base(_: &f64) -> f64 {
+        0.0
+    }
+    fn natural(&self) -> Vec<f64> {
+        vec![self.shape - 1.0, -self.rate]
+    }
+    fn from_natural(eta: &[f64]) -> Self {
+        assert!(eta[0] > -1.0 && eta[1] < 0.0, "gamma isn't proper");
+        Gamma {
+            shape: eta[0] + 1.0,
+            rate: -eta[1],
+        }
+    }
+    fn log_partition(&self) -> f64 {
+        dist::ln_gamma(self.shape) - self.shape * self.rate.ln()
+    }
+    fn expectation(&self) -> Vec<f64> {
+        vec![
+            dist::digamma(self.shape) - self.rate.ln(),
+            self.shape / self.rate,
+        ]
+    }
+    fn from_expectation(mu: &[f64]) -> Self {
+        // solve ln(shape) - digamma(shape) = s by newton, from Minka's initial guess
+        let s = mu[1].ln() - mu[0];
+        assert!(s > 0.0, "expectation isn't attainable");
+        let mut shape = (3.0 - s + ((s - 3.0).powi(2) + 24.0 * s).sqrt()) / (12.0 * s);
+        for _ in 0..20 {
+            let step =
+                (shape.ln() - dist::digamma(shape) - s) / (1.0 / shape - dist::trigamma(shape));
+            shape = (shape - step).max(shape / 10.0);
+            if step.abs() < 1e-12 * shape {
+                break;
+            }
+        }
+        Gamma {
+            shape,
+            rate: shape / mu[1],
+        }
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> f64 {
+        dist::gamma(gen, self.shape) / self.rate
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exponential {
+    pub rate: f64,
+}
+impl ExponentialFamily for Exponential {
+    type Domain = f64;
+    fn statistics(x: &f64) -> Vec<f64> {
+        vec![*x]
+    }
+    fn log_base(_: &f64) -> f64 {
+        0.0
+    }
+    fn natural(&self) -> Vec<f64> {
+        vec![-self.rate]
+    }
+    fn from_natural(eta: &[f64]) -> Self {
+        assert!(eta[0] < 0.0, "rate isn't positive");
+        Exponential { rate: -eta[0] }
+    }
+    fn log_partition(&self) -> f64 {
+        -self.rate.ln()
+    }
+    fn expectation(&self) -> Vec<f64> {
+        vec![1.0 / self.rate]
+    }
+    fn from_expectation(mu: &[f64]) -> Self {
+        assert!(mu[0] > 0.0, "mean isn't positive");
+        Exponential { rate: 1.0 / mu[0] }
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> f64 {
+        let u: f64 = gen.gen_range(0.0..1.0);
+        -(1.0 - u).ln() / self.rate
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bernoulli {
+    pub p: f64,
+}
+impl ExponentialFamily for Bernoulli {
+    type Domain = bool;
+    fn statistics(x: &bool) -> Vec<f64> {
+        vec![if *x { 1.0 } else { 0.0 }]
+    }
+    fn log_base(_: &bool) -> f64 {
+        0.0
+    }
+    fn natural(&self) -> Vec<f64> {
+        vec![(self.p / (1.0 - self.p)).ln()]
+    }
+    fn from_natural(eta: &[f64]) -> Self {
+        Bernoulli {
+            p: 1.0 / (1.0 + (-eta[0]).exp()),
+        }
+    }
+    fn log_partition(&self) -> f64 {
+        -(1.0 - self.p).ln()
+    }
+    fn expectation(&self) -> Vec<f64> {
+        vec![self.p]
+    }
+    fn from_expectation(mu: &[f64]) -> Self {
+        assert!((0.0..=1.0).contains(&mu[0]), "probability isn't in [0, 1]");
+        Bernoulli { p: mu[0] }
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> bool {
+        gen.gen_range(0.0..1.0) < self.p
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Poisson {
+    pub lambda: f64,
+}
+impl ExponentialFamily for Poisson {
+    type Domain = usize;
+    fn statistics(x: &usize) -> Vec<f64> {
+        vec![*x as f64]
+    }
+    fn log_base(x: &usize) -> f64 {
+        -dist::ln_gamma(*x as f64 + 1.0)
+    }
+    fn natural(&self) -> Vec<f64> {
+        vec![self.lambda.ln()]
+    }
+    fn from_natural(eta: &[f64]) -> Self {
+        Poisson {
+            lambda: eta[0].exp(),
+        }
+    }
+    fn log_partition(&self) -> f64 {
+        self.lambda
+    }
+    fn expectation(&self) -> Vec<f64> {
+        vec![self.lambda]
+    }
+    fn from_expectation(mu: &[f64]) -> Self {
+        assert!(mu[0] > 0.0, "rate isn't positive");
+        Poisson { lambda: mu[0] }
+    }
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> usize {
+        poisson(gen, self.lambda)
+    }
+}
+
+// multiplication method for small rates, transformed rejection (PTRS, Hörmann) otherwise
+fn poisson<R: rand::Rng + ?Sized>(gen: &mut R, lambda: f64) -> usize {
+    if lambda < 10.0 {
+        let (limit, mut prod, mut k) = ((-lambda).exp(), gen.gen_range(0.0..1.0), 0);
+        while prod > limit {
+            prod *= gen.gen_range(0.0..1.0);
+            k += 1;
+        }
+        return k;
+    }
+    let b = 0.931 + 2.53 * lambda.sqrt();
+    let a = -0.059 + 0.02483 * b;
+    let alpha = 1.1239 + 1.1328 / (b - 3.4);
+    let vr = 0.9277 - 3.6224 / (b - 2.0);
+    loop {
+        let u = gen.gen_range(0.0..1.0) - 0.5;
+        let v: f64 = gen.gen_range(0.0..1.0);
+        let us = 0.5 - f64::abs(u);
+        let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+        if us >= 0.07 && v <= vr {
+            return k as usize;
+        }
+        if k < 0.0 || (us < 0.013 && v > us) {
+            continue;
+        }
+        let lhs = v.ln() + alpha.ln() - (a / (us * us) + b).ln();
+        if lhs <= -lambda + k * lambda.ln() - dist::ln_gamma(k + 1.0) {
+            return k as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip<F: ExponentialFamily>(f: F, x: F::Domain) {
+        let g = F::from_natural(&f.natural());
+        let h = F::from_expectation(&f.expectation());
+        assert!((f.log_pdf(&x) - g.log_pdf(&x)).abs() < 1e-9);
+        assert!((f.log_pdf(&x) - h.log_pdf(&x)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn families() {
+        roundtrip(
+            Gaussian {
+                mu: 1.0,
+                sigma: 2.0,
+            },
+            0.5,
+        );
+        roundtrip(
+            Gamma {
+                shape: 2.5,
+                rate: 0.5,
+            },
+            3.0,
+        );
+        roundtrip(
+            Gamma {
+                shape: 0.3,
+                rate: 2.0,
+            },
+            0.1,
+        );
+        roundtrip(Exponential { rate: 1.5 }, 2.0);
+        roundtrip(Bernoulli { p: 0.3 }, true);
+        roundtrip(Poisson { lambda: 4.0 }, 3);
+        let gamma = Gamma {
+            shape: 3.0,
+            rate: 2.0,
+        };
+        assert!((gamma.pdf(&1.0) - 4.0 * (-2.0f64).exp()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn conjugate() {
+        let posterior = Conjugate::<Poisson>::new(vec![1.0], 1.0).observe(&[2, 4, 3]);
+        assert_eq!(posterior.mode(), Poisson { lambda: 2.5 });
+    }
+}
@@ -0,0 +1,248 @@
+use super::*;
+
+pub use kriging::Kriging;
+
+#[doc = "Covariance kernels"]
+pub mod kernel {
+    use super::*;
+
+    fn distance(x: nd::ArrayView1<f64>, y: nd::ArrayView1<f64>) -> f64 {
+        x.iter()
+            .zip(y.iter())
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    pub fn rbf(sigma: f64, scale: f64) -> impl Fn(nd::ArrayView1<f64>, nd::ArrayView1<f64>) -> f64 {
+        move |x, y| sigma.powi(2) * (-distance(x, y).powi(2) / (2.0 * scale.powi(2))).exp()
+    }
+
+    pub fn exponential(
+        sigma: f64,
+        scale: f64,
+    ) -> impl Fn(nd::ArrayView1<f64>, nd::ArrayView1<f64>) -> f64 {
+        move |x, y| sigma.powi(2) * (-distance(x, y) / scale).exp()
+    }
+
+    pub fn matern32(
+        sigma: f64,
+        scale: f64,
+    ) -> impl Fn(nd::ArrayView1<f64>, nd::ArrayView1<f64>) -> f64 {
+        move |x, y| {
+            let r = 3f64.sqrt() * distance(x, y) / scale;
+            sigma.powi(2) * (1.0 + r) * (-r).exp()
+        }
+    }
+
+    #[doc = "Random features approximating the rbf kernel"]
+    pub mod features {
+        use super::*;
+
+        pub struct Fourier {
+            pub omega: nd::Array2<f64>,
+            pub phase: nd::Array1<f64>,
+            pub sigma: f64,
+        }
+        impl Fourier {
+            #[doc = "Random fourier features with gaussian frequencies"]
+            pub fn rbf(dim: usize, features: usize, sigma: f64, scale: f64) -> Self {
+                Fourier::rbf_with_rng(dim, features, sigma, scale, &mut rand::thread_rng())
+            }
+
+            #[doc = "Random fourier features with gaussian frequencies, drawn from `gen`"]
+            pub fn rbf_with_rng<R: rand::Rng + ?Sized>(
+                dim: usize,
+                features: usize,
+                sigma: f64,
+                scale: f64,
+                gen: &mut R,
+            ) -> Self {
+                let omega =
+                    nd::Array2::from_shape_fn((features, dim), |_| dist::normal(gen) / scale);
+                Fourier::with(omega, sigma, gen)
+            }
+
+            #[doc = "Orthogonal random features, with frequencies orthogonal within blocks of `dim`"]
+            pub fn orthogonal(dim: usize, features: usize, sigma: f64, scale: f64) -> Self {
+                Fourier::orthogonal_with_rng(dim, features, sigma, scale, &mut rand::thread_rng())
+            }
+
+            #[doc = "Orthogonal random features, drawn from `gen`"]
+            pub fn orthogonal_with_rng<R: rand::Rng + ?Sized>(
+                dim: usize,
+                features: usize,
+                sigma: f64,
+                scale: f64,
+                gen: &mut R,
+            ) -> Self {
+                let mut omega = nd::Array2::zeros((features, dim));
+                for block in (0..features).step_by(dim) {
+                    let g = na::DMatrix::from_fn(dim, dim, |_, _| dist::normal(gen));
+                    let q = g.qr().q();
+                    for i in 0..dim.min(features - block) {
+                        let norm = (0..dim)
+                            .map(|_| dist::normal(gen).powi(2))
+                            .sum::<f64>()
+                            .sqrt();
+                        for j in 0..dim {
+                            omega[[block + i, j]] = norm * q[(i, j)] / scale;
+                        }
+                    }
+                }
+                Fourier::with(omega, sigma, gen)
+            }
+
+            fn with<R: rand::Rng + ?Sized>(
+                omega: nd::Array2<f64>,
+                sigma: f64,
+                gen: &mut R,
+            ) -> Self {
+                assert!(sigma > 0.0, "sigma isn't positive");
+                let phase = nd::Array1::from_shape_fn(omega.nrows(), |_| {
+                    gen.gen_range(0.0..2.0 * std::f64::consts::PI)
+                });
+                Fourier {
+                    omega,
+                    phase,
+                    sigma,
+                }
+            }
+
+            #[doc = "Feature map `z` with `z(x)·z(y) ≈ k(x, y)`"]
+            pub fn transform(&self, x: nd::ArrayView1<f64>) -> nd::Array1<f64> {
+                let weight = self.sigma * (2.0 / self.omega.nrows() as f64).sqrt();
+                let mut z = self.omega.dot(&x) + &self.phase;
+                z.mapv_inplace(|z| weight * z.cos());
+                z
+            }
+
+            #[doc = "Feature matrix with a row per location"]
+            pub fn matrix(&self, xs: &nd::Array2<f64>) -> nd::Array2<f64> {
+                let mut zs = nd::Array2::zeros((xs.nrows(), self.omega.nrows()));
+                for (i, x) in xs.rows().into_iter().enumerate() {
+                    zs.row_mut(i).assign(&self.transform(x));
+                }
+                zs
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn rbf() {
+                use rand::SeedableRng;
+                let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+                let kernel = gp::kernel::rbf(1.0, 0.5);
+                let (x, y) = (
+                    nd::Array1::from(vec![0.1, 0.2]),
+                    nd::Array1::from(vec![0.3, -0.1]),
+                );
+                for fourier in [
+                    Fourier::rbf_with_rng(2, 4000, 1.0, 0.5, &mut gen),
+                    Fourier::orthogonal_with_rng(2, 4000, 1.0, 0.5, &mut gen),
+                ] {
+                    let approx = fourier
+                        .transform(x.view())
+                        .dot(&fourier.transform(y.view()));
+                    assert!((approx - kernel(x.view(), y.view())).abs() < 0.05);
+                }
+            }
+        }
+    }
+}
+
+#[doc = "Spatial prediction by ordinary kriging"]
+pub mod kriging {
+    use super::*;
+
+    pub struct Kriging<K: Fn(nd::ArrayView1<f64>, nd::ArrayView1<f64>) -> f64> {
+        pub kernel: K,
+        pub locations: nd::Array2<f64>,
+        pub mean: f64,
+        chol: na::Cholesky<f64, na::Dynamic>,
+        weights: na::DVector<f64>,
+        ones: na::DVector<f64>,
+    }
+    impl<K: Fn(nd::ArrayView1<f64>, nd::ArrayView1<f64>) -> f64> Kriging<K> {
+        #[allow(unused)]
+        pub fn new(
+            kernel: K,
+            locations: nd::Array2<f64>,
+            values: nd::Array1<f64>,
+            noise: f64,
+        ) -> Self {
+            assert_eq!(locations.nrows(), values.len(), "values mismatch");
+            assert!(noise >= 0.0, "noise is negative");
+
+            let n = values.len();
+            let cov = na::DMatrix::from_fn(n, n, |i, j| {
+                let nugget = if i == j { noise } else { 0.0 };
+                kernel(locations.row(i), locations.row(j)) + nugget
+            });
+            let chol = cov.cholesky().expect("covariance isn't positive definite");
+
+            let ys = na::DVector::from_iterator(n, values.iter().cloned());
+            let ones = chol.solve(&na::DVector::repeat(n, 1.0));
+            let mean = ones.dot(&ys) / ones.sum();
+            let weights = chol.solve(&ys.map(|y| y - mean));
+
+            Kriging {
+                kernel,
+                locations,
+                mean,
+                chol,
+                weights,
+                ones,
+            }
+        }
+
+        pub fn predict(&self, x: nd::ArrayView1<f64>) -> (f64, f64) {
+            let n = self.locations.nrows();
+            let k = na::DVector::from_fn(n, |i, _| (self.kernel)(x, self.locations.row(i)));
+
+            let mean = self.mean + k.dot(&self.weights);
+            let drift = 1.0 - self.ones.dot(&k);
+            let var =
+                (self.kernel)(x, x) - k.dot(&self.chol.solve(&k)) + drift.powi(2) / self.ones.sum();
+            (mean, var.max(0.0))
+        }
+
+        pub fn surface(
+            &self,
+            xs: &nd::Array1<f64>,
+            ys: &nd::Array1<f64>,
+        ) -> (nd::Array2<f64>, nd::Array2<f64>) {
+            assert_eq!(self.locations.ncols(), 2, "locations aren't planar");
+            let mut mean = nd::Array2::zeros((xs.len(), ys.len()));
+            let mut var = nd::Array2::zeros((xs.len(), ys.len()));
+            for (i, &x) in xs.iter().enumerate() {
+                for (j, &y) in ys.iter().enumerate() {
+                    let point = nd::Array1::from(vec![x, y]);
+                    let (m, v) = self.predict(point.view());
+                    mean[[i, j]] = m;
+                    var[[i, j]] = v;
+                }
+            }
+            (mean, var)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn interpolate() {
+            let locations = nd::Array2::from_shape_vec((3, 2), vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+            let values = nd::Array1::from(vec![1.0, 2.0, 3.0]);
+            let kernel = gp::kernel::rbf(1.0, 1.0);
+            let kriging = Kriging::new(kernel, locations.unwrap(), values, 0.0);
+            let (mean, var) = kriging.predict(kriging.locations.row(1));
+            assert!((mean - 2.0).abs() < 1e-6);
+            assert!(var < 1e-6);
+        }
+    }
+}
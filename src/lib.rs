@@ -4,13 +4,38 @@
 extern crate num;
 extern crate rand;
 
+extern crate self as probs;
+
 extern crate nalgebra as na;
 extern crate ndarray as nd;
 extern crate nshare as ns;
 
+pub mod approx;
+pub mod diag;
 pub mod dist;
+pub mod ep;
+pub mod error;
+pub mod estimator;
+pub mod experiment;
+pub mod family;
+pub mod gp;
+pub mod prelude;
+pub mod random_matrix;
 pub mod randvar;
+pub mod rng;
 pub mod sampler;
+pub mod scale;
+pub mod statespace;
+pub mod stats;
+pub mod timeseries;
+pub mod vi;
 
+pub use error::Error;
+pub use probs_derive::{Coordinates, Discrete, Domain, Perturb};
 pub use randvar::*;
 pub use sampler::*;
+
+#[doc(hidden)]
+pub mod __private {
+    pub extern crate rand;
+}
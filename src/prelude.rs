@@ -0,0 +1,29 @@
+pub use crate::dist::Distribution;
+pub use crate::family::ExponentialFamily;
+pub use crate::randvar::{Coordinates, Discrete, Domain, Finite, Perturb, Uniform};
+pub use crate::sampler::{Build, Kernel, Sampler};
+pub use crate::scale::Scale;
+pub use probs_derive::{Coordinates, Discrete, Domain, Perturb};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dist, sampler::univar};
+
+    #[test]
+    fn methods() {
+        let gamma = dist::univar::gamma(2.0, 1.0);
+        let mean = univar::Metropolis::builder()
+            .proposal(|x: &f64, _: &mut dyn rand::RngCore| (x + 0.5).abs())
+            .build()
+            .unwrap()
+            .burn(100)
+            .pick(2)
+            .sample(dist::density(gamma.clone()))
+            .take(10)
+            .sum::<f64>()
+            / 10.0;
+        assert!(mean > 0.0);
+        assert!(gamma.cdf(&1.0).unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,117 @@
+use super::*;
+use rand::Rng;
+
+#[doc = "Dense gaussian projection from `d` to `k` dimensions"]
+pub fn gaussian(k: usize, d: usize) -> nd::Array2<f64> {
+    gaussian_with_rng(k, d, &mut rand::thread_rng())
+}
+
+#[doc = "Dense gaussian projection from `d` to `k` dimensions, drawn from `gen`"]
+pub fn gaussian_with_rng<R: Rng + ?Sized>(k: usize, d: usize, gen: &mut R) -> nd::Array2<f64> {
+    let scale = 1.0 / (k as f64).sqrt();
+    nd::Array2::from_shape_fn((k, d), |_| scale * dist::normal(gen))
+}
+
+#[doc = "Sparse sign projection with density `1 / s`, Achlioptas' for `s = 3`"]
+pub fn sparse(k: usize, d: usize, s: f64) -> nd::Array2<f64> {
+    sparse_with_rng(k, d, s, &mut rand::thread_rng())
+}
+
+#[doc = "Sparse sign projection with density `1 / s`, drawn from `gen`"]
+pub fn sparse_with_rng<R: Rng + ?Sized>(
+    k: usize,
+    d: usize,
+    s: f64,
+    gen: &mut R,
+) -> nd::Array2<f64> {
+    assert!(s >= 1.0, "density exceeds one");
+    let scale = (s / k as f64).sqrt();
+    nd::Array2::from_shape_fn((k, d), |_| {
+        let u: f64 = gen.gen_range(0.0..s);
+        match u {
+            u if u < 0.5 => scale,
+            u if u < 1.0 => -scale,
+            _ => 0.0,
+        }
+    })
+}
+
+#[doc = "Subsampled randomized Hadamard transform"]
+pub struct Srht {
+    pub signs: Vec<f64>,
+    pub rows: Vec<usize>,
+    pub dim: usize,
+}
+impl Srht {
+    #[allow(unused)]
+    pub fn new(k: usize, d: usize) -> Self {
+        Srht::new_with_rng(k, d, &mut rand::thread_rng())
+    }
+
+    #[doc = "Transform with signs and rows drawn from `gen`"]
+    pub fn new_with_rng<R: Rng + ?Sized>(k: usize, d: usize, gen: &mut R) -> Self {
+        let n = d.next_power_of_two();
+        assert!(0 < k && k <= n, "projection isn't reducing");
+        let signs = (0..n)
+            .map(|_| if gen.gen_bool(0.5) { 1.0 } else { -1.0 })
+            .collect();
+        let mut rows: Vec<usize> = (0..n).collect();
+        for i in 0..k {
+            let j = gen.gen_range(i..n);
+            rows.swap(i, j);
+        }
+        rows.truncate(k);
+        Srht {
+            signs,
+            rows,
+            dim: d,
+        }
+    }
+
+    pub fn apply(&self, x: nd::ArrayView1<f64>) -> nd::Array1<f64> {
+        assert_eq!(x.len(), self.dim, "dimension mismatch");
+        let mut y: Vec<f64> = (self.signs.iter().enumerate())
+            .map(|(i, s)| if i < self.dim { s * x[i] } else { 0.0 })
+            .collect();
+        hadamard(&mut y);
+        let scale = 1.0 / (self.rows.len() as f64).sqrt();
+        self.rows.iter().map(|&r| scale * y[r]).collect()
+    }
+
+    pub fn matrix(&self) -> nd::Array2<f64> {
+        let mut m = nd::Array2::zeros((self.rows.len(), self.dim));
+        for j in 0..self.dim {
+            let mut e = nd::Array1::zeros(self.dim);
+            e[j] = 1.0;
+            m.column_mut(j).assign(&self.apply(e.view()));
+        }
+        m
+    }
+}
+
+// in-place unnormalized walsh-hadamard transform
+fn hadamard(xs: &mut [f64]) {
+    let mut h = 1;
+    while h < xs.len() {
+        for i in (0..xs.len()).step_by(2 * h) {
+            for j in i..i + h {
+                let (a, b) = (xs[j], xs[j + h]);
+                xs[j] = a + b;
+                xs[j + h] = a - b;
+            }
+        }
+        h *= 2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isometry() {
+        let x = nd::Array1::from(vec![1.0, -2.0, 0.5, 3.0, 0.0, 1.5, -1.0, 2.0]);
+        let y = Srht::new(8, 8).apply(x.view());
+        assert!((x.dot(&x) - y.dot(&y)).abs() < 1e-9);
+    }
+}
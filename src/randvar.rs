@@ -4,6 +4,9 @@ use super::*;
 pub trait Domain: na::Scalar {
     type Iter: Iterator<Item = Self>;
     fn random() -> Self::Iter;
+
+    #[doc = "Single random point drawn with `gen`"]
+    fn random_with<R: rand::Rng + ?Sized>(gen: &mut R) -> Self;
 }
 
 #[doc = "Discrete Random Variable"]
@@ -12,6 +15,32 @@ pub trait Discrete: na::Scalar {
     fn iter() -> Self::Iter;
 }
 
+#[doc = "Discrete domain with a known number of states"]
+pub trait Finite: Discrete {
+    const SIZE: usize;
+}
+
+#[doc = "Domain reachable from the unit interval"]
+pub trait Uniform: Domain {
+    fn uniform(u: f64) -> Self;
+}
+
+#[doc = "Local random move, the default proposal for samplers and annealing"]
+pub trait Perturb: Sized {
+    fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self;
+}
+
+#[doc = "Real coordinates of a point, for running statistics"]
+pub trait Coordinates {
+    fn coordinates(&self) -> Vec<f64>;
+}
+
+#[doc = "Symmetric proposal perturbing the state by `scale`, drawing from the chain's generator"]
+pub fn perturb<D: Perturb>(scale: f64) -> impl Fn(&D, &mut dyn rand::RngCore) -> D {
+    assert!(scale > 0.0, "scale isn't positive");
+    move |x, gen| x.perturb(scale, gen)
+}
+
 /* -------------------------------------------------------------------------- */
 /*                                  PROVIDED                                  */
 /* -------------------------------------------------------------------------- */
@@ -24,9 +53,26 @@ macro_rules! impl_domain {
                 impl Domain for $Num {
                     type Iter = impl Iterator<Item = Self>;
                     fn random() -> Self::Iter {
-                        use rand::Rng;
                         let mut gen = rand::thread_rng();
-                        std::iter::from_fn(move || Some(gen.gen_range(0.0..1.0)))
+                        std::iter::from_fn(move || Some(Self::random_with(&mut gen)))
+                    }
+                    fn random_with<R: rand::Rng + ?Sized>(gen: &mut R) -> Self {
+                        gen.gen_range(0.0..1.0)
+                    }
+                }
+                impl Uniform for $Num {
+                    fn uniform(u: f64) -> Self {
+                        u as $Num
+                    }
+                }
+                impl Perturb for $Num {
+                    fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+                        self + gen.gen_range(-scale..scale) as $Num
+                    }
+                }
+                impl Coordinates for $Num {
+                    fn coordinates(&self) -> Vec<f64> {
+                        vec![*self as f64]
                     }
                 }
             )*
@@ -34,20 +80,129 @@ macro_rules! impl_domain {
     }
 impl_domain![f32, f64];
 
+impl<T: Perturb, const N: usize> Perturb for [T; N] {
+    fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+        let mut xs = self.iter();
+        [(); N].map(|_| xs.next().unwrap().perturb(scale, gen))
+    }
+}
+
+impl<T: Perturb> Perturb for Vec<T> {
+    fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+        self.iter().map(|x| x.perturb(scale, gen)).collect()
+    }
+}
+
+impl<T: Perturb + Clone, S: nd::Dimension> Perturb for nd::Array<T, S> {
+    fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+        self.map(|x| x.perturb(scale, gen))
+    }
+}
+
+impl<A: Perturb, B: Perturb> Perturb for (A, B) {
+    fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+        (self.0.perturb(scale, gen), self.1.perturb(scale, gen))
+    }
+}
+
+impl<T: Coordinates, const N: usize> Coordinates for [T; N] {
+    fn coordinates(&self) -> Vec<f64> {
+        self.iter().flat_map(T::coordinates).collect()
+    }
+}
+
+impl<T: Coordinates> Coordinates for Vec<T> {
+    fn coordinates(&self) -> Vec<f64> {
+        self.iter().flat_map(T::coordinates).collect()
+    }
+}
+
+impl<T: Coordinates, S: nd::Dimension> Coordinates for nd::Array<T, S> {
+    fn coordinates(&self) -> Vec<f64> {
+        self.iter().flat_map(T::coordinates).collect()
+    }
+}
+
+impl<A: Coordinates, B: Coordinates> Coordinates for (A, B) {
+    fn coordinates(&self) -> Vec<f64> {
+        let mut xs = self.0.coordinates();
+        xs.extend(self.1.coordinates());
+        xs
+    }
+}
+
+pub mod circular {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[doc = "Angle on the circle, wrapped into [-π, π)"]
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct Angle(pub f64);
+    impl Angle {
+        #[allow(unused)]
+        pub fn new(theta: f64) -> Self {
+            Angle(theta - 2.0 * PI * ((theta + PI) / (2.0 * PI)).floor())
+        }
+    }
+
+    impl Domain for Angle {
+        type Iter = impl Iterator<Item = Self>;
+        fn random() -> Self::Iter {
+            let mut gen = rand::thread_rng();
+            std::iter::from_fn(move || Some(Self::random_with(&mut gen)))
+        }
+        fn random_with<R: rand::Rng + ?Sized>(gen: &mut R) -> Self {
+            Angle(gen.gen_range(-PI..PI))
+        }
+    }
+
+    impl Uniform for Angle {
+        fn uniform(u: f64) -> Self {
+            Angle::new(2.0 * PI * u - PI)
+        }
+    }
+
+    impl Perturb for Angle {
+        fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+            Angle::new(self.0.perturb(scale, gen))
+        }
+    }
+
+    impl Coordinates for Angle {
+        fn coordinates(&self) -> Vec<f64> {
+            vec![self.0]
+        }
+    }
+
+    impl num::ToPrimitive for Angle {
+        fn to_u64(&self) -> Option<u64> {
+            self.0.to_u64()
+        }
+        fn to_i64(&self) -> Option<i64> {
+            self.0.to_i64()
+        }
+        fn to_f64(&self) -> Option<f64> {
+            Some(self.0)
+        }
+    }
+}
+
 /* -------------------------------- Discrete -------------------------------- */
 
 pub mod modular {
     use super::*;
     use std::fmt::*;
 
-    #[derive(Clone, PartialEq, Debug)]
+    #[derive(Clone, PartialEq, PartialOrd, Debug)]
     pub struct Z<const N: usize>(pub usize);
     impl<const N: usize> Domain for Z<N> {
         type Iter = impl Iterator<Item = Self>;
         fn random() -> Self::Iter {
-            use rand::Rng;
             let mut gen = rand::thread_rng();
-            std::iter::from_fn(move || Some(gen.gen_range(0..N)).map(Z))
+            std::iter::from_fn(move || Some(Self::random_with(&mut gen)))
+        }
+        fn random_with<R: rand::Rng + ?Sized>(gen: &mut R) -> Self {
+            Z(gen.gen_range(0..N))
         }
     }
     impl<const N: usize> Discrete for Z<N> {
@@ -57,6 +212,31 @@ pub mod modular {
         }
     }
 
+    impl<const N: usize> Finite for Z<N> {
+        const SIZE: usize = N;
+    }
+
+    impl<const N: usize> Uniform for Z<N> {
+        fn uniform(u: f64) -> Self {
+            Z(((u * N as f64) as usize).min(N - 1))
+        }
+    }
+
+    // integer step of at most `ceil(scale)`, wrapping around
+    impl<const N: usize> Perturb for Z<N> {
+        fn perturb<R: rand::Rng + ?Sized>(&self, scale: f64, gen: &mut R) -> Self {
+            let reach = (scale.ceil() as usize).max(1).min(N / 2);
+            let step = gen.gen_range(0..=2 * reach);
+            Z((self.0 + N - reach + step) % N)
+        }
+    }
+
+    impl<const N: usize> Coordinates for Z<N> {
+        fn coordinates(&self) -> Vec<f64> {
+            vec![self.0 as f64]
+        }
+    }
+
     impl<const N: usize> num::ToPrimitive for Z<N> {
         fn to_u64(&self) -> Option<u64> {
             Some(self.0 as u64)
@@ -65,6 +245,71 @@ pub mod modular {
             Some(self.0 as i64)
         }
     }
+
+    impl<const N: usize> num::FromPrimitive for Z<N> {
+        fn from_u64(n: u64) -> Option<Self> {
+            Some(Z(n as usize)).filter(|z| z.0 < N)
+        }
+        fn from_i64(n: i64) -> Option<Self> {
+            u64::try_from(n).ok().and_then(Self::from_u64)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn perturb() {
+            let mut gen = rand::thread_rng();
+            for _ in 0..100 {
+                let Z(z) = Z::<5>(0).perturb(1.0, &mut gen);
+                assert!(z == 4 || z <= 1);
+                let xs = [1.0, -1.0].perturb(0.5, &mut gen);
+                assert!((xs[0] - 1.0f64).abs() <= 0.5 && (xs[1] + 1.0f64).abs() <= 0.5);
+            }
+        }
+    }
+}
+
+impl Domain for bool {
+    type Iter = impl Iterator<Item = Self>;
+    fn random() -> Self::Iter {
+        let mut gen = rand::thread_rng();
+        std::iter::from_fn(move || Some(Self::random_with(&mut gen)))
+    }
+    fn random_with<R: rand::Rng + ?Sized>(gen: &mut R) -> Self {
+        gen.gen_bool(0.5)
+    }
+}
+impl Discrete for bool {
+    type Iter = impl Iterator<Item = Self>;
+    fn iter() -> Self::Iter {
+        [false, true].into_iter()
+    }
+}
+
+impl Finite for bool {
+    const SIZE: usize = 2;
+}
+
+impl Uniform for bool {
+    fn uniform(u: f64) -> Self {
+        u >= 0.5
+    }
+}
+
+// the only move is a flip
+impl Perturb for bool {
+    fn perturb<R: rand::Rng + ?Sized>(&self, _: f64, _: &mut R) -> Self {
+        !self
+    }
+}
+
+impl Coordinates for bool {
+    fn coordinates(&self) -> Vec<f64> {
+        vec![*self as u8 as f64]
+    }
 }
 
 // pub mod float {
@@ -110,3 +355,57 @@ pub mod modular {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boolean() {
+        assert_eq!(bool::iter().collect::<Vec<bool>>(), vec![false, true]);
+        assert_eq!(bool::SIZE, bool::iter().count());
+        assert!(!bool::uniform(0.2) && bool::uniform(0.7));
+        use rand::SeedableRng;
+        let pdf = |x: &bool| if *x { 3.0 } else { 1.0 };
+        let gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let xs: Vec<bool> = (univar::Icdf::new().sample_with_rng(pdf, gen))
+            .take(4000)
+            .collect();
+        let p = xs.iter().filter(|x| **x).count() as f64 / 4000.0;
+        assert!((p - 0.75).abs() < 0.05);
+    }
+
+    #[derive(Clone, Debug, PartialEq, Domain, Discrete, Coordinates, Perturb)]
+    enum Spin {
+        Up,
+        #[allow(unused)]
+        Down = 3,
+    }
+
+    #[test]
+    fn derive() {
+        assert_eq!(
+            Spin::iter().collect::<Vec<Spin>>(),
+            vec![Spin::Up, Spin::Down]
+        );
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let ups = (0..1000)
+            .filter(|_| Spin::random_with(&mut gen) == Spin::Up)
+            .count();
+        assert!(ups > 400 && ups < 600);
+        assert_eq!(Spin::Down.coordinates(), vec![1.0]);
+        assert_eq!(Spin::Up.perturb(1.0, &mut gen).coordinates().len(), 1);
+
+        let xs: Vec<Spin> = (univar::Metropolis::builder())
+            .proposal(perturb::<Spin>(1.0))
+            .build()
+            .unwrap()
+            .with_stats()
+            .sample_with_rng(|x: &Spin| if *x == Spin::Up { 3.0 } else { 1.0 }, &mut gen)
+            .take(4000)
+            .collect();
+        let p = xs.iter().filter(|x| **x == Spin::Up).count() as f64 / 4000.0;
+        assert!((p - 0.75).abs() < 0.1);
+    }
+}
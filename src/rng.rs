@@ -0,0 +1,113 @@
+use super::*;
+
+#[doc = "Generator for stream `index` of `seed`, independent of the other streams"]
+pub fn stream<G: rand::SeedableRng>(seed: u64, index: u64) -> G {
+    // splitmix64 over the pair, filling the whole seed so nearby streams don't overlap
+    let mut state = seed ^ index.wrapping_mul(0xD1B54A32D192ED03);
+    let mut next = || {
+        state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    };
+    let mut bytes = G::Seed::default();
+    for chunk in bytes.as_mut().chunks_mut(8) {
+        let z = next().to_le_bytes();
+        chunk.copy_from_slice(&z[..chunk.len()]);
+    }
+    G::from_seed(bytes)
+}
+
+#[doc = "Generator wrapper taping every byte it hands out"]
+#[derive(Clone, Debug)]
+pub struct Recorder<R: rand::RngCore> {
+    pub gen: R,
+    pub tape: Vec<u8>,
+}
+impl<R: rand::RngCore> Recorder<R> {
+    #[allow(unused)]
+    pub fn new(gen: R) -> Self {
+        Recorder { gen, tape: vec![] }
+    }
+
+    #[doc = "Generator replaying the draws recorded so far"]
+    pub fn replay(&self) -> Replay {
+        Replay::new(self.tape.clone())
+    }
+}
+impl<R: rand::RngCore> rand::RngCore for Recorder<R> {
+    fn next_u32(&mut self) -> u32 {
+        let x = self.gen.next_u32();
+        self.tape.extend_from_slice(&x.to_le_bytes());
+        x
+    }
+    fn next_u64(&mut self) -> u64 {
+        let x = self.gen.next_u64();
+        self.tape.extend_from_slice(&x.to_le_bytes());
+        x
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.gen.fill_bytes(dest);
+        self.tape.extend_from_slice(dest);
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.gen.try_fill_bytes(dest)?;
+        self.tape.extend_from_slice(dest);
+        Ok(())
+    }
+}
+
+#[doc = "Generator reading back a recorded tape, panicking once it runs out"]
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub tape: Vec<u8>,
+    pub pos: usize,
+}
+impl Replay {
+    #[allow(unused)]
+    pub fn new(tape: Vec<u8>) -> Self {
+        Replay { tape, pos: 0 }
+    }
+
+    fn read(&mut self, n: usize) -> &[u8] {
+        assert!(self.pos + n <= self.tape.len(), "tape is exhausted");
+        self.pos += n;
+        &self.tape[self.pos - n..self.pos]
+    }
+}
+impl rand::RngCore for Replay {
+    fn next_u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.read(4).try_into().unwrap())
+    }
+    fn next_u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.read(8).try_into().unwrap())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.copy_from_slice(self.read(dest.len()));
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use modular::*;
+
+    #[test]
+    fn replay() {
+        let pdf = |z: &Z<8>| (z.0 + 1) as f64;
+        let sampler = univar::Icdf::new().burn(10);
+        let mut recorder = Recorder::new(rand::thread_rng());
+        let xs: Vec<Z<8>> = (sampler.sample_with_rng(pdf, &mut recorder))
+            .take(50)
+            .collect();
+        let ys: Vec<Z<8>> = (sampler.sample_with_rng(pdf, recorder.replay()))
+            .take(50)
+            .collect();
+        assert_eq!(xs, ys);
+    }
+}
@@ -1,21 +1,141 @@
 use super::*;
 
 pub trait Sampler<D: na::Scalar> {
-    type Iter<F: FnMut(&D) -> f64>: Iterator<Item = D>;
-    fn sample<F: FnMut(&D) -> f64>(&self, pdf: F) -> Self::Iter<F>;
+    type Iter<F: FnMut(&D) -> f64, R: rand::Rng>: Iterator<Item = D>;
+
+    #[doc = "Draw every random number from `gen`, reproducible when it is seeded"]
+    fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+        &self,
+        pdf: F,
+        gen: R,
+    ) -> Self::Iter<F, R>;
+
+    fn sample<F: FnMut(&D) -> f64>(&self, pdf: F) -> Self::Iter<F, rand::rngs::ThreadRng> {
+        self.sample_with_rng(pdf, rand::thread_rng())
+    }
+
+    #[doc = "Continue the chain from `state`, drawing from `gen`"]
+    fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+        &self,
+        pdf: F,
+        state: D,
+        gen: R,
+    ) -> Self::Iter<F, R>;
+
+    #[doc = "Continue the chain from `state`"]
+    fn resume<F: FnMut(&D) -> f64>(
+        &self,
+        pdf: F,
+        state: D,
+    ) -> Self::Iter<F, rand::rngs::ThreadRng> {
+        self.resume_with_rng(pdf, state, rand::thread_rng())
+    }
+
+    #[doc = "Visit `n` samples by reference drawing from `gen`, sparing the clones where the sampler can"]
+    fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+        &self,
+        pdf: F,
+        n: usize,
+        mut visit: V,
+        gen: R,
+    ) {
+        self.sample_with_rng(pdf, gen)
+            .take(n)
+            .for_each(|x| visit(&x))
+    }
+
+    #[doc = "Visit `n` samples by reference, sparing the clones where the sampler can"]
+    fn for_each_sample<F: FnMut(&D) -> f64, V: FnMut(&D)>(&self, pdf: F, n: usize, visit: V) {
+        self.for_each_sample_with_rng(pdf, n, visit, rand::thread_rng())
+    }
 
     fn burn(self, skip: usize) -> adapter::Burn<D, Self>
     where
         Self: Sized,
     {
-        adapter::Burn::new(self, skip)
+        (adapter::Burn::builder().sampler(self).skip(skip).build())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    #[doc = "Pick every `interval`-th sample, or `Interval::Auto` for the autocorrelation time"]
+    fn pick<I: adapter::pick::IntoInterval<D>>(self, interval: I) -> adapter::Pick<D, Self>
+    where
+        Self: Sized,
+    {
+        let builder = adapter::Pick::builder().sampler(self).interval(interval);
+        builder.build().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn trace(self, capacity: usize) -> adapter::Trace<D, Self>
+    where
+        Self: Sized,
+    {
+        (adapter::Trace::builder()
+            .sampler(self)
+            .capacity(capacity)
+            .build())
+        .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn with_stats(self) -> adapter::Monitor<D, Self>
+    where
+        Self: Sized,
+        D: Coordinates,
+    {
+        adapter::Monitor::new(self)
+    }
+
+    fn until_ess(self, target: f64) -> adapter::UntilEss<D, Self>
+    where
+        Self: Sized,
+        D: Coordinates,
+    {
+        (adapter::UntilEss::builder()
+            .sampler(self)
+            .target(target)
+            .build())
+        .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn seeded(self, seed: u64) -> adapter::Seeded<D, Self>
+    where
+        Self: Sized,
+    {
+        adapter::Seeded::new(self, seed)
+    }
+
+    fn parallel(self, chains: usize, seed: u64) -> adapter::Parallel<D, Self>
+    where
+        Self: Sized,
+    {
+        let builder = adapter::Parallel::builder().sampler(self).chains(chains);
+        builder
+            .seed(seed)
+            .build()
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
-    fn pick(self, interval: usize) -> adapter::Pick<D, Self>
+    #[doc = "Bind to `pdf` as a `rand` distribution"]
+    fn target<F: Fn(&D) -> f64>(self, pdf: F) -> Target<D, Self, F>
     where
         Self: Sized,
     {
-        adapter::Pick::new(self, interval)
+        Target {
+            pd: std::marker::PhantomData,
+            sampler: self,
+            pdf,
+        }
+    }
+
+    fn temper(self, betas: Vec<f64>) -> adapter::Tempered<D, Self>
+    where
+        Self: Sized,
+    {
+        (adapter::Tempered::builder()
+            .sampler(self)
+            .betas(betas)
+            .build())
+        .unwrap_or_else(|e| panic!("{}", e))
     }
 
     fn gibbs<R: nd::Dimension>(self, dim: R) -> multivar::Gibbs<D, R, Self>
@@ -23,14 +143,58 @@ pub trait Sampler<D: na::Scalar> {
         Self: Sized,
         D: Domain,
     {
-        multivar::Gibbs::new(dim, self)
+        (multivar::Gibbs::builder().dim(dim).sampler(self).build())
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn gibbs_fixed<const N: usize>(self) -> multivar::FixedGibbs<D, Self, N>
+    where
+        Self: Sized,
+        D: Domain,
+    {
+        (multivar::FixedGibbs::builder().sampler(self).build()).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+#[doc = "Sampler bound to its target, each `rand` draw being the first sample of a fresh chain"]
+pub struct Target<D: na::Scalar, S: Sampler<D>, F: Fn(&D) -> f64> {
+    pd: std::marker::PhantomData<D>,
+    pub sampler: S,
+    pub pdf: F,
+}
+impl<D: na::Scalar, S: Sampler<D>, F: Fn(&D) -> f64> rand::distributions::Distribution<D>
+    for Target<D, S, F>
+{
+    fn sample<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> D {
+        (self.sampler.sample_with_rng(&self.pdf, gen))
+            .next()
+            .expect("sampler is exhausted")
     }
 }
 
+#[doc = "Validating configuration of a sampler"]
+pub trait Build<D: na::Scalar>: Sized {
+    type Sampler;
+
+    fn build(self) -> Result<Self::Sampler, Error>;
+
+    #[doc = "Build the sampler seeded, as with `Sampler::seeded`"]
+    fn seed(self, seed: u64) -> adapter::seeded::Builder<D, Self> {
+        adapter::seeded::Builder::new(self, seed)
+    }
+}
+
+#[doc = "Markov transition kernel"]
+pub trait Kernel<D> {
+    fn step<R: rand::Rng + ?Sized>(&self, state: &D, gen: &mut R) -> D;
+}
+
 #[doc = "Sample from univariate domain"]
 pub mod univar {
     use super::*;
 
+    pub use cftp::Sampler as Cftp;
+    pub use exact::Sampler as Exact;
     pub use icdf::Sampler as Icdf;
     pub use metropolis::Sampler as Metropolis;
 
@@ -38,8 +202,8 @@ pub mod univar {
     pub mod icdf {
         use super::*;
 
-        pub struct Sampler<D: Domain + Discrete> {
-            pd: std::marker::PhantomData<D>,
+        pub struct Sampler<D: Domain + Discrete, L: scale::Scale = scale::Linear> {
+            pd: std::marker::PhantomData<(D, L)>,
         }
         impl<D: Domain + Discrete> Sampler<D> {
             #[allow(unused)]
@@ -49,12 +213,24 @@ pub mod univar {
                 }
             }
         }
-        impl<D: Domain + Discrete> super::Sampler<D> for Sampler<D> {
-            type Iter<F: FnMut(&D) -> f64> = impl Iterator<Item = D>;
-            fn sample<F: FnMut(&D) -> f64>(&self, pdf: F) -> Self::Iter<F> {
+        impl<D: Domain + Discrete, L: scale::Scale> Sampler<D, L> {
+            #[doc = "Interpret pdf values on another scale"]
+            pub fn scale<M: scale::Scale>(self) -> Sampler<D, M> {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                }
+            }
+        }
+        impl<D: Domain + Discrete, L: scale::Scale> super::Sampler<D> for Sampler<D, L> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
                 use std::ops::AddAssign;
                 let xs: Vec<D> = D::iter().collect();
-                let ys: Vec<f64> = xs.iter().map(pdf).collect();
+                let ys: Vec<f64> = scale::weights::<L, _>(xs.iter().map(pdf));
                 let zs: Vec<f64> = ys
                     .iter()
                     .scan(0.0, |z, y| {
@@ -67,15 +243,23 @@ pub mod univar {
                 assert!(sum > 0.0, "pdf isn't positive");
                 assert!(sum.is_finite(), "pdf overflow");
 
-                let mut aux = rand::thread_rng();
+                let mut aux = gen;
                 std::iter::from_fn(move || {
-                    use rand::Rng;
                     let aux = aux.gen_range(0.0..sum);
                     let pos = zs.binary_search_by(|z| z.partial_cmp(&aux).unwrap());
                     let pos = pos.unwrap_or_else(|pos| pos);
                     Some(xs[pos].clone())
                 })
             }
+            // draws are independent, there's no state to carry on from
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                _: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                self.sample_with_rng(pdf, gen)
+            }
         }
 
         #[cfg(test)]
@@ -90,6 +274,40 @@ pub mod univar {
                     dist::univar::gaussian(128.0, 32.0),
                 );
             }
+
+            #[test]
+            fn seeded() {
+                use rand::SeedableRng;
+                use sampler::Sampler;
+                let draw = |seed: u64| -> Vec<Z<16>> {
+                    let gen = rand::rngs::StdRng::seed_from_u64(seed);
+                    (univar::Icdf::new())
+                        .burn(10)
+                        .sample_with_rng(|z: &Z<16>| (z.0 + 1) as f64, gen)
+                        .take(100)
+                        .collect()
+                };
+                assert_eq!(draw(7), draw(7));
+                assert_ne!(draw(7), draw(8));
+            }
+
+            #[test]
+            fn log_scale() {
+                use rand::SeedableRng;
+                use sampler::Sampler;
+                let pdf = |z: &Z<2>| -1000.0 + z.0 as f64 * 3f64.ln();
+                let ones = |zs: Vec<Z<2>>| zs.iter().filter(|z| z.0 == 1).count() as f64 / 4000.0;
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let icdf = univar::Icdf::new().scale::<scale::Log>();
+                assert!(
+                    (ones(icdf.sample_with_rng(pdf, gen).take(4000).collect()) - 0.75).abs() < 0.03
+                );
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let cftp = univar::Cftp::new().scale::<scale::Log>();
+                assert!(
+                    (ones(cftp.sample_with_rng(pdf, gen).take(4000).collect()) - 0.75).abs() < 0.03
+                );
+            }
         }
     }
 
@@ -98,41 +316,214 @@ pub mod univar {
         use super::*;
         use std::sync::*;
 
-        pub struct Sampler<D: Domain, P: Fn(&D) -> D> {
-            pd: std::marker::PhantomData<D>,
+        #[doc = "Shared counters of proposed and accepted moves"]
+        #[derive(Clone, Debug, Default)]
+        pub struct Acceptance {
+            proposed: Arc<atomic::AtomicUsize>,
+            accepted: Arc<atomic::AtomicUsize>,
+        }
+        impl Acceptance {
+            pub fn proposed(&self) -> usize {
+                self.proposed.load(atomic::Ordering::Relaxed)
+            }
+
+            pub fn accepted(&self) -> usize {
+                self.accepted.load(atomic::Ordering::Relaxed)
+            }
+
+            pub fn rate(&self) -> f64 {
+                self.accepted() as f64 / self.proposed().max(1) as f64
+            }
+
+            pub fn reset(&self) {
+                self.proposed.store(0, atomic::Ordering::Relaxed);
+                self.accepted.store(0, atomic::Ordering::Relaxed);
+            }
+
+            fn record(&self, accepted: bool) {
+                self.proposed.fetch_add(1, atomic::Ordering::Relaxed);
+                if accepted {
+                    self.accepted.fetch_add(1, atomic::Ordering::Relaxed);
+                }
+            }
+        }
+
+        pub struct Sampler<
+            D: Domain,
+            P: Fn(&D, &mut dyn rand::RngCore) -> D,
+            L: scale::Scale = scale::Linear,
+        > {
+            pd: std::marker::PhantomData<L>,
             pub proposal: Arc<P>,
+            pub init: Option<D>,
+            acceptance: Acceptance,
         }
-        impl<D: Domain, P: Fn(&D) -> D> Sampler<D, P> {
+        impl<D: Domain, P: Fn(&D, &mut dyn rand::RngCore) -> D> Sampler<D, P> {
             #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
             pub fn new(proposal: P) -> Self {
+                (Self::builder().proposal(proposal).build()).unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, P> {
+                Builder {
+                    proposal: None,
+                    scale: None,
+                    init: None,
+                }
+            }
+        }
+        impl<D: Domain, P: Fn(&D, &mut dyn rand::RngCore) -> D, L: scale::Scale> Sampler<D, P, L> {
+            #[doc = "Interpret pdf values on another scale"]
+            pub fn scale<M: scale::Scale>(self) -> Sampler<D, P, M> {
                 Sampler {
                     pd: std::marker::PhantomData,
-                    proposal: Arc::new(proposal),
+                    proposal: self.proposal,
+                    init: self.init,
+                    acceptance: self.acceptance,
+                }
+            }
+
+            #[doc = "Handle on the acceptance counters, shared by every chain drawn from this sampler"]
+            pub fn acceptance(&self) -> Acceptance {
+                self.acceptance.clone()
+            }
+
+            fn start<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> D {
+                (self.init.clone()).unwrap_or_else(|| D::random_with(gen))
+            }
+        }
+
+        #[doc = "Proposal perturbing the state, as set by the builder's `perturb`"]
+        pub type Perturbation<D: Perturb> = impl Fn(&D, &mut dyn rand::RngCore) -> D;
+
+        fn perturbation<D: Perturb>(scale: f64) -> Perturbation<D> {
+            move |x: &D, gen: &mut dyn rand::RngCore| x.perturb(scale, gen)
+        }
+
+        pub struct Builder<D: Domain, P: Fn(&D, &mut dyn rand::RngCore) -> D> {
+            proposal: Option<P>,
+            scale: Option<f64>,
+            init: Option<D>,
+        }
+        impl<D: Domain, P: Fn(&D, &mut dyn rand::RngCore) -> D> Builder<D, P> {
+            pub fn proposal(self, proposal: P) -> Self {
+                Builder {
+                    proposal: Some(proposal),
+                    ..self
                 }
             }
+
+            pub fn init(self, init: D) -> Self {
+                Builder {
+                    init: Some(init),
+                    ..self
+                }
+            }
+        }
+        impl<D: Domain + Perturb> Builder<D, Perturbation<D>> {
+            #[doc = "Propose by perturbing the state by `scale`"]
+            pub fn perturb(self, scale: f64) -> Self {
+                Builder {
+                    proposal: Some(perturbation(scale)),
+                    scale: Some(scale),
+                    ..self
+                }
+            }
+        }
+        impl<D: Domain, P: Fn(&D, &mut dyn rand::RngCore) -> D> Build<D> for Builder<D, P> {
+            type Sampler = Sampler<D, P>;
+            fn build(self) -> Result<Sampler<D, P>, Error> {
+                if self
+                    .scale
+                    .map_or(false, |scale| scale.is_nan() || scale <= 0.0)
+                {
+                    return Err(Error::NotPositive("scale"));
+                }
+                let proposal = self.proposal.ok_or(Error::Missing("proposal"))?;
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    proposal: Arc::new(proposal),
+                    init: self.init,
+                    acceptance: Acceptance::default(),
+                })
+            }
         }
-        impl<D: Domain, P: Fn(&D) -> D> super::Sampler<D> for Sampler<D, P> {
-            type Iter<F: FnMut(&D) -> f64> = impl Iterator<Item = D>;
-            fn sample<F: FnMut(&D) -> f64>(&self, mut pdf: F) -> Self::Iter<F> {
-                let proposal = self.proposal.clone();
-                let mut state = D::random().next().unwrap();
+        impl<D: Domain, P: Fn(&D, &mut dyn rand::RngCore) -> D, L: scale::Scale> super::Sampler<D>
+            for Sampler<D, P, L>
+        {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let mut gen = gen;
+                let state = self.start(&mut gen);
+                self.resume_with_rng(pdf, state, gen)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                mut pdf: F,
+                mut state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let (proposal, acceptance) = (self.proposal.clone(), self.acceptance.clone());
                 let mut prob = pdf(&state);
 
-                let mut aux = rand::thread_rng();
+                let mut aux = gen;
                 std::iter::from_fn(move || {
-                    let new_state = proposal(&state);
-                    let new_prob = pdf(&new_state);
-
-                    use rand::Rng;
-                    let aux = aux.gen_range(0.0..1.0);
-                    if aux <= new_prob / prob {
-                        state = new_state;
-                        prob = new_prob;
-                    }
-
+                    let accepted = step::<L, _, _, _, _>(
+                        &*proposal, &mut pdf, &mut state, &mut prob, &mut aux,
+                    );
+                    acceptance.record(accepted);
                     Some(state.clone())
                 })
             }
+            fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+                &self,
+                mut pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                let mut aux = gen;
+                let mut state = self.start(&mut aux);
+                let mut prob = pdf(&state);
+
+                for _ in 0..n {
+                    let proposal = &*self.proposal;
+                    let accepted =
+                        step::<L, _, _, _, _>(proposal, &mut pdf, &mut state, &mut prob, &mut aux);
+                    self.acceptance.record(accepted);
+                    visit(&state);
+                }
+            }
+        }
+
+        fn step<
+            L: scale::Scale,
+            D: Domain,
+            P: Fn(&D, &mut dyn rand::RngCore) -> D,
+            F: FnMut(&D) -> f64,
+            R: rand::Rng,
+        >(
+            proposal: &P,
+            pdf: &mut F,
+            state: &mut D,
+            prob: &mut f64,
+            aux: &mut R,
+        ) -> bool {
+            let new_state = proposal(state, aux);
+            let new_prob = pdf(&new_state);
+
+            let aux = aux.gen_range(0.0..1.0);
+            let accepted = aux <= L::ratio(new_prob, *prob);
+            if accepted {
+                *state = new_state;
+                *prob = new_prob;
+            }
+            accepted
         }
 
         #[cfg(test)]
@@ -143,155 +534,3186 @@ pub mod univar {
             #[test]
             fn gaussian() {
                 super::test::sample(
-                    univar::Metropolis::new(|&_| Z::<256>::random().next().unwrap()),
+                    univar::Metropolis::builder()
+                        .proposal(|_: &Z<256>, gen: &mut dyn rand::RngCore| Z::random_with(gen))
+                        .build()
+                        .unwrap(),
                     dist::univar::gaussian(128.0, 32.0),
                 );
             }
-        }
-    }
-}
 
-#[doc = "Sample from multiple correlated domain"]
-pub mod multivar {
-    use super::*;
+            #[test]
+            fn visit() {
+                use sampler::Sampler;
+                let (mut count, mut sum) = (0, 0.0);
+                univar::Metropolis::builder()
+                    .proposal(|_: &Z<256>, gen: &mut dyn rand::RngCore| Z::random_with(gen))
+                    .build()
+                    .unwrap()
+                    .pick(2)
+                    .burn(100)
+                    .for_each_sample(dist::univar::gaussian(128.0, 32.0), 5000, |x| {
+                        count += 1;
+                        sum += x.0 as f64;
+                    });
+                assert_eq!(count, 5000);
+                assert!((sum / count as f64 - 128.0).abs() < 5.0);
+            }
 
-    pub use gibbs::Sampler as Gibbs;
+            #[test]
+            fn builder() {
+                let missing =
+                    univar::Metropolis::<f64, fn(&f64, &mut dyn rand::RngCore) -> f64>::builder()
+                        .init(3.0)
+                        .build();
+                assert_eq!(missing.err(), Some(Error::Missing("proposal")));
+                let sampler = univar::Metropolis::builder()
+                    .proposal(|x: &f64, _: &mut dyn rand::RngCore| x + 1.0)
+                    .init(3.0)
+                    .build()
+                    .unwrap();
+                assert_eq!(sampler.init, Some(3.0));
+                let zero = univar::Metropolis::<f64, _>::builder().perturb(0.0).build();
+                assert_eq!(zero.err(), Some(Error::NotPositive("scale")));
 
-    #[doc = "Gibbs Sampling Algorithm"]
-    pub mod gibbs {
+                use sampler::Sampler;
+                let pdf = |x: &f64| (-x * x / 2.0).exp();
+                let draw = || -> Vec<f64> {
+                    let sampler = univar::Metropolis::builder()
+                        .perturb(1.0)
+                        .seed(42)
+                        .build()
+                        .unwrap();
+                    sampler.sample(pdf).take(10).collect()
+                };
+                assert_eq!(draw(), draw());
+            }
+
+            #[test]
+            fn visit_seeded() {
+                use rand::SeedableRng;
+                use sampler::Sampler;
+                let sampler = univar::Metropolis::builder()
+                    .proposal(perturb::<f64>(1.0))
+                    .build()
+                    .unwrap();
+                let pdf = |x: &f64| (-x * x / 2.0).exp();
+                let gen = || rand::rngs::StdRng::seed_from_u64(7);
+                let mut xs = vec![];
+                sampler.for_each_sample_with_rng(pdf, 50, |x| xs.push(*x), gen());
+                let ys: Vec<f64> = sampler.sample_with_rng(pdf, gen()).take(50).collect();
+                assert_eq!(xs, ys);
+            }
+
+            #[test]
+            fn acceptance() {
+                use sampler::Sampler;
+                let sampler = univar::Metropolis::builder()
+                    .proposal(perturb::<f64>(1.0))
+                    .build()
+                    .unwrap();
+                let acceptance = sampler.acceptance();
+                let n = sampler
+                    .burn(100)
+                    .sample(|x: &f64| (-x * x / 2.0).exp())
+                    .take(1000)
+                    .count();
+                assert_eq!(n, 1000);
+                assert_eq!(acceptance.proposed(), 1100);
+                assert!(0.5 < acceptance.rate() && acceptance.rate() < 1.0);
+                acceptance.reset();
+                assert_eq!(acceptance.rate(), 0.0);
+            }
+
+            #[test]
+            fn log_scale() {
+                use rand::{Rng, SeedableRng};
+                use sampler::Sampler;
+                let proposal = |x: &f64, gen: &mut dyn rand::RngCore| x + gen.gen_range(-0.1..0.1);
+                let gen = rand::rngs::SmallRng::seed_from_u64(0);
+                let xs: Vec<f64> = univar::Metropolis::builder()
+                    .proposal(proposal)
+                    .build()
+                    .unwrap()
+                    .scale::<scale::Log>()
+                    .burn(1000)
+                    .sample_with_rng(|x: &f64| -1000.0 - 50.0 * (x - 3.0).powi(2), gen)
+                    .take(5000)
+                    .collect();
+                assert!((xs.iter().sum::<f64>() / 5000.0 - 3.0).abs() < 0.1);
+            }
+        }
+    }
+
+    #[doc = "Exact Sampling from closed-form generator"]
+    pub mod exact {
         use super::*;
         use std::sync::*;
 
-        pub struct Sampler<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> {
+        pub struct Sampler<D: na::Scalar, G: Fn(&mut dyn rand::RngCore) -> D> {
             pd: std::marker::PhantomData<D>,
-            pub dim: R,
-            pub sampler: Arc<S>,
+            pub generator: Arc<G>,
         }
-        impl<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> Sampler<D, R, S> {
+        impl<D: na::Scalar, G: Fn(&mut dyn rand::RngCore) -> D> Sampler<D, G> {
             #[allow(unused)]
-            pub fn new(dim: R, sampler: S) -> Self {
+            pub fn new(generator: G) -> Self {
                 Sampler {
                     pd: std::marker::PhantomData,
-                    dim,
-                    sampler: Arc::new(sampler),
+                    generator: Arc::new(generator),
                 }
             }
         }
-        impl<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>>
-            super::Sampler<nd::Array<D, R>> for Sampler<D, R, S>
-        {
-            type Iter<F: FnMut(&nd::Array<D, R>) -> f64> = impl Iterator<Item = nd::Array<D, R>>;
-            fn sample<F: FnMut(&nd::Array<D, R>) -> f64>(&self, mut pdf: F) -> Self::Iter<F> {
-                let mut init = D::random();
-                let mut state =
-                    nd::Array::from_shape_fn(self.dim.clone(), |_| init.next().unwrap());
-
-                let sampler = self.sampler.clone();
-                let (dim, ptr) = (state.raw_dim(), state.as_mut_ptr());
-
-                std::iter::repeat_with(move || unsafe {
-                    nd::ArrayViewMut::from_shape_ptr(dim.clone(), ptr).into_iter()
-                })
-                .flatten()
-                .map(move |old_value| {
-                    let new_value = sampler
-                        .sample(|value| {
-                            drop(std::mem::replace(old_value, value.clone()));
-                            pdf(&state)
-                        })
-                        .next()
-                        .unwrap();
-                    drop(std::mem::replace(old_value, new_value));
-                    state.clone()
-                })
+        impl<D: na::Scalar, G: Fn(&mut dyn rand::RngCore) -> D> super::Sampler<D> for Sampler<D, G> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                _: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let (generator, mut aux) = (self.generator.clone(), gen);
+                std::iter::from_fn(move || Some(generator(&mut aux)))
+            }
+            // draws are independent, there's no state to carry on from
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                _: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                self.sample_with_rng(pdf, gen)
             }
         }
 
         #[cfg(test)]
         mod tests {
             use super::*;
-            use modular::*;
 
             #[test]
             fn gaussian() {
+                use rand::SeedableRng;
                 use sampler::Sampler;
-                super::test::sample(
-                    univar::Icdf::<Z<256>>::new().gibbs(nd::Dim([2])).burn(1000),
-                    dist::multivar::gaussian(
-                        na::vector![128.0, 128.0],
-                        na::matrix![
-                            128.0, 32.0;
-                            32.0, 64.0;
-                        ],
-                    ),
-                );
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let xs: Vec<f64> = dist::univar::gaussian_exact(128.0, 32.0)
+                    .sample_with_rng(|_: &f64| 1.0, gen)
+                    .take(20000)
+                    .collect();
+                let moments: stats::Moments = xs.iter().cloned().collect();
+                assert!((moments.mean - 128.0).abs() < 1.0);
+                assert!((moments.variance().sqrt() - 32.0).abs() < 1.0);
             }
         }
     }
-}
 
-#[doc = "Sampler adapters"]
-pub mod adapter {
-    use super::*;
-
-    pub use burn::Sampler as Burn;
-    pub use pick::Sampler as Pick;
-
-    #[doc = "Discard non-equilibrium samples"]
-    pub mod burn {
+    #[doc = "Exact Sampling by Coupling from the Past"]
+    pub mod cftp {
         use super::*;
 
-        pub struct Sampler<D: na::Scalar, S: super::Sampler<D>> {
-            pd: std::marker::PhantomData<D>,
-            pub sampler: S,
-            pub skip: usize,
+        #[doc = "Propp-Wilson coupling from the past, for an update monotone between extremal states"]
+        pub fn propp_wilson<D, U, R>(bottom: &D, top: &D, update: U, gen: &mut R) -> D
+        where
+            D: PartialEq + Clone,
+            U: Fn(&D, f64) -> D,
+            R: rand::Rng + ?Sized,
+        {
+            let mut us: Vec<f64> = vec![];
+            let mut t = 1;
+            loop {
+                while us.len() < t {
+                    us.push(gen.gen_range(0.0..1.0));
+                }
+                let (mut lo, mut hi) = (bottom.clone(), top.clone());
+                for u in us[..t].iter().rev() {
+                    lo = update(&lo, *u);
+                    hi = update(&hi, *u);
+                }
+                if lo == hi {
+                    return lo;
+                }
+                t *= 2;
+            }
         }
-        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+
+        pub struct Sampler<D: Discrete, L: scale::Scale = scale::Linear> {
+            pd: std::marker::PhantomData<(D, L)>,
+        }
+        impl<D: Discrete> Sampler<D> {
             #[allow(unused)]
-            pub fn new(sampler: S, skip: usize) -> Self {
+            pub fn new() -> Self {
                 Sampler {
                     pd: std::marker::PhantomData,
-                    sampler,
-                    skip,
                 }
             }
         }
-        impl<D: na::Scalar, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
-            type Iter<F: FnMut(&D) -> f64> = impl Iterator<Item = D>;
-            fn sample<F: FnMut(&D) -> f64>(&self, pdf: F) -> Self::Iter<F> {
-                self.sampler.sample(pdf).skip(self.skip)
-            }
-        }
-    }
-
-    #[doc = "Pick samples over intervals"]
-    pub mod pick {
-        use super::*;
-
-        pub struct Sampler<D: na::Scalar, S: super::Sampler<D>> {
-            pd: std::marker::PhantomData<D>,
-            pub sampler: S,
-            pub interval: usize,
-        }
-        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
-            #[allow(unused)]
-            pub fn new(sampler: S, interval: usize) -> Self {
+        impl<D: Discrete, L: scale::Scale> Sampler<D, L> {
+            #[doc = "Interpret pdf values on another scale"]
+            pub fn scale<M: scale::Scale>(self) -> Sampler<D, M> {
                 Sampler {
                     pd: std::marker::PhantomData,
-                    sampler,
-                    interval,
                 }
             }
         }
-        impl<D: na::Scalar, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
-            type Iter<F: FnMut(&D) -> f64> = impl Iterator<Item = D>;
-            fn sample<F: FnMut(&D) -> f64>(&self, pdf: F) -> Self::Iter<F> {
-                let mut sampler = self.sampler.sample(pdf);
-                let interval = self.interval;
-                std::iter::from_fn(move || {
-                    (1..interval).for_each(|_| drop(sampler.next()));
-                    sampler.next()
-                })
+        impl<D: Discrete, L: scale::Scale> super::Sampler<D> for Sampler<D, L> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let xs: Vec<D> = D::iter().collect();
+                let ws: Vec<f64> = scale::weights::<L, _>(xs.iter().map(pdf));
+
+                // a zero weight inside the support would keep the extremal chains apart forever
+                let first = ws
+                    .iter()
+                    .position(|w| *w > 0.0)
+                    .expect("pdf isn't positive");
+                let last = ws.iter().rposition(|w| *w > 0.0).unwrap();
+                assert!(
+                    ws[first..=last].iter().all(|w| *w > 0.0),
+                    "support isn't contiguous"
+                );
+                let (xs, ws) = (xs[first..=last].to_vec(), ws[first..=last].to_vec());
+
+                // nearest-neighbour metropolis in iteration order, monotone under shared uniforms
+                let update = move |&i: &usize, u: f64| {
+                    let (v, up) = if u < 0.5 {
+                        (2.0 * u, false)
+                    } else {
+                        (2.0 * u - 1.0, true)
+                    };
+                    let j = match up {
+                        false if i > 0 => i - 1,
+                        true if i + 1 < ws.len() => i + 1,
+                        _ => return i,
+                    };
+                    if v * ws[i] < ws[j] {
+                        j
+                    } else {
+                        i
+                    }
+                };
+
+                let top = xs.len() - 1;
+                let mut aux = gen;
+                std::iter::from_fn(move || {
+                    let i = propp_wilson(&0, &top, &update, &mut aux);
+                    Some(xs[i].clone())
+                })
+            }
+            // draws are independent, there's no state to carry on from
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                _: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                self.sample_with_rng(pdf, gen)
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use modular::*;
+            use sampler::Sampler as _;
+
+            #[test]
+            fn exact() {
+                let ws = [1.0, 3.0, 0.5, 2.0, 4.0, 1.0, 0.5, 2.0];
+                let mut counts = [0.0; 8];
+                univar::Cftp::<Z<8>>::new()
+                    .sample(|z: &Z<8>| ws[z.0])
+                    .take(20000)
+                    .for_each(|z| counts[z.0] += 1.0 / 20000.0);
+                let sum: f64 = ws.iter().sum();
+                for (c, w) in counts.iter().zip(ws.iter()) {
+                    assert!((c - w / sum).abs() < 0.02);
+                }
+
+                let edges = [0.0, 0.0, 1.0, 2.0, 0.0, 0.0, 0.0, 0.0];
+                let zs: Vec<Z<8>> = (univar::Cftp::<Z<8>>::new())
+                    .sample(|z: &Z<8>| edges[z.0])
+                    .take(1000)
+                    .collect();
+                assert!(zs.iter().all(|z| z.0 == 2 || z.0 == 3));
+            }
+        }
+    }
+}
+
+#[doc = "Sample from multiple correlated domain"]
+pub mod multivar {
+    use super::*;
+
+    pub use elliptical::Sampler as Elliptical;
+    pub use fixed::Sampler as FixedGibbs;
+    pub use gibbs::Sampler as Gibbs;
+    pub use pcn::Sampler as Pcn;
+
+    fn cholesky(cov: nd::Array2<f64>) -> Option<nd::Array2<f64>> {
+        let n = cov.nrows();
+        let l = ns::ToNalgebra::into_nalgebra(cov).cholesky()?.l();
+        Some(nd::Array2::from_shape_fn((n, n), |(i, j)| l[(i, j)]))
+    }
+
+    // validated mean and cholesky factor of a gaussian prior
+    fn prior(
+        mean: Option<nd::Array1<f64>>,
+        cov: Option<nd::Array2<f64>>,
+    ) -> Result<(nd::Array1<f64>, nd::Array2<f64>), Error> {
+        let mean = mean.ok_or(Error::Missing("mean"))?;
+        let cov = cov.ok_or(Error::Missing("covariance"))?;
+        if cov.dim() != (mean.len(), mean.len()) {
+            return Err(Error::Mismatch("covariance"));
+        }
+        let chol = cholesky(cov).ok_or(Error::NotPositiveDefinite("covariance"))?;
+        Ok((mean, chol))
+    }
+
+    fn draw(chol: &nd::Array2<f64>, gen: &mut impl rand::Rng) -> nd::Array1<f64> {
+        let z = nd::Array1::from_shape_fn(chol.nrows(), |_| dist::normal(gen));
+        chol.dot(&z)
+    }
+
+    #[doc = "Gibbs Sampling Algorithm"]
+    pub mod gibbs {
+        use super::*;
+        use std::sync::*;
+
+        pub struct Sampler<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub dim: R,
+            pub sampler: Arc<S>,
+        }
+        impl<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> Sampler<D, R, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(dim: R, sampler: S) -> Self {
+                (Self::builder().dim(dim).sampler(sampler).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, R, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    dim: None,
+                    sampler: None,
+                }
+            }
+        }
+
+        pub struct Builder<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            dim: Option<R>,
+            sampler: Option<S>,
+        }
+        impl<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> Builder<D, R, S> {
+            pub fn dim(self, dim: R) -> Self {
+                Builder {
+                    dim: Some(dim),
+                    ..self
+                }
+            }
+
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+        }
+        impl<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>> Build<nd::Array<D, R>>
+            for Builder<D, R, S>
+        {
+            type Sampler = Sampler<D, R, S>;
+            fn build(self) -> Result<Sampler<D, R, S>, Error> {
+                let dim = self.dim.ok_or(Error::Missing("dim"))?;
+                if dim.size() == 0 {
+                    return Err(Error::NotPositive("dim"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    dim,
+                    sampler: Arc::new(self.sampler.ok_or(Error::Missing("sampler"))?),
+                })
+            }
+        }
+        impl<D: Domain, R: nd::Dimension + 'static, S: super::Sampler<D>>
+            super::Sampler<nd::Array<D, R>> for Sampler<D, R, S>
+        {
+            type Iter<F: FnMut(&nd::Array<D, R>) -> f64, G: rand::Rng> =
+                impl Iterator<Item = nd::Array<D, R>>;
+            fn sample_with_rng<F: FnMut(&nd::Array<D, R>) -> f64, G: rand::Rng>(
+                &self,
+                pdf: F,
+                mut gen: G,
+            ) -> Self::Iter<F, G> {
+                let state =
+                    nd::Array::from_shape_fn(self.dim.clone(), |_| D::random_with(&mut gen));
+                self.resume_with_rng(pdf, state, gen)
+            }
+            fn resume_with_rng<F: FnMut(&nd::Array<D, R>) -> f64, G: rand::Rng>(
+                &self,
+                mut pdf: F,
+                mut state: nd::Array<D, R>,
+                mut gen: G,
+            ) -> Self::Iter<F, G> {
+                assert!(state.is_standard_layout(), "state isn't in standard layout");
+                let sampler = self.sampler.clone();
+                let (dim, ptr) = (state.raw_dim(), state.as_mut_ptr());
+
+                std::iter::repeat_with(move || unsafe {
+                    nd::ArrayViewMut::from_shape_ptr(dim.clone(), ptr).into_iter()
+                })
+                .flatten()
+                .map(move |old_value| {
+                    let current = old_value.clone();
+                    let new_value = sampler
+                        .resume_with_rng(
+                            |value| {
+                                drop(std::mem::replace(old_value, value.clone()));
+                                pdf(&state)
+                            },
+                            current,
+                            &mut gen,
+                        )
+                        .next()
+                        .unwrap();
+                    drop(std::mem::replace(old_value, new_value));
+                    state.clone()
+                })
+            }
+            fn for_each_sample_with_rng<
+                F: FnMut(&nd::Array<D, R>) -> f64,
+                V: FnMut(&nd::Array<D, R>),
+                G: rand::Rng,
+            >(
+                &self,
+                mut pdf: F,
+                n: usize,
+                mut visit: V,
+                mut gen: G,
+            ) {
+                let mut state =
+                    nd::Array::from_shape_fn(self.dim.clone(), |_| D::random_with(&mut gen));
+
+                for i in (0..state.len()).cycle().take(n) {
+                    let current = state.as_slice().unwrap()[i].clone();
+                    let new_value = (self.sampler)
+                        .resume_with_rng(
+                            |value| {
+                                state.as_slice_mut().unwrap()[i] = value.clone();
+                                pdf(&state)
+                            },
+                            current,
+                            &mut gen,
+                        )
+                        .next()
+                        .unwrap();
+                    state.as_slice_mut().unwrap()[i] = new_value;
+                    visit(&state);
+                }
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use modular::*;
+
+            #[test]
+            fn gaussian() {
+                use sampler::Sampler;
+                super::test::sample(
+                    univar::Icdf::<Z<256>>::new().gibbs(nd::Dim([2])).burn(1000),
+                    dist::multivar::gaussian(
+                        na::vector![128.0, 128.0],
+                        na::matrix![
+                            128.0, 32.0;
+                            32.0, 64.0;
+                        ],
+                    ),
+                );
+            }
+        }
+    }
+
+    #[doc = "Gibbs Sampling over vectors of compile-time dimension"]
+    pub mod fixed {
+        use super::*;
+        use std::sync::*;
+
+        pub struct Sampler<D: Domain, S: super::Sampler<D>, const N: usize> {
+            pd: std::marker::PhantomData<D>,
+            pub sampler: Arc<S>,
+        }
+        impl<D: Domain, S: super::Sampler<D>, const N: usize> Sampler<D, S, N> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S) -> Self {
+                (Self::builder().sampler(sampler).build()).unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S, N> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                }
+            }
+        }
+
+        pub struct Builder<D: Domain, S: super::Sampler<D>, const N: usize> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+        }
+        impl<D: Domain, S: super::Sampler<D>, const N: usize> Builder<D, S, N> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+        }
+        impl<D: Domain, S: super::Sampler<D>, const N: usize> Build<na::SVector<D, N>>
+            for Builder<D, S, N>
+        {
+            type Sampler = Sampler<D, S, N>;
+            fn build(self) -> Result<Sampler<D, S, N>, Error> {
+                if N == 0 {
+                    return Err(Error::NotPositive("dim"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: Arc::new(self.sampler.ok_or(Error::Missing("sampler"))?),
+                })
+            }
+        }
+        impl<D: Domain, S: super::Sampler<D>, const N: usize> super::Sampler<na::SVector<D, N>>
+            for Sampler<D, S, N>
+        {
+            type Iter<F: FnMut(&na::SVector<D, N>) -> f64, R: rand::Rng> =
+                impl Iterator<Item = na::SVector<D, N>>;
+            fn sample_with_rng<F: FnMut(&na::SVector<D, N>) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                mut gen: R,
+            ) -> Self::Iter<F, R> {
+                let state = na::SVector::<D, N>::from_fn(|_, _| D::random_with(&mut gen));
+                self.resume_with_rng(pdf, state, gen)
+            }
+            fn resume_with_rng<F: FnMut(&na::SVector<D, N>) -> f64, R: rand::Rng>(
+                &self,
+                mut pdf: F,
+                mut state: na::SVector<D, N>,
+                mut gen: R,
+            ) -> Self::Iter<F, R> {
+                let sampler = self.sampler.clone();
+                (0..N).cycle().map(move |i| {
+                    let current = state[i].clone();
+                    let new_value = sampler
+                        .resume_with_rng(
+                            |value| {
+                                state[i] = value.clone();
+                                pdf(&state)
+                            },
+                            current,
+                            &mut gen,
+                        )
+                        .next()
+                        .unwrap();
+                    state[i] = new_value;
+                    state.clone()
+                })
+            }
+            fn for_each_sample_with_rng<F, V, R>(
+                &self,
+                mut pdf: F,
+                n: usize,
+                mut visit: V,
+                mut gen: R,
+            ) where
+                F: FnMut(&na::SVector<D, N>) -> f64,
+                V: FnMut(&na::SVector<D, N>),
+                R: rand::Rng,
+            {
+                let mut state = na::SVector::<D, N>::from_fn(|_, _| D::random_with(&mut gen));
+
+                for i in (0..N).cycle().take(n) {
+                    let current = state[i].clone();
+                    let new_value = (self.sampler)
+                        .resume_with_rng(
+                            |value| {
+                                state[i] = value.clone();
+                                pdf(&state)
+                            },
+                            current,
+                            &mut gen,
+                        )
+                        .next()
+                        .unwrap();
+                    state[i] = new_value;
+                    visit(&state);
+                }
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use modular::*;
+
+            #[test]
+            fn gaussian() {
+                use rand::SeedableRng;
+                use sampler::Sampler;
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let xs: Vec<na::SVector<Z<256>, 2>> = univar::Icdf::<Z<256>>::new()
+                    .gibbs_fixed::<2>()
+                    .burn(100)
+                    .sample_with_rng(
+                        |xs: &na::SVector<Z<256>, 2>| {
+                            let (x, y) = (xs[0].0 as f64 - 128.0, xs[1].0 as f64 - 96.0);
+                            (-(x * x + y * y) / (2.0 * 32.0f64.powi(2))).exp()
+                        },
+                        gen,
+                    )
+                    .take(5000)
+                    .collect();
+                let mean = |k: usize| xs.iter().map(|x| x[k].0 as f64).sum::<f64>() / 5000.0;
+                assert!((mean(0) - 128.0).abs() < 3.0);
+                assert!((mean(1) - 96.0).abs() < 3.0);
+            }
+        }
+    }
+
+    #[doc = "Elliptical Slice Sampling under gaussian prior"]
+    pub mod elliptical {
+        use super::*;
+
+        pub struct Sampler<L: scale::Scale = scale::Linear> {
+            pd: std::marker::PhantomData<L>,
+            pub mean: nd::Array1<f64>,
+            pub chol: nd::Array2<f64>,
+        }
+        impl Sampler {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(mean: nd::Array1<f64>, cov: nd::Array2<f64>) -> Self {
+                (Self::builder().mean(mean).cov(cov).build()).unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+        impl<L: scale::Scale> Sampler<L> {
+            #[doc = "Interpret pdf values on another scale"]
+            pub fn scale<M: scale::Scale>(self) -> Sampler<M> {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    mean: self.mean,
+                    chol: self.chol,
+                }
+            }
+        }
+        impl Sampler {
+            pub fn builder() -> Builder {
+                Builder {
+                    mean: None,
+                    cov: None,
+                }
+            }
+        }
+
+        pub struct Builder {
+            mean: Option<nd::Array1<f64>>,
+            cov: Option<nd::Array2<f64>>,
+        }
+        impl Builder {
+            pub fn mean(self, mean: nd::Array1<f64>) -> Self {
+                Builder {
+                    mean: Some(mean),
+                    ..self
+                }
+            }
+
+            pub fn cov(self, cov: nd::Array2<f64>) -> Self {
+                Builder {
+                    cov: Some(cov),
+                    ..self
+                }
+            }
+        }
+        impl Build<nd::Array1<f64>> for Builder {
+            type Sampler = Sampler;
+            fn build(self) -> Result<Sampler, Error> {
+                let (mean, chol) = prior(self.mean, self.cov)?;
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    mean,
+                    chol,
+                })
+            }
+        }
+
+        // the pdf to sample is the likelihood, on top of the gaussian prior
+        impl<L: scale::Scale> super::Sampler<nd::Array1<f64>> for Sampler<L> {
+            type Iter<F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng> =
+                impl Iterator<Item = nd::Array1<f64>>;
+            fn sample_with_rng<F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                mut gen: R,
+            ) -> Self::Iter<F, R> {
+                let state = &self.mean + &draw(&self.chol, &mut gen);
+                self.resume_with_rng(pdf, state, gen)
+            }
+            fn resume_with_rng<F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng>(
+                &self,
+                mut pdf: F,
+                mut state: nd::Array1<f64>,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let mut aux = gen;
+                let (mean, chol) = (self.mean.clone(), self.chol.clone());
+                let mut prob = pdf(&state);
+                assert!(L::ln(prob) > f64::NEG_INFINITY, "likelihood isn't positive");
+
+                std::iter::from_fn(move || {
+                    step::<L, _, _>(&mean, &chol, &mut pdf, &mut state, &mut prob, &mut aux);
+                    Some(state.clone())
+                })
+            }
+            fn for_each_sample_with_rng<
+                F: FnMut(&nd::Array1<f64>) -> f64,
+                V: FnMut(&nd::Array1<f64>),
+                R: rand::Rng,
+            >(
+                &self,
+                mut pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                let mut aux = gen;
+                let mut state = &self.mean + &draw(&self.chol, &mut aux);
+                let mut prob = pdf(&state);
+                assert!(L::ln(prob) > f64::NEG_INFINITY, "likelihood isn't positive");
+
+                for _ in 0..n {
+                    let (mean, chol) = (&self.mean, &self.chol);
+                    step::<L, _, _>(mean, chol, &mut pdf, &mut state, &mut prob, &mut aux);
+                    visit(&state);
+                }
+            }
+        }
+
+        fn step<L: scale::Scale, F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng>(
+            mean: &nd::Array1<f64>,
+            chol: &nd::Array2<f64>,
+            pdf: &mut F,
+            state: &mut nd::Array1<f64>,
+            prob: &mut f64,
+            aux: &mut R,
+        ) {
+            let nu = draw(chol, aux);
+            let level = L::mul(*prob, aux.gen_range(0.0..1.0));
+
+            let mut theta = aux.gen_range(0.0..std::f64::consts::TAU);
+            let (mut lo, mut hi) = (theta - std::f64::consts::TAU, theta);
+            loop {
+                let new_state = mean + &((&*state - mean) * theta.cos() + &nu * theta.sin());
+                let new_prob = pdf(&new_state);
+                if new_prob > level {
+                    *state = new_state;
+                    *prob = new_prob;
+                    break;
+                }
+                if theta < 0.0 {
+                    lo = theta;
+                } else {
+                    hi = theta;
+                }
+                // bracket collapsed onto the current state under rounding
+                if lo >= hi {
+                    break;
+                }
+                theta = aux.gen_range(lo..hi);
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            // posterior mean of the prior N(0, [1 .5; .5 1]) under likelihood N(x0; 1, 1)
+            #[test]
+            fn gaussian() {
+                use rand::SeedableRng;
+                use sampler::Sampler;
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let xs: Vec<nd::Array1<f64>> = multivar::Elliptical::builder()
+                    .mean(nd::Array1::from(vec![0.0, 0.0]))
+                    .cov(nd::Array2::from_shape_vec((2, 2), vec![1.0, 0.5, 0.5, 1.0]).unwrap())
+                    .build()
+                    .unwrap()
+                    .burn(100)
+                    .sample_with_rng(
+                        |xs: &nd::Array1<f64>| (-(xs[0] - 1.0).powi(2) / 2.0).exp(),
+                        gen,
+                    )
+                    .take(5000)
+                    .collect();
+                let mean = |k: usize| xs.iter().map(|x| x[k]).sum::<f64>() / 5000.0;
+                assert!((mean(0) - 0.5).abs() < 0.1);
+                assert!((mean(1) - 0.25).abs() < 0.1);
+            }
+        }
+    }
+
+    #[doc = "Preconditioned Crank-Nicolson under gaussian prior"]
+    pub mod pcn {
+        use super::*;
+
+        pub struct Sampler<L: scale::Scale = scale::Linear> {
+            pd: std::marker::PhantomData<L>,
+            pub mean: nd::Array1<f64>,
+            pub chol: nd::Array2<f64>,
+            pub beta: f64,
+        }
+        impl Sampler {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(mean: nd::Array1<f64>, cov: nd::Array2<f64>, beta: f64) -> Self {
+                (Self::builder().mean(mean).cov(cov).beta(beta).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+        }
+        impl<L: scale::Scale> Sampler<L> {
+            #[doc = "Interpret pdf values on another scale"]
+            pub fn scale<M: scale::Scale>(self) -> Sampler<M> {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    mean: self.mean,
+                    chol: self.chol,
+                    beta: self.beta,
+                }
+            }
+        }
+        impl Sampler {
+            pub fn builder() -> Builder {
+                Builder {
+                    mean: None,
+                    cov: None,
+                    beta: 0.2,
+                }
+            }
+        }
+
+        pub struct Builder {
+            mean: Option<nd::Array1<f64>>,
+            cov: Option<nd::Array2<f64>>,
+            beta: f64,
+        }
+        impl Builder {
+            pub fn mean(self, mean: nd::Array1<f64>) -> Self {
+                Builder {
+                    mean: Some(mean),
+                    ..self
+                }
+            }
+
+            pub fn cov(self, cov: nd::Array2<f64>) -> Self {
+                Builder {
+                    cov: Some(cov),
+                    ..self
+                }
+            }
+
+            pub fn beta(self, beta: f64) -> Self {
+                Builder { beta, ..self }
+            }
+        }
+        impl Build<nd::Array1<f64>> for Builder {
+            type Sampler = Sampler;
+            fn build(self) -> Result<Sampler, Error> {
+                if !(self.beta > 0.0 && self.beta <= 1.0) {
+                    return Err(Error::OutOfRange("beta"));
+                }
+                let (mean, chol) = prior(self.mean, self.cov)?;
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    mean,
+                    chol,
+                    beta: self.beta,
+                })
+            }
+        }
+        // the pdf to sample is the likelihood, on top of the gaussian prior
+        impl<L: scale::Scale> super::Sampler<nd::Array1<f64>> for Sampler<L> {
+            type Iter<F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng> =
+                impl Iterator<Item = nd::Array1<f64>>;
+            fn sample_with_rng<F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                mut gen: R,
+            ) -> Self::Iter<F, R> {
+                let state = &self.mean + &draw(&self.chol, &mut gen);
+                self.resume_with_rng(pdf, state, gen)
+            }
+            fn resume_with_rng<F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng>(
+                &self,
+                mut pdf: F,
+                mut state: nd::Array1<f64>,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let mut aux = gen;
+                let (mean, chol, beta) = (self.mean.clone(), self.chol.clone(), self.beta);
+                let mut prob = pdf(&state);
+
+                std::iter::from_fn(move || {
+                    step::<L, _, _>(
+                        &mean, &chol, beta, &mut pdf, &mut state, &mut prob, &mut aux,
+                    );
+                    Some(state.clone())
+                })
+            }
+            fn for_each_sample_with_rng<
+                F: FnMut(&nd::Array1<f64>) -> f64,
+                V: FnMut(&nd::Array1<f64>),
+                R: rand::Rng,
+            >(
+                &self,
+                mut pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                let mut aux = gen;
+                let mut state = &self.mean + &draw(&self.chol, &mut aux);
+                let mut prob = pdf(&state);
+
+                for _ in 0..n {
+                    let (mean, chol, beta) = (&self.mean, &self.chol, self.beta);
+                    step::<L, _, _>(mean, chol, beta, &mut pdf, &mut state, &mut prob, &mut aux);
+                    visit(&state);
+                }
+            }
+        }
+
+        fn step<L: scale::Scale, F: FnMut(&nd::Array1<f64>) -> f64, R: rand::Rng>(
+            mean: &nd::Array1<f64>,
+            chol: &nd::Array2<f64>,
+            beta: f64,
+            pdf: &mut F,
+            state: &mut nd::Array1<f64>,
+            prob: &mut f64,
+            aux: &mut R,
+        ) {
+            let shrink = (1.0 - beta * beta).sqrt();
+            let new_state = mean + &((&*state - mean) * shrink + &draw(chol, aux) * beta);
+            let new_prob = pdf(&new_state);
+
+            let aux = aux.gen_range(0.0..1.0);
+            if aux <= L::ratio(new_prob, *prob) {
+                *state = new_state;
+                *prob = new_prob;
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            // posterior mean of the prior N(0, [1 .5; .5 1]) under likelihood N(x0; 1, 1)
+            #[test]
+            fn gaussian() {
+                use rand::SeedableRng;
+                use sampler::Sampler;
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let xs: Vec<nd::Array1<f64>> = multivar::Pcn::builder()
+                    .mean(nd::Array1::from(vec![0.0, 0.0]))
+                    .cov(nd::Array2::from_shape_vec((2, 2), vec![1.0, 0.5, 0.5, 1.0]).unwrap())
+                    .beta(0.5)
+                    .build()
+                    .unwrap()
+                    .burn(500)
+                    .sample_with_rng(
+                        |xs: &nd::Array1<f64>| (-(xs[0] - 1.0).powi(2) / 2.0).exp(),
+                        gen,
+                    )
+                    .take(20000)
+                    .collect();
+                let mean = |k: usize| xs.iter().map(|x| x[k]).sum::<f64>() / 20000.0;
+                assert!((mean(0) - 0.5).abs() < 0.1);
+                assert!((mean(1) - 0.25).abs() < 0.1);
+            }
+        }
+    }
+}
+
+#[doc = "Sampler adapters"]
+pub mod adapter {
+    use super::*;
+
+    pub use burn::Sampler as Burn;
+    pub use monitor::Sampler as Monitor;
+    pub use parallel::Sampler as Parallel;
+    pub use pick::Interval;
+    pub use pick::Sampler as Pick;
+    pub use seeded::Sampler as Seeded;
+    pub use tempered::Sampler as Tempered;
+    pub use trace::Sampler as Trace;
+    pub use until::Sampler as UntilEss;
+
+    #[doc = "Discard non-equilibrium samples"]
+    pub mod burn {
+        use super::*;
+
+        pub struct Sampler<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub sampler: S,
+            pub skip: usize,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S, skip: usize) -> Self {
+                (Self::builder().sampler(sampler).skip(skip).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                    skip: None,
+                }
+            }
+        }
+
+        pub struct Builder<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+            skip: Option<usize>,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Builder<D, S> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+
+            pub fn skip(self, skip: usize) -> Self {
+                Builder {
+                    skip: Some(skip),
+                    ..self
+                }
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Build<D> for Builder<D, S> {
+            type Sampler = Sampler<D, S>;
+            fn build(self) -> Result<Sampler<D, S>, Error> {
+                let skip = self.skip.ok_or(Error::Missing("skip"))?;
+                if skip == 0 {
+                    return Err(Error::NotPositive("skip"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler.ok_or(Error::Missing("sampler"))?,
+                    skip,
+                })
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                self.sampler.sample_with_rng(pdf, gen).skip(self.skip)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                self.sampler
+                    .resume_with_rng(pdf, state, gen)
+                    .skip(self.skip)
+            }
+            fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+                &self,
+                pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                let mut count = 0;
+                let skip = self.skip;
+                let visit = |x: &D| {
+                    if count >= skip {
+                        visit(x);
+                    }
+                    count += 1;
+                };
+                self.sampler
+                    .for_each_sample_with_rng(pdf, skip + n, visit, gen)
+            }
+        }
+    }
+
+    #[doc = "Pick samples over intervals"]
+    pub mod pick {
+        use super::*;
+        use std::sync::atomic::*;
+
+        const PILOT: usize = 1000;
+
+        #[doc = "Spacing between picked samples"]
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        pub enum Interval {
+            Fixed(usize),
+            #[doc = "The autocorrelation time of a pilot run, measured once"]
+            Auto,
+        }
+
+        #[doc = "Measures the interval from the pilot draws"]
+        pub type Pilot<D> = fn(&[D]) -> usize;
+
+        #[doc = "Intervals `pick` accepts: counts for any domain, `Interval` where it has coordinates"]
+        pub trait IntoInterval<D> {
+            fn into_interval(self) -> (Interval, Option<Pilot<D>>);
+        }
+        impl<D> IntoInterval<D> for usize {
+            fn into_interval(self) -> (Interval, Option<Pilot<D>>) {
+                (Interval::Fixed(self), None)
+            }
+        }
+        impl<D: Coordinates> IntoInterval<D> for Interval {
+            fn into_interval(self) -> (Interval, Option<Pilot<D>>) {
+                (self, Some(pilot::<D>))
+            }
+        }
+
+        pub struct Sampler<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub sampler: S,
+            pub interval: Interval,
+            pilot: Option<Pilot<D>>,
+            measured: AtomicUsize,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S, interval: usize) -> Self {
+                (Self::builder().sampler(sampler).interval(interval).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                    interval: Interval::Fixed(1),
+                    pilot: None,
+                }
+            }
+
+            // the fixed interval, else the one measured by the first chain's pilot run
+            fn interval<I: Iterator<Item = D>>(&self, sampler: &mut I) -> usize {
+                match (self.interval, self.measured.load(Ordering::Relaxed)) {
+                    (Interval::Fixed(interval), _) => interval,
+                    (Interval::Auto, 0) => {
+                        let draws: Vec<D> = sampler.take(PILOT).collect();
+                        let pilot = self.pilot.expect("automatic interval without coordinates");
+                        let interval = pilot(&draws);
+                        self.measured.store(interval, Ordering::Relaxed);
+                        interval
+                    }
+                    (Interval::Auto, interval) => interval,
+                }
+            }
+        }
+
+        pub struct Builder<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+            interval: Interval,
+            pilot: Option<Pilot<D>>,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Builder<D, S> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+
+            pub fn interval<I: IntoInterval<D>>(self, interval: I) -> Self {
+                let (interval, pilot) = interval.into_interval();
+                Builder {
+                    interval,
+                    pilot,
+                    ..self
+                }
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Build<D> for Builder<D, S> {
+            type Sampler = Sampler<D, S>;
+            fn build(self) -> Result<Sampler<D, S>, Error> {
+                if self.interval == Interval::Fixed(0) {
+                    return Err(Error::NotPositive("interval"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler.ok_or(Error::Missing("sampler"))?,
+                    interval: self.interval,
+                    pilot: self.pilot,
+                    measured: AtomicUsize::new(0),
+                })
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let mut sampler = self.sampler.sample_with_rng(pdf, gen);
+                let interval = self.interval(&mut sampler);
+                every(sampler, interval)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let mut sampler = self.sampler.resume_with_rng(pdf, state, gen);
+                let interval = self.interval(&mut sampler);
+                every(sampler, interval)
+            }
+            fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+                &self,
+                pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                let interval = match (self.interval, self.measured.load(Ordering::Relaxed)) {
+                    (Interval::Fixed(interval), _) => interval,
+                    (Interval::Auto, 0) => {
+                        // the pilot run needs the draws themselves
+                        return (self.sample_with_rng(pdf, gen).take(n)).for_each(|x| visit(&x));
+                    }
+                    (Interval::Auto, interval) => interval,
+                };
+                let mut count = 0;
+                let visit = |x: &D| {
+                    count += 1;
+                    if count % interval == 0 {
+                        visit(x);
+                    }
+                };
+                self.sampler
+                    .for_each_sample_with_rng(pdf, interval * n, visit, gen)
+            }
+        }
+
+        fn every<I: Iterator>(mut sampler: I, interval: usize) -> impl Iterator<Item = I::Item> {
+            std::iter::from_fn(move || {
+                (1..interval).for_each(|_| drop(sampler.next()));
+                sampler.next()
+            })
+        }
+
+        // the slowest coordinate's autocorrelation time over the pilot draws
+        fn pilot<D: Coordinates>(draws: &[D]) -> usize {
+            let draws: Vec<Vec<f64>> = draws.iter().map(|x| x.coordinates()).collect();
+            let tau = (0..draws.first().map_or(0, Vec::len))
+                .map(|k| diag::iat(&draws.iter().map(|x| x[k]).collect::<Vec<f64>>()))
+                .fold(1.0, f64::max);
+            tau.ceil() as usize
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use sampler::Sampler;
+
+            #[test]
+            fn fixed() {
+                // vectors aren't `Coordinates`, which fixed picking doesn't need
+                let count = std::cell::Cell::new(0);
+                let xs: Vec<na::SVector<f64, 2>> = (univar::Exact::new(|_| {
+                    count.set(count.get() + 1);
+                    na::vector![count.get() as f64, 0.0]
+                }))
+                .pick(3)
+                .sample(|_: &na::SVector<f64, 2>| 1.0)
+                .take(3)
+                .collect();
+                assert_eq!(
+                    xs.iter().map(|x| x[0]).collect::<Vec<f64>>(),
+                    [3.0, 6.0, 9.0]
+                );
+            }
+
+            #[test]
+            fn auto() {
+                use rand::SeedableRng;
+                let sampler = univar::Metropolis::builder()
+                    .proposal(perturb::<f64>(0.5))
+                    .build()
+                    .unwrap();
+                let sampler = sampler.pick(Interval::Auto);
+                let pdf = |x: &f64| (-x * x / 2.0).exp();
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let xs: Vec<f64> = sampler.sample_with_rng(pdf, gen).take(2000).collect();
+                assert!(diag::ess(&xs) > 500.0);
+
+                // resuming reuses the measured interval instead of another pilot run
+                let interval = sampler.measured.load(Ordering::Relaxed);
+                assert!(interval > 1);
+                let count = std::cell::Cell::new(0);
+                let pdf = |x: &f64| {
+                    count.set(count.get() + 1);
+                    (-x * x / 2.0).exp()
+                };
+                let gen = rand::rngs::StdRng::seed_from_u64(1);
+                let _: Vec<f64> = sampler.resume_with_rng(pdf, 0.0, gen).take(10).collect();
+                assert!(count.get() <= 10 * interval + 1);
+                assert_eq!(sampler.measured.load(Ordering::Relaxed), interval);
+            }
+
+            #[test]
+            fn builder() {
+                let zero = adapter::Pick::builder()
+                    .sampler(univar::Icdf::<modular::Z<8>>::new())
+                    .interval(0)
+                    .build();
+                assert_eq!(zero.err(), Some(Error::NotPositive("interval")));
+                let missing = adapter::Burn::<modular::Z<8>, univar::Icdf<_>>::builder()
+                    .skip(10)
+                    .build();
+                assert_eq!(missing.err(), Some(Error::Missing("sampler")));
+                let zero = adapter::Burn::builder()
+                    .sampler(univar::Icdf::<modular::Z<8>>::new())
+                    .skip(0)
+                    .build();
+                assert_eq!(zero.err(), Some(Error::NotPositive("skip")));
+                let missing = multivar::Gibbs::<_, nd::Ix1, _>::builder()
+                    .sampler(univar::Icdf::<modular::Z<8>>::new())
+                    .build();
+                assert_eq!(missing.err(), Some(Error::Missing("dim")));
+            }
+        }
+    }
+
+    #[doc = "Stop once every coordinate reaches an effective sample size"]
+    pub mod until {
+        use super::*;
+
+        pub struct Sampler<D: na::Scalar + Coordinates, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub sampler: S,
+            pub target: f64,
+        }
+        impl<D: na::Scalar + Coordinates, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S, target: f64) -> Self {
+                (Self::builder().sampler(sampler).target(target).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                    target: None,
+                }
+            }
+        }
+
+        pub struct Builder<D: na::Scalar + Coordinates, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+            target: Option<f64>,
+        }
+        impl<D: na::Scalar + Coordinates, S: super::Sampler<D>> Builder<D, S> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+
+            pub fn target(self, target: f64) -> Self {
+                Builder {
+                    target: Some(target),
+                    ..self
+                }
+            }
+        }
+        impl<D: na::Scalar + Coordinates, S: super::Sampler<D>> Build<D> for Builder<D, S> {
+            type Sampler = Sampler<D, S>;
+            fn build(self) -> Result<Sampler<D, S>, Error> {
+                let target = self.target.ok_or(Error::Missing("target"))?;
+                if target.is_nan() || target <= 0.0 {
+                    return Err(Error::NotPositive("target"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler.ok_or(Error::Missing("sampler"))?,
+                    target,
+                })
+            }
+        }
+        impl<D: na::Scalar + Coordinates, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                until(self.sampler.sample_with_rng(pdf, gen), self.target)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                until(self.sampler.resume_with_rng(pdf, state, gen), self.target)
+            }
+        }
+
+        // estimate at geometrically spaced checkpoints, keeping the overhead linear
+        fn until<D: Coordinates, I: Iterator<Item = D>>(
+            mut sampler: I,
+            target: f64,
+        ) -> impl Iterator<Item = D> {
+            let (mut chains, mut checkpoint, mut done) = (Vec::<Vec<f64>>::new(), 16, false);
+            std::iter::from_fn(move || {
+                if done {
+                    return None;
+                }
+                let x = sampler.next()?;
+                let xs = x.coordinates();
+                assert!(!xs.is_empty(), "no coordinates");
+                if chains.is_empty() {
+                    chains = vec![vec![]; xs.len()];
+                }
+                (chains.iter_mut()).zip(xs).for_each(|(c, x)| c.push(x));
+                if chains[0].len() >= checkpoint {
+                    checkpoint += checkpoint / 4;
+                    done = (chains.iter())
+                        .map(|c| diag::ess(c))
+                        .fold(f64::INFINITY, f64::min)
+                        >= target;
+                }
+                Some(x)
+            })
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use sampler::Sampler;
+
+            #[test]
+            fn stopping() {
+                let sampler = univar::Metropolis::builder()
+                    .proposal(perturb::<f64>(1.0))
+                    .build()
+                    .unwrap();
+                let xs: Vec<f64> = (sampler.until_ess(200.0))
+                    .sample(|x: &f64| (-x * x / 2.0).exp())
+                    .take(1000000)
+                    .collect();
+                assert!(xs.len() < 1000000);
+                assert!(diag::ess(&xs) >= 200.0);
+            }
+        }
+    }
+
+    #[doc = "Running statistics of each coordinate, readable mid-run"]
+    pub mod monitor {
+        use super::*;
+        use std::sync::*;
+
+        #[derive(Clone, Debug, Default)]
+        struct Running {
+            n: usize,
+            mean: Vec<f64>,
+            m2: Vec<f64>,
+            min: Vec<f64>,
+            max: Vec<f64>,
+        }
+
+        #[doc = "Shared running mean, variance and range of each coordinate"]
+        #[derive(Clone, Debug, Default)]
+        pub struct Stats {
+            running: Arc<Mutex<Running>>,
+        }
+        impl Stats {
+            pub fn count(&self) -> usize {
+                self.running.lock().unwrap().n
+            }
+
+            pub fn mean(&self) -> Vec<f64> {
+                self.running.lock().unwrap().mean.clone()
+            }
+
+            #[doc = "Unbiased sample variance"]
+            pub fn variance(&self) -> Vec<f64> {
+                let running = self.running.lock().unwrap();
+                let n = running.n.max(2) - 1;
+                running.m2.iter().map(|m2| m2 / n as f64).collect()
+            }
+
+            pub fn min(&self) -> Vec<f64> {
+                self.running.lock().unwrap().min.clone()
+            }
+
+            pub fn max(&self) -> Vec<f64> {
+                self.running.lock().unwrap().max.clone()
+            }
+
+            pub fn reset(&self) {
+                *self.running.lock().unwrap() = Running::default()
+            }
+
+            fn observe(&self, xs: Vec<f64>) {
+                let mut running = self.running.lock().unwrap();
+                if running.n == 0 {
+                    let d = xs.len();
+                    *running = Running {
+                        n: 0,
+                        mean: vec![0.0; d],
+                        m2: vec![0.0; d],
+                        min: vec![f64::INFINITY; d],
+                        max: vec![f64::NEG_INFINITY; d],
+                    };
+                }
+                assert_eq!(xs.len(), running.mean.len(), "dimension mismatch");
+                running.n += 1;
+                let n = running.n as f64;
+                let Running {
+                    mean, m2, min, max, ..
+                } = &mut *running;
+                for (i, x) in xs.into_iter().enumerate() {
+                    let delta = x - mean[i];
+                    mean[i] += delta / n;
+                    m2[i] += delta * (x - mean[i]);
+                    min[i] = min[i].min(x);
+                    max[i] = max[i].max(x);
+                }
+            }
+        }
+
+        pub struct Sampler<D: na::Scalar + Coordinates, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub sampler: S,
+            stats: Stats,
+        }
+        impl<D: na::Scalar + Coordinates, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            pub fn new(sampler: S) -> Self {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler,
+                    stats: Stats::default(),
+                }
+            }
+
+            #[doc = "Handle on the statistics, updated as samples are drawn"]
+            pub fn stats(&self) -> Stats {
+                self.stats.clone()
+            }
+        }
+        impl<D: na::Scalar + Coordinates, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                monitor(self.sampler.sample_with_rng(pdf, gen), self.stats.clone())
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                monitor(
+                    self.sampler.resume_with_rng(pdf, state, gen),
+                    self.stats.clone(),
+                )
+            }
+            fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+                &self,
+                pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                let visit = |x: &D| {
+                    self.stats.observe(x.coordinates());
+                    visit(x);
+                };
+                self.sampler.for_each_sample_with_rng(pdf, n, visit, gen)
+            }
+        }
+
+        fn monitor<D: Coordinates, I: Iterator<Item = D>>(
+            sampler: I,
+            stats: Stats,
+        ) -> impl Iterator<Item = D> {
+            sampler.inspect(move |x| stats.observe(x.coordinates()))
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use sampler::Sampler;
+
+            #[test]
+            fn running() {
+                let sampler = univar::Metropolis::builder()
+                    .proposal(perturb::<f64>(1.0))
+                    .build()
+                    .unwrap()
+                    .with_stats();
+                let stats = sampler.stats();
+                let xs: Vec<f64> = (sampler.sample(|x: &f64| (-x * x / 2.0).exp()))
+                    .take(20000)
+                    .collect();
+                assert_eq!(stats.count(), 20000);
+                let mean = xs.iter().sum::<f64>() / xs.len() as f64;
+                assert!((stats.mean()[0] - mean).abs() < 1e-9);
+                let variance = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / 19999.0;
+                assert!((stats.variance()[0] - variance).abs() < 1e-9);
+                assert_eq!(
+                    stats.min()[0],
+                    xs.iter().cloned().fold(f64::INFINITY, f64::min)
+                );
+                stats.reset();
+                assert_eq!(stats.count(), 0);
+            }
+        }
+    }
+
+    #[doc = "Record the latest samples for diagnostics"]
+    pub mod trace {
+        use super::*;
+        use std::collections::VecDeque;
+        use std::sync::*;
+
+        pub struct Sampler<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub sampler: S,
+            pub capacity: usize,
+            buffer: Arc<Mutex<VecDeque<D>>>,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S, capacity: usize) -> Self {
+                (Self::builder().sampler(sampler).capacity(capacity).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                    capacity: None,
+                }
+            }
+
+            #[doc = "Latest samples drawn, oldest first"]
+            pub fn samples(&self) -> Vec<D> {
+                self.buffer.lock().unwrap().iter().cloned().collect()
+            }
+
+            pub fn clear(&self) {
+                self.buffer.lock().unwrap().clear()
+            }
+        }
+
+        pub struct Builder<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+            capacity: Option<usize>,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Builder<D, S> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+
+            pub fn capacity(self, capacity: usize) -> Self {
+                Builder {
+                    capacity: Some(capacity),
+                    ..self
+                }
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Build<D> for Builder<D, S> {
+            type Sampler = Sampler<D, S>;
+            fn build(self) -> Result<Sampler<D, S>, Error> {
+                let capacity = self.capacity.ok_or(Error::Missing("capacity"))?;
+                if capacity == 0 {
+                    return Err(Error::NotPositive("capacity"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler.ok_or(Error::Missing("sampler"))?,
+                    capacity,
+                    buffer: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+                })
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> super::Sampler<D> for Sampler<D, S> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let sampler = self.sampler.sample_with_rng(pdf, gen);
+                tee(sampler, self.buffer.clone(), self.capacity)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let sampler = self.sampler.resume_with_rng(pdf, state, gen);
+                tee(sampler, self.buffer.clone(), self.capacity)
+            }
+            fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+                &self,
+                pdf: F,
+                n: usize,
+                mut visit: V,
+                gen: R,
+            ) {
+                // released before `visit`, which may well read the trace
+                let visit = |x: &D| {
+                    push(&mut self.buffer.lock().unwrap(), x.clone(), self.capacity);
+                    visit(x);
+                };
+                self.sampler.for_each_sample_with_rng(pdf, n, visit, gen)
+            }
+        }
+
+        fn tee<D: Clone, I: Iterator<Item = D>>(
+            sampler: I,
+            buffer: Arc<Mutex<VecDeque<D>>>,
+            capacity: usize,
+        ) -> impl Iterator<Item = D> {
+            sampler.inspect(move |x| push(&mut buffer.lock().unwrap(), x.clone(), capacity))
+        }
+
+        fn push<D>(buffer: &mut VecDeque<D>, x: D, capacity: usize) {
+            if buffer.len() == capacity {
+                buffer.pop_front();
+            }
+            buffer.push_back(x);
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use sampler::Sampler;
+
+            #[test]
+            fn ring() {
+                let trace = univar::Metropolis::builder()
+                    .proposal(perturb::<f64>(1.0))
+                    .build()
+                    .unwrap()
+                    .trace(10);
+                let xs: Vec<f64> = trace.sample(|x: &f64| (-x * x).exp()).take(25).collect();
+                assert_eq!(trace.samples(), xs[15..].to_vec());
+                trace.clear();
+                let mut lens = vec![];
+                trace.for_each_sample(
+                    |x: &f64| (-x * x).exp(),
+                    3,
+                    |_| lens.push(trace.samples().len()),
+                );
+                assert_eq!(lens, vec![1, 2, 3]);
+            }
+        }
+    }
+
+    #[doc = "Replay the same chain from a fixed seed, in place of the given generator"]
+    pub mod seeded {
+        use super::*;
+        use rand::SeedableRng;
+        use std::sync::atomic::*;
+
+        #[doc = "Sampler whose `k`-th chain reads stream `k` of `seed`, so a fresh one replays them all"]
+        pub struct Sampler<D, S, G = rand::rngs::StdRng>
+        where
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            G: rand::Rng + SeedableRng,
+        {
+            pd: std::marker::PhantomData<(D, G)>,
+            pub sampler: S,
+            pub seed: u64,
+            calls: AtomicU64,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            pub fn new(sampler: S, seed: u64) -> Self {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler,
+                    seed,
+                    calls: AtomicU64::new(0),
+                }
+            }
+        }
+
+        #[doc = "Builder seeding whatever the inner builder builds"]
+        pub struct Builder<D: na::Scalar, B: Build<D>> {
+            pd: std::marker::PhantomData<D>,
+            pub builder: B,
+            pub seed: u64,
+        }
+        impl<D: na::Scalar, B: Build<D>> Builder<D, B> {
+            #[allow(unused)]
+            pub fn new(builder: B, seed: u64) -> Self {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    builder,
+                    seed,
+                }
+            }
+        }
+        impl<D: na::Scalar, B: Build<D>> Build<D> for Builder<D, B>
+        where
+            B::Sampler: super::Sampler<D>,
+        {
+            type Sampler = Sampler<D, B::Sampler>;
+            fn build(self) -> Result<Self::Sampler, Error> {
+                Ok(Sampler::new(self.builder.build()?, self.seed))
+            }
+        }
+        impl<D, S, G> Sampler<D, S, G>
+        where
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            G: rand::Rng + SeedableRng,
+        {
+            #[doc = "Seed another generator, e.g. `SmallRng` for speed or `ChaCha` for quality"]
+            pub fn rng<H: rand::Rng + SeedableRng>(self) -> Sampler<D, S, H> {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler,
+                    seed: self.seed,
+                    calls: self.calls,
+                }
+            }
+
+            // a fresh stream per chain, so nesting under gibbs or tempering never replays draws
+            fn stream(&self) -> G {
+                rng::stream(self.seed, self.calls.fetch_add(1, Ordering::Relaxed))
+            }
+        }
+        impl<D, S, G> super::Sampler<D> for Sampler<D, S, G>
+        where
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            G: rand::Rng + SeedableRng,
+        {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = S::Iter<F, G>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                _: R,
+            ) -> Self::Iter<F, R> {
+                let gen = self.stream();
+                self.sampler.sample_with_rng(pdf, gen)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                state: D,
+                _: R,
+            ) -> Self::Iter<F, R> {
+                let gen = self.stream();
+                self.sampler.resume_with_rng(pdf, state, gen)
+            }
+            fn for_each_sample_with_rng<F: FnMut(&D) -> f64, V: FnMut(&D), R: rand::Rng>(
+                &self,
+                pdf: F,
+                n: usize,
+                visit: V,
+                _: R,
+            ) {
+                let gen = self.stream();
+                self.sampler.for_each_sample_with_rng(pdf, n, visit, gen)
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use modular::*;
+            use sampler::Sampler;
+
+            #[test]
+            fn replay() {
+                let pdf = |z: &Z<8>| (z.0 + 1) as f64;
+                let draw = |seed: u64| -> Vec<Z<8>> {
+                    let sampler = univar::Cftp::new().seeded(seed);
+                    sampler.sample(pdf).take(50).collect()
+                };
+                assert_eq!(draw(42), draw(42));
+                assert_ne!(draw(42), draw(43));
+
+                let small = || univar::Cftp::new().seeded(42).rng::<rand::rngs::SmallRng>();
+                let xs: Vec<Z<8>> = small().sample(pdf).take(50).collect();
+                assert_eq!(xs, small().sample(pdf).take(50).collect::<Vec<Z<8>>>());
+            }
+
+            #[test]
+            fn nested() {
+                let draw = || -> Vec<nd::Array1<Z<16>>> {
+                    (univar::Icdf::new().seeded(7).gibbs(nd::Dim([2])).seeded(3))
+                        .sample(|_: &nd::Array1<Z<16>>| 1.0)
+                        .take(200)
+                        .collect()
+                };
+                let xs = draw();
+                assert_eq!(xs, draw());
+                // reseeding every step would pin each coordinate to one value
+                let values: std::collections::HashSet<usize> = xs.iter().map(|x| x[0].0).collect();
+                assert!(values.len() > 8);
+            }
+        }
+    }
+
+    #[doc = "Independent chains on their own threads, each drawing from its own stream"]
+    pub mod parallel {
+        use super::*;
+        use rand::SeedableRng;
+        use std::sync::Arc;
+
+        pub struct Sampler<D, S, G = rand::rngs::StdRng>
+        where
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            G: rand::Rng + SeedableRng,
+        {
+            pd: std::marker::PhantomData<(D, G)>,
+            pub sampler: Arc<S>,
+            pub chains: usize,
+            pub seed: u64,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S, chains: usize, seed: u64) -> Self {
+                let builder = Self::builder().sampler(sampler).chains(chains);
+                builder
+                    .seed(seed)
+                    .build()
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                    chains: None,
+                    seed: 0,
+                }
+            }
+        }
+
+        pub struct Builder<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+            chains: Option<usize>,
+            seed: u64,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Builder<D, S> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+
+            pub fn chains(self, chains: usize) -> Self {
+                Builder {
+                    chains: Some(chains),
+                    ..self
+                }
+            }
+
+            #[doc = "Seed whose derived streams drive the chains"]
+            pub fn seed(self, seed: u64) -> Self {
+                Builder { seed, ..self }
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Build<D> for Builder<D, S> {
+            type Sampler = Sampler<D, S>;
+            fn build(self) -> Result<Sampler<D, S>, Error> {
+                let chains = self.chains.ok_or(Error::Missing("chains"))?;
+                if chains == 0 {
+                    return Err(Error::NotPositive("chains"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: Arc::new(self.sampler.ok_or(Error::Missing("sampler"))?),
+                    chains,
+                    seed: self.seed,
+                })
+            }
+        }
+        impl<D, S, G> Sampler<D, S, G>
+        where
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            G: rand::Rng + SeedableRng,
+        {
+            pub fn rng<H: rand::Rng + SeedableRng>(self) -> Sampler<D, S, H> {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler,
+                    chains: self.chains,
+                    seed: self.seed,
+                }
+            }
+
+            #[doc = "First `n` draws of every chain"]
+            pub fn run<F>(&self, pdf: F, n: usize) -> Vec<Vec<D>>
+            where
+                D: Send + 'static,
+                S: Send + Sync + 'static,
+                F: Fn(&D) -> f64 + Send + Sync + 'static,
+                G: Send + 'static,
+            {
+                let pdf = Arc::new(pdf);
+                let handles: Vec<_> = (0..self.chains)
+                    .map(|k| {
+                        let (sampler, pdf) = (self.sampler.clone(), pdf.clone());
+                        let gen: G = rng::stream(self.seed, k as u64);
+                        std::thread::spawn(move || {
+                            (sampler.sample_with_rng(|x: &D| pdf(x), gen))
+                                .take(n)
+                                .collect::<Vec<D>>()
+                        })
+                    })
+                    .collect();
+                let mut chains = vec![];
+                for handle in handles {
+                    chains.push(handle.join().expect("chain panicked"));
+                }
+                chains
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+            use modular::*;
+            use sampler::Sampler;
+
+            #[test]
+            fn streams() {
+                let pdf = |z: &Z<8>| (z.0 + 1) as f64;
+                let sampler = univar::Icdf::new().parallel(4, 42);
+                let chains = sampler.run(pdf, 50);
+                assert_eq!(chains.len(), 4);
+                assert_eq!(chains, sampler.run(pdf, 50));
+                assert!((1..4).all(|k| chains[k] != chains[0]));
+
+                let none = adapter::Parallel::builder()
+                    .sampler(univar::Icdf::<Z<8>>::new())
+                    .chains(0)
+                    .build();
+                assert_eq!(none.err(), Some(Error::NotPositive("chains")));
+            }
+        }
+    }
+
+    #[doc = "Tempered transitions through a ladder of inverse temperatures"]
+    pub mod tempered {
+        use super::*;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use std::sync::*;
+
+        pub struct Sampler<D: na::Scalar, S: super::Sampler<D>, L: scale::Scale = scale::Linear> {
+            pd: std::marker::PhantomData<(D, L)>,
+            pub sampler: Arc<S>,
+            pub betas: Vec<f64>,
+            pub period: usize,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Sampler<D, S> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(sampler: S, betas: Vec<f64>) -> Self {
+                (Self::builder().sampler(sampler).betas(betas).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D, S> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    sampler: None,
+                    betas: None,
+                    period: 10,
+                }
+            }
+        }
+
+        pub struct Builder<D: na::Scalar, S: super::Sampler<D>> {
+            pd: std::marker::PhantomData<D>,
+            sampler: Option<S>,
+            betas: Option<Vec<f64>>,
+            period: usize,
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Builder<D, S> {
+            pub fn sampler(self, sampler: S) -> Self {
+                Builder {
+                    sampler: Some(sampler),
+                    ..self
+                }
+            }
+
+            #[doc = "Inverse temperatures of the ladder, decreasing in (0, 1)"]
+            pub fn betas(self, betas: Vec<f64>) -> Self {
+                Builder {
+                    betas: Some(betas),
+                    ..self
+                }
+            }
+
+            #[doc = "Steps at the base temperature between tempered transitions"]
+            pub fn period(self, period: usize) -> Self {
+                Builder { period, ..self }
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>> Build<D> for Builder<D, S> {
+            type Sampler = Sampler<D, S>;
+            fn build(self) -> Result<Sampler<D, S>, Error> {
+                let betas = self.betas.ok_or(Error::Missing("betas"))?;
+                let decreasing = (std::iter::once(&1.0).chain(betas.iter()))
+                    .zip(betas.iter())
+                    .all(|(a, b)| 0.0 < *b && b < a);
+                if betas.is_empty() || !decreasing {
+                    return Err(Error::OutOfRange("betas"));
+                }
+                if self.period == 0 {
+                    return Err(Error::NotPositive("period"));
+                }
+                Ok(Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: Arc::new(self.sampler.ok_or(Error::Missing("sampler"))?),
+                    betas,
+                    period: self.period,
+                })
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>, L: scale::Scale> Sampler<D, S, L> {
+            #[doc = "Interpret pdf values on another scale, that of the base sampler"]
+            pub fn scale<M: scale::Scale>(self) -> Sampler<D, S, M> {
+                Sampler {
+                    pd: std::marker::PhantomData,
+                    sampler: self.sampler,
+                    betas: self.betas,
+                    period: self.period,
+                }
+            }
+        }
+        impl<D: na::Scalar, S: super::Sampler<D>, L: scale::Scale> super::Sampler<D> for Sampler<D, S, L> {
+            type Iter<F: FnMut(&D) -> f64, R: rand::Rng> = impl Iterator<Item = D>;
+            fn sample_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                mut pdf: F,
+                mut gen: R,
+            ) -> Self::Iter<F, R> {
+                let state = (self.sampler.sample_with_rng(&mut pdf, &mut gen))
+                    .next()
+                    .unwrap();
+                self.resume_with_rng(pdf, state, gen)
+            }
+            fn resume_with_rng<F: FnMut(&D) -> f64, R: rand::Rng>(
+                &self,
+                pdf: F,
+                mut state: D,
+                gen: R,
+            ) -> Self::Iter<F, R> {
+                let (sampler, betas, period) =
+                    (self.sampler.clone(), self.betas.clone(), self.period);
+                let pdf = Rc::new(RefCell::new(pdf));
+                let (mut aux, mut count) = (gen, 0);
+                std::iter::from_fn(move || {
+                    count += 1;
+                    if count % period == 0 {
+                        let old = state.clone();
+                        state = transition::<L, _, _, _, _>(&*sampler, &pdf, &betas, old, &mut aux);
+                    }
+                    state = step::<L, _, _, _, _>(&*sampler, &pdf, 1.0, state.clone(), &mut aux);
+                    Some(state.clone())
+                })
+            }
+        }
+
+        // one move of the base sampler on `pdf^beta`
+        fn step<L, D, S, F, R>(
+            sampler: &S,
+            pdf: &Rc<RefCell<F>>,
+            beta: f64,
+            state: D,
+            aux: &mut R,
+        ) -> D
+        where
+            L: scale::Scale,
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            F: FnMut(&D) -> f64,
+            R: rand::Rng,
+        {
+            let pdf = pdf.clone();
+            let tempered = move |x: &D| L::pow((pdf.borrow_mut())(x), beta);
+            (sampler.resume_with_rng(tempered, state, aux))
+                .next()
+                .unwrap()
+        }
+
+        // heat up the ladder then cool back down, accepting the end point by Neal's ratio
+        fn transition<L, D, S, F, R>(
+            sampler: &S,
+            pdf: &Rc<RefCell<F>>,
+            betas: &[f64],
+            state: D,
+            aux: &mut R,
+        ) -> D
+        where
+            L: scale::Scale,
+            D: na::Scalar,
+            S: super::Sampler<D>,
+            F: FnMut(&D) -> f64,
+            R: rand::Rng,
+        {
+            let log = |x: &D| L::ln((pdf.borrow_mut())(x));
+            let (mut x, mut log_ratio, mut prev) = (state.clone(), 0.0, 1.0);
+            for &beta in betas {
+                log_ratio += (beta - prev) * log(&x);
+                x = step::<L, _, _, _, _>(sampler, pdf, beta, x, aux);
+                prev = beta;
+            }
+            for (i, &beta) in betas.iter().enumerate().rev() {
+                x = step::<L, _, _, _, _>(sampler, pdf, beta, x, aux);
+                let next = if i == 0 { 1.0 } else { betas[i - 1] };
+                log_ratio -= (beta - next) * log(&x);
+            }
+            if aux.gen_range(0.0f64..1.0).ln() < log_ratio {
+                x
+            } else {
+                state
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            use rand::{Rng, SeedableRng};
+            use sampler::Sampler as _;
+
+            #[test]
+            fn bimodal() {
+                let pdf =
+                    |x: &f64| (-(x - 5.0).powi(2) * 2.0).exp() + (-(x + 5.0).powi(2) * 2.0).exp();
+                let proposal = |x: &f64, gen: &mut dyn rand::RngCore| x + gen.gen_range(-2.0..2.0);
+                let betas = vec![0.3, 0.1, 0.03, 0.01];
+                let xs: Vec<f64> = univar::Metropolis::builder()
+                    .proposal(proposal)
+                    .build()
+                    .unwrap()
+                    .pick(20)
+                    .temper(betas)
+                    .sample_with_rng(pdf, rand::rngs::SmallRng::seed_from_u64(0))
+                    .take(2000)
+                    .collect();
+                let right = xs.iter().filter(|x| **x > 0.0).count() as f64 / xs.len() as f64;
+                assert!((0.2..0.8).contains(&right));
+
+                let rising = adapter::Tempered::builder()
+                    .sampler(univar::Icdf::<modular::Z<8>>::new())
+                    .betas(vec![0.1, 0.3])
+                    .build();
+                assert_eq!(rising.err(), Some(Error::OutOfRange("betas")));
+            }
+        }
+    }
+}
+
+#[doc = "Quasi-Monte Carlo sequences"]
+pub mod qmc {
+    use super::*;
+
+    pub use halton::Sequence as Halton;
+    pub use sobol::Sequence as Sobol;
+
+    #[doc = "Sobol low-discrepancy sequence"]
+    pub mod sobol {
+        use super::*;
+
+        // primitive polynomials (s, a) and initial direction numbers m by Joe & Kuo
+        const DIRECTIONS: [(u32, u32, &[u32]); 20] = [
+            (1, 0, &[1]),
+            (2, 1, &[1, 3]),
+            (3, 1, &[1, 3, 1]),
+            (3, 2, &[1, 1, 1]),
+            (4, 1, &[1, 1, 3, 3]),
+            (4, 4, &[1, 3, 5, 13]),
+            (5, 2, &[1, 1, 5, 5, 17]),
+            (5, 4, &[1, 1, 5, 5, 5]),
+            (5, 7, &[1, 1, 7, 11, 19]),
+            (5, 11, &[1, 1, 5, 1, 1]),
+            (5, 13, &[1, 1, 1, 3, 11]),
+            (5, 14, &[1, 3, 5, 5, 31]),
+            (6, 1, &[1, 3, 3, 9, 7, 49]),
+            (6, 13, &[1, 1, 1, 15, 21, 21]),
+            (6, 16, &[1, 3, 1, 13, 27, 49]),
+            (6, 19, &[1, 1, 1, 15, 7, 5]),
+            (6, 22, &[1, 3, 1, 15, 13, 25]),
+            (6, 25, &[1, 1, 5, 5, 19, 61]),
+            (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+            (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+        ];
+
+        const BITS: usize = 32;
+
+        pub struct Sequence<D: Uniform> {
+            pd: std::marker::PhantomData<D>,
+            pub dim: usize,
+        }
+        impl<D: Uniform> Sequence<D> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(dim: usize) -> Self {
+                Self::builder()
+                    .dim(dim)
+                    .build()
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    dim: None,
+                }
+            }
+
+            fn directions(&self) -> Vec<[u32; BITS]> {
+                let mut vs = vec![[0; BITS]; self.dim];
+                (0..BITS).for_each(|i| vs[0][i] = 1 << (BITS - 1 - i));
+                for (v, &(s, a, m)) in vs.iter_mut().skip(1).zip(DIRECTIONS.iter()) {
+                    let s = s as usize;
+                    (0..s).for_each(|i| v[i] = m[i] << (BITS - 1 - i));
+                    for i in s..BITS {
+                        v[i] = v[i - s] ^ (v[i - s] >> s);
+                        for k in 1..s {
+                            if (a >> (s - 1 - k)) & 1 == 1 {
+                                v[i] ^= v[i - k];
+                            }
+                        }
+                    }
+                }
+                vs
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = nd::Array1<D>> {
+                let vs = self.directions();
+                let mut xs = vec![0u32; self.dim];
+                (0u64..1 << BITS).map(move |n| {
+                    let point = xs
+                        .iter()
+                        .map(|&x| D::uniform(x as f64 / (1u64 << BITS) as f64));
+                    let point = nd::Array1::from_iter(point);
+                    let c = n.trailing_ones() as usize;
+                    if c < BITS {
+                        xs.iter_mut().zip(vs.iter()).for_each(|(x, v)| *x ^= v[c]);
+                    }
+                    point
+                })
+            }
+        }
+
+        pub struct Builder<D: Uniform> {
+            pd: std::marker::PhantomData<D>,
+            dim: Option<usize>,
+        }
+        impl<D: Uniform> Builder<D> {
+            pub fn dim(self, dim: usize) -> Self {
+                Builder {
+                    dim: Some(dim),
+                    ..self
+                }
+            }
+        }
+        impl<D: Uniform> Build<nd::Array1<D>> for Builder<D> {
+            type Sampler = Sequence<D>;
+            fn build(self) -> Result<Sequence<D>, Error> {
+                let dim = self.dim.ok_or(Error::Missing("dim"))?;
+                if dim == 0 {
+                    return Err(Error::NotPositive("dim"));
+                }
+                if dim > DIRECTIONS.len() + 1 {
+                    return Err(Error::OutOfRange("dim"));
+                }
+                Ok(Sequence {
+                    pd: std::marker::PhantomData,
+                    dim,
+                })
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn unit() {
+                let sobol = qmc::Sobol::<f64>::builder().dim(2).build().unwrap();
+                let xs: Vec<_> = sobol.iter().take(4).collect();
+                let ys = [[0.0, 0.0], [0.5, 0.5], [0.75, 0.25], [0.25, 0.75]];
+                xs.iter()
+                    .zip(ys.iter())
+                    .for_each(|(x, y)| assert_eq!(x.to_vec(), y.to_vec()));
+                let wide = qmc::Sobol::<f64>::builder().dim(22).build();
+                assert_eq!(wide.err(), Some(Error::OutOfRange("dim")));
+            }
+        }
+    }
+
+    #[doc = "Halton low-discrepancy sequence"]
+    pub mod halton {
+        use super::*;
+
+        const PRIMES: [u64; 20] = [
+            2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71,
+        ];
+
+        pub struct Sequence<D: Uniform> {
+            pd: std::marker::PhantomData<D>,
+            pub dim: usize,
+            pub leap: usize,
+            pub scramble: bool,
+        }
+        impl<D: Uniform> Sequence<D> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(dim: usize) -> Self {
+                Self::builder()
+                    .dim(dim)
+                    .build()
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<D> {
+                Builder {
+                    pd: std::marker::PhantomData,
+                    dim: None,
+                    leap: 1,
+                    scramble: false,
+                }
+            }
+
+            fn permutations<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Vec<Vec<u64>> {
+                use rand::seq::SliceRandom;
+                PRIMES[..self.dim]
+                    .iter()
+                    .map(|&base| {
+                        let mut perm: Vec<u64> = (0..base).collect();
+                        if self.scramble {
+                            perm[1..].shuffle(gen); // keep zero digit fixed
+                        }
+                        perm
+                    })
+                    .collect()
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = nd::Array1<D>> {
+                self.iter_with_rng(&mut rand::thread_rng())
+            }
+
+            #[doc = "Points scrambled by digit permutations drawn from `gen`"]
+            pub fn iter_with_rng<R: rand::Rng + ?Sized>(
+                &self,
+                gen: &mut R,
+            ) -> impl Iterator<Item = nd::Array1<D>> {
+                let perms = self.permutations(gen);
+                let leap = self.leap as u64;
+                (0u64..).map(move |n| {
+                    let point = PRIMES.iter().zip(perms.iter()).map(|(&base, perm)| {
+                        let (mut n, mut x, mut f) = (n * leap, 0.0, 1.0 / base as f64);
+                        while n > 0 {
+                            x += perm[(n % base) as usize] as f64 * f;
+                            n /= base;
+                            f /= base as f64;
+                        }
+                        D::uniform(x)
+                    });
+                    nd::Array1::from_iter(point)
+                })
+            }
+        }
+
+        pub struct Builder<D: Uniform> {
+            pd: std::marker::PhantomData<D>,
+            dim: Option<usize>,
+            leap: usize,
+            scramble: bool,
+        }
+        impl<D: Uniform> Builder<D> {
+            pub fn dim(self, dim: usize) -> Self {
+                Builder {
+                    dim: Some(dim),
+                    ..self
+                }
+            }
+
+            pub fn leap(self, leap: usize) -> Self {
+                Builder { leap, ..self }
+            }
+
+            pub fn scramble(self) -> Self {
+                Builder {
+                    scramble: true,
+                    ..self
+                }
+            }
+        }
+        impl<D: Uniform> Build<nd::Array1<D>> for Builder<D> {
+            type Sampler = Sequence<D>;
+            fn build(self) -> Result<Sequence<D>, Error> {
+                let dim = self.dim.ok_or(Error::Missing("dim"))?;
+                if dim == 0 {
+                    return Err(Error::NotPositive("dim"));
+                }
+                if dim > PRIMES.len() {
+                    return Err(Error::OutOfRange("dim"));
+                }
+                if self.leap == 0 {
+                    return Err(Error::NotPositive("leap"));
+                }
+                Ok(Sequence {
+                    pd: std::marker::PhantomData,
+                    dim,
+                    leap: self.leap,
+                    scramble: self.scramble,
+                })
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn unit() {
+                let halton = qmc::Halton::<f64>::builder().dim(2).build().unwrap();
+                let xs: Vec<_> = halton.iter().take(4).collect();
+                let ys = [
+                    [0.0, 0.0],
+                    [0.5, 1.0 / 3.0],
+                    [0.25, 2.0 / 3.0],
+                    [0.75, 1.0 / 9.0],
+                ];
+                xs.iter().zip(ys.iter()).for_each(|(x, y)| {
+                    x.iter()
+                        .zip(y.iter())
+                        .for_each(|(x, y)| assert!((x - y).abs() < 1e-12))
+                });
+                let still = qmc::Halton::<f64>::builder().dim(2).leap(0).build();
+                assert_eq!(still.err(), Some(Error::NotPositive("leap")));
+            }
+        }
+    }
+}
+
+#[doc = "Cluster samplers for Potts models `exp(J Σ δ(s_i, s_j))` on periodic lattices"]
+pub mod lattice {
+    use super::*;
+    use modular::Z;
+
+    pub use swendsen_wang::Sampler as SwendsenWang;
+    pub use wolff::Sampler as Wolff;
+
+    // ferromagnetic coupling on a non-empty lattice
+    fn validate(
+        coupling: Option<f64>,
+        shape: Option<(usize, usize)>,
+    ) -> Result<(f64, (usize, usize)), Error> {
+        let coupling = coupling.ok_or(Error::Missing("coupling"))?;
+        if coupling.is_nan() || coupling < 0.0 {
+            return Err(Error::OutOfRange("coupling"));
+        }
+        let shape = shape.ok_or(Error::Missing("shape"))?;
+        if shape.0 == 0 || shape.1 == 0 {
+            return Err(Error::NotPositive("shape"));
+        }
+        Ok((coupling, shape))
+    }
+
+    // nearest-neighbour pairs, wrapping around the torus
+    fn bonds((n, m): (usize, usize)) -> impl Iterator<Item = ((usize, usize), (usize, usize))> {
+        (0..n).flat_map(move |i| {
+            (0..m).flat_map(move |j| [((i, j), ((i + 1) % n, j)), ((i, j), (i, (j + 1) % m))])
+        })
+    }
+
+    #[doc = "Swendsen-Wang multi-cluster updates"]
+    pub mod swendsen_wang {
+        use super::*;
+
+        pub struct Sampler<const N: usize> {
+            pub coupling: f64,
+            pub shape: (usize, usize),
+        }
+        impl<const N: usize> Sampler<N> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(coupling: f64, shape: (usize, usize)) -> Self {
+                (Self::builder().coupling(coupling).shape(shape).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<N> {
+                Builder {
+                    coupling: None,
+                    shape: None,
+                }
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = nd::Array2<Z<N>>> {
+                self.iter_with_rng(rand::thread_rng())
+            }
+
+            pub fn iter_with_rng<R: rand::Rng>(
+                &self,
+                mut gen: R,
+            ) -> impl Iterator<Item = nd::Array2<Z<N>>> {
+                let (n, m) = self.shape;
+                let p = 1.0 - (-self.coupling).exp();
+                let mut spins = nd::Array2::from_shape_fn(self.shape, |_| Z(gen.gen_range(0..N)));
+                std::iter::from_fn(move || {
+                    let mut parent: Vec<usize> = (0..n * m).collect();
+                    for (a, b) in bonds((n, m)) {
+                        if spins[a] == spins[b] && gen.gen_range(0.0..1.0) < p {
+                            let ra = find(&mut parent, a.0 * m + a.1);
+                            let rb = find(&mut parent, b.0 * m + b.1);
+                            parent[ra] = rb;
+                        }
+                    }
+                    let colors: Vec<usize> = (0..n * m).map(|_| gen.gen_range(0..N)).collect();
+                    for i in 0..n {
+                        for j in 0..m {
+                            spins[[i, j]] = Z(colors[find(&mut parent, i * m + j)]);
+                        }
+                    }
+                    Some(spins.clone())
+                })
+            }
+        }
+
+        pub struct Builder<const N: usize> {
+            coupling: Option<f64>,
+            shape: Option<(usize, usize)>,
+        }
+        impl<const N: usize> Builder<N> {
+            pub fn coupling(self, coupling: f64) -> Self {
+                Builder {
+                    coupling: Some(coupling),
+                    ..self
+                }
+            }
+
+            pub fn shape(self, shape: (usize, usize)) -> Self {
+                Builder {
+                    shape: Some(shape),
+                    ..self
+                }
+            }
+        }
+        impl<const N: usize> Build<nd::Array2<Z<N>>> for Builder<N> {
+            type Sampler = Sampler<N>;
+            fn build(self) -> Result<Sampler<N>, Error> {
+                let (coupling, shape) = validate(self.coupling, self.shape)?;
+                Ok(Sampler { coupling, shape })
+            }
+        }
+
+        // union-find root with path halving
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn ordered() {
+                use rand::SeedableRng;
+                let sampler = SwendsenWang::<2>::builder().coupling(3.0).shape((8, 8));
+                let gen = rand::rngs::SmallRng::seed_from_u64(0);
+                let spins = sampler.build().unwrap().iter_with_rng(gen).nth(20).unwrap();
+                let up = spins.iter().filter(|s| s.0 == 1).count();
+                assert!(up.max(64 - up) > 56);
+            }
+        }
+    }
+
+    #[doc = "Wolff single-cluster updates"]
+    pub mod wolff {
+        use super::*;
+
+        pub struct Sampler<const N: usize> {
+            pub coupling: f64,
+            pub shape: (usize, usize),
+        }
+        impl<const N: usize> Sampler<N> {
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(coupling: f64, shape: (usize, usize)) -> Self {
+                (Self::builder().coupling(coupling).shape(shape).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<N> {
+                Builder {
+                    coupling: None,
+                    shape: None,
+                }
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = nd::Array2<Z<N>>> {
+                self.iter_with_rng(rand::thread_rng())
+            }
+
+            pub fn iter_with_rng<R: rand::Rng>(
+                &self,
+                mut gen: R,
+            ) -> impl Iterator<Item = nd::Array2<Z<N>>> {
+                let (n, m) = self.shape;
+                let p = 1.0 - (-self.coupling).exp();
+                let mut spins = nd::Array2::from_shape_fn(self.shape, |_| Z(gen.gen_range(0..N)));
+                std::iter::from_fn(move || {
+                    let seed = (gen.gen_range(0..n), gen.gen_range(0..m));
+                    let old = spins[seed].clone();
+                    let new = Z((old.0 + gen.gen_range(1..N)) % N);
+                    spins[seed] = new.clone();
+                    let mut stack = vec![seed];
+                    while let Some((i, j)) = stack.pop() {
+                        let around = [
+                            ((i + 1) % n, j),
+                            ((i + n - 1) % n, j),
+                            (i, (j + 1) % m),
+                            (i, (j + m - 1) % m),
+                        ];
+                        for site in around {
+                            if spins[site] == old && gen.gen_range(0.0..1.0) < p {
+                                spins[site] = new.clone();
+                                stack.push(site);
+                            }
+                        }
+                    }
+                    Some(spins.clone())
+                })
+            }
+        }
+
+        pub struct Builder<const N: usize> {
+            coupling: Option<f64>,
+            shape: Option<(usize, usize)>,
+        }
+        impl<const N: usize> Builder<N> {
+            pub fn coupling(self, coupling: f64) -> Self {
+                Builder {
+                    coupling: Some(coupling),
+                    ..self
+                }
+            }
+
+            pub fn shape(self, shape: (usize, usize)) -> Self {
+                Builder {
+                    shape: Some(shape),
+                    ..self
+                }
+            }
+        }
+        impl<const N: usize> Build<nd::Array2<Z<N>>> for Builder<N> {
+            type Sampler = Sampler<N>;
+            fn build(self) -> Result<Sampler<N>, Error> {
+                if N < 2 {
+                    return Err(Error::OutOfRange("spins"));
+                }
+                let (coupling, shape) = validate(self.coupling, self.shape)?;
+                Ok(Sampler { coupling, shape })
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn ordered() {
+                use rand::SeedableRng;
+                let sampler = Wolff::<2>::builder().coupling(3.0).shape((8, 8));
+                let gen = rand::rngs::SmallRng::seed_from_u64(0);
+                let spins = sampler.build().unwrap().iter_with_rng(gen).nth(50).unwrap();
+                let up = spins.iter().filter(|s| s.0 == 1).count();
+                assert!(up.max(64 - up) > 56);
+
+                let antiferro = Wolff::<2>::builder().coupling(-1.0).shape((8, 8)).build();
+                assert_eq!(antiferro.err(), Some(Error::OutOfRange("coupling")));
+                let empty = Wolff::<2>::builder().coupling(1.0).shape((0, 8)).build();
+                assert_eq!(empty.err(), Some(Error::NotPositive("shape")));
+            }
+        }
+    }
+}
+
+#[doc = "Piecewise-deterministic Markov processes"]
+pub mod pdmp {
+    use super::*;
+    use std::sync::*;
+
+    pub use bouncy::Sampler as Bouncy;
+    pub use zigzag::Sampler as ZigZag;
+
+    #[doc = "Switching point of a piecewise-linear trajectory"]
+    #[derive(Clone, Debug)]
+    pub struct Event {
+        pub time: f64,
+        pub position: nd::Array1<f64>,
+        pub velocity: nd::Array1<f64>,
+    }
+    impl Event {
+        #[doc = "Position along the segment starting at this event"]
+        pub fn at(&self, time: f64) -> nd::Array1<f64> {
+            &self.position + &(&self.velocity * (time - self.time))
+        }
+    }
+
+    #[doc = "Exact time-average of the position along a finite skeleton"]
+    pub fn mean<I: IntoIterator<Item = Event>>(events: I) -> nd::Array1<f64> {
+        let mut events = events.into_iter();
+        let first = events.next().expect("no events");
+        let (start, mut sum) = (first.time, first.position.mapv(|_| 0.0));
+        let end = events.fold(first, |prev, e| {
+            sum = &sum + &((&prev.position + &e.position) * ((e.time - prev.time) / 2.0));
+            e
+        });
+        assert!(end.time > start, "trajectory is empty");
+        sum / (end.time - start)
+    }
+
+    #[doc = "Positions every `step` time units along a skeleton of events"]
+    pub fn discretize<I: Iterator<Item = Event>>(
+        mut events: I,
+        step: f64,
+    ) -> impl Iterator<Item = nd::Array1<f64>> {
+        assert!(step > 0.0, "step isn't positive");
+        let mut current = events.next();
+        let mut next = events.next();
+        let mut time = current.as_ref().map_or(0.0, |e| e.time);
+        std::iter::from_fn(move || {
+            while let (Some(_), Some(e)) = (&current, &next) {
+                if e.time > time {
+                    break;
+                }
+                current = next.take();
+                next = events.next();
+            }
+            let e = current.as_ref()?;
+            let x = e.at(time);
+            time += step;
+            Some(x)
+        })
+    }
+
+    // waiting time of a homogeneous poisson process
+    fn arrival<R: rand::Rng + ?Sized>(rate: f64, gen: &mut R) -> f64 {
+        let u: f64 = gen.gen_range(0.0..1.0);
+        -(1.0 - u).ln() / rate
+    }
+
+    #[doc = "Bouncy particle sampler with gaussian refreshments"]
+    pub mod bouncy {
+        use super::*;
+
+        pub struct Sampler<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> {
+            pub gradient: Arc<G>,
+            pub bound: f64,
+            pub refresh: f64,
+        }
+        impl<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> Sampler<G> {
+            #[doc = "Target `exp(-U)` given `∇U` and a bound on its norm"]
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(gradient: G, bound: f64) -> Self {
+                (Self::builder().gradient(gradient).bound(bound).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<G> {
+                Builder {
+                    gradient: None,
+                    bound: None,
+                    refresh: 1.0,
+                }
+            }
+
+            pub fn iter(&self, position: nd::Array1<f64>) -> impl Iterator<Item = Event> {
+                self.iter_with_rng(position, rand::thread_rng())
+            }
+
+            pub fn iter_with_rng<R: rand::Rng>(
+                &self,
+                position: nd::Array1<f64>,
+                mut gen: R,
+            ) -> impl Iterator<Item = Event> {
+                let (gradient, bound, refresh) = (self.gradient.clone(), self.bound, self.refresh);
+                let velocity = position.mapv(|_| dist::normal(&mut gen));
+                let mut event = Event {
+                    time: 0.0,
+                    position,
+                    velocity,
+                };
+                std::iter::once(event.clone()).chain(std::iter::from_fn(move || loop {
+                    let speed = event.velocity.dot(&event.velocity).sqrt();
+                    let total = bound * speed + refresh;
+                    let tau = arrival(total, &mut gen);
+                    event.time += tau;
+                    event.position = &event.position + &(&event.velocity * tau);
+
+                    let aux = gen.gen_range(0.0..total);
+                    if aux < refresh {
+                        event.velocity = event.velocity.mapv(|_| dist::normal(&mut gen));
+                        return Some(event.clone());
+                    }
+                    let g = gradient(&event.position);
+                    let rate = event.velocity.dot(&g).max(0.0);
+                    if aux - refresh < rate {
+                        let reflect = 2.0 * event.velocity.dot(&g) / g.dot(&g);
+                        event.velocity = &event.velocity - &(g * reflect);
+                        return Some(event.clone());
+                    }
+                }))
+            }
+        }
+
+        pub struct Builder<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> {
+            gradient: Option<G>,
+            bound: Option<f64>,
+            refresh: f64,
+        }
+        impl<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> Builder<G> {
+            #[doc = "`∇U` of the target `exp(-U)`"]
+            pub fn gradient(self, gradient: G) -> Self {
+                Builder {
+                    gradient: Some(gradient),
+                    ..self
+                }
+            }
+
+            #[doc = "Bound on the norm of the gradient"]
+            pub fn bound(self, bound: f64) -> Self {
+                Builder {
+                    bound: Some(bound),
+                    ..self
+                }
+            }
+
+            pub fn refresh(self, refresh: f64) -> Self {
+                Builder { refresh, ..self }
+            }
+        }
+        impl<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> Build<nd::Array1<f64>> for Builder<G> {
+            type Sampler = Sampler<G>;
+            fn build(self) -> Result<Sampler<G>, Error> {
+                let bound = self.bound.ok_or(Error::Missing("bound"))?;
+                if bound.is_nan() || bound <= 0.0 {
+                    return Err(Error::NotPositive("bound"));
+                }
+                if self.refresh.is_nan() || self.refresh <= 0.0 {
+                    return Err(Error::NotPositive("refresh"));
+                }
+                Ok(Sampler {
+                    gradient: Arc::new(self.gradient.ok_or(Error::Missing("gradient"))?),
+                    bound,
+                    refresh: self.refresh,
+                })
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn hyperbolic() {
+                let gradient = |x: &nd::Array1<f64>| x / (1.0 + x.dot(x)).sqrt();
+                let bouncy = pdmp::Bouncy::builder()
+                    .gradient(gradient)
+                    .bound(1.0)
+                    .refresh(0.5)
+                    .build()
+                    .unwrap();
+                let mean = pdmp::mean(bouncy.iter(nd::Array1::zeros(2)).take(20000));
+                assert!(mean.iter().all(|m| m.abs() < 0.3));
+
+                let frozen = pdmp::Bouncy::builder().gradient(gradient).bound(1.0);
+                let frozen = frozen.refresh(0.0).build();
+                assert_eq!(frozen.err(), Some(Error::NotPositive("refresh")));
+            }
+        }
+    }
+
+    #[doc = "Zig-Zag process with velocities in `{-1, 1}^d`"]
+    pub mod zigzag {
+        use super::*;
+
+        pub struct Sampler<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> {
+            pub gradient: Arc<G>,
+            pub bound: nd::Array1<f64>,
+        }
+        impl<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> Sampler<G> {
+            #[doc = "Target `exp(-U)` given `∇U` and bounds on its coordinates' magnitude"]
+            #[allow(unused)]
+            #[deprecated(note = "use `builder`, which reports invalid configuration")]
+            pub fn new(gradient: G, bound: nd::Array1<f64>) -> Self {
+                (Self::builder().gradient(gradient).bound(bound).build())
+                    .unwrap_or_else(|e| panic!("{}", e))
+            }
+
+            pub fn builder() -> Builder<G> {
+                Builder {
+                    gradient: None,
+                    bound: None,
+                }
+            }
+
+            pub fn iter(&self, position: nd::Array1<f64>) -> impl Iterator<Item = Event> {
+                self.iter_with_rng(position, rand::thread_rng())
+            }
+
+            pub fn iter_with_rng<R: rand::Rng>(
+                &self,
+                position: nd::Array1<f64>,
+                mut gen: R,
+            ) -> impl Iterator<Item = Event> {
+                assert_eq!(position.len(), self.bound.len(), "dimension mismatch");
+                let (gradient, bound) = (self.gradient.clone(), self.bound.clone());
+                let total = bound.sum();
+                let velocity = position.mapv(|_| if gen.gen_bool(0.5) { 1.0 } else { -1.0 });
+                let mut event = Event {
+                    time: 0.0,
+                    position,
+                    velocity,
+                };
+                std::iter::once(event.clone()).chain(std::iter::from_fn(move || loop {
+                    let tau = arrival(total, &mut gen);
+                    event.time += tau;
+                    event.position = &event.position + &(&event.velocity * tau);
+
+                    let mut aux = gen.gen_range(0.0..total);
+                    let i = (bound.iter())
+                        .position(|b| {
+                            aux -= b;
+                            aux < 0.0
+                        })
+                        .unwrap_or(bound.len() - 1);
+                    let rate = (event.velocity[i] * gradient(&event.position)[i]).max(0.0);
+                    if gen.gen_range(0.0..bound[i]) < rate {
+                        event.velocity[i] = -event.velocity[i];
+                        return Some(event.clone());
+                    }
+                }))
+            }
+        }
+
+        pub struct Builder<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> {
+            gradient: Option<G>,
+            bound: Option<nd::Array1<f64>>,
+        }
+        impl<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> Builder<G> {
+            #[doc = "`∇U` of the target `exp(-U)`"]
+            pub fn gradient(self, gradient: G) -> Self {
+                Builder {
+                    gradient: Some(gradient),
+                    ..self
+                }
+            }
+
+            #[doc = "Bounds on the magnitude of each coordinate of the gradient"]
+            pub fn bound(self, bound: nd::Array1<f64>) -> Self {
+                Builder {
+                    bound: Some(bound),
+                    ..self
+                }
+            }
+        }
+        impl<G: Fn(&nd::Array1<f64>) -> nd::Array1<f64>> Build<nd::Array1<f64>> for Builder<G> {
+            type Sampler = Sampler<G>;
+            fn build(self) -> Result<Sampler<G>, Error> {
+                let bound = self.bound.ok_or(Error::Missing("bound"))?;
+                if bound.is_empty() || !bound.iter().all(|b| *b > 0.0) {
+                    return Err(Error::NotPositive("bound"));
+                }
+                Ok(Sampler {
+                    gradient: Arc::new(self.gradient.ok_or(Error::Missing("gradient"))?),
+                    bound,
+                })
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn hyperbolic() {
+                let gradient = |x: &nd::Array1<f64>| x.mapv(|x| x / (1.0 + x * x).sqrt());
+                let zigzag = pdmp::ZigZag::builder()
+                    .gradient(gradient)
+                    .bound(nd::Array1::from(vec![1.0, 1.0]))
+                    .build()
+                    .unwrap();
+                let xs: Vec<nd::Array1<f64>> =
+                    pdmp::discretize(zigzag.iter(nd::Array1::zeros(2)), 0.5)
+                        .take(20000)
+                        .collect();
+                let mean = xs.iter().map(|x| x[0]).sum::<f64>() / xs.len() as f64;
+                assert!(mean.abs() < 0.3);
             }
         }
     }
@@ -0,0 +1,90 @@
+#[doc = "Representation of unnormalized probabilities returned by pdfs"]
+pub trait Scale: 'static {
+    fn from_ln(ln: f64) -> f64;
+    fn ln(p: f64) -> f64;
+
+    #[doc = "Linear ratio `new / old`"]
+    fn ratio(new: f64, old: f64) -> f64;
+
+    #[doc = "Product with a linear factor"]
+    fn mul(p: f64, factor: f64) -> f64;
+
+    #[doc = "Power `p^beta`, as in tempering"]
+    fn pow(p: f64, beta: f64) -> f64;
+
+    #[doc = "Density of an impossible state"]
+    fn zero() -> f64 {
+        Self::from_ln(f64::NEG_INFINITY)
+    }
+}
+
+#[doc = "Linear weights proportional to `ps`, exp-normalized against the largest"]
+pub fn weights<L: Scale, I: IntoIterator<Item = f64>>(ps: I) -> Vec<f64> {
+    let lns: Vec<f64> = ps.into_iter().map(L::ln).collect();
+    let max = lns.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    assert!(max > f64::NEG_INFINITY, "pdf isn't positive");
+    assert!(max < f64::INFINITY, "pdf overflow");
+    lns.iter().map(|ln| (ln - max).exp()).collect()
+}
+
+#[doc = "Plain densities"]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Linear;
+impl Scale for Linear {
+    fn from_ln(ln: f64) -> f64 {
+        ln.exp()
+    }
+    fn ln(p: f64) -> f64 {
+        p.ln()
+    }
+    fn ratio(new: f64, old: f64) -> f64 {
+        new / old
+    }
+    fn mul(p: f64, factor: f64) -> f64 {
+        p * factor
+    }
+    fn pow(p: f64, beta: f64) -> f64 {
+        p.powf(beta)
+    }
+}
+
+#[doc = "Log-densities, for targets under- or overflowing in linear space"]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Log;
+impl Scale for Log {
+    fn from_ln(ln: f64) -> f64 {
+        ln
+    }
+    fn ln(p: f64) -> f64 {
+        p
+    }
+    fn ratio(new: f64, old: f64) -> f64 {
+        (new - old).exp()
+    }
+    fn mul(p: f64, factor: f64) -> f64 {
+        p + factor.ln()
+    }
+    fn pow(p: f64, beta: f64) -> f64 {
+        p * beta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn underflow() {
+        let (p, q) = (-1000.0, -1001.0);
+        assert!(Linear::ratio(Linear::from_ln(q), Linear::from_ln(p)).is_nan());
+        assert!((Log::ratio(Log::from_ln(q), Log::from_ln(p)) - (-1.0f64).exp()).abs() < 1e-12);
+        assert!((Log::mul(p, 0.5) - (p + 0.5f64.ln())).abs() < 1e-12);
+        assert_eq!(Log::pow(p, 0.5), -500.0);
+        assert_eq!((Linear::zero(), Log::zero()), (0.0, f64::NEG_INFINITY));
+        assert_eq!(
+            weights::<Log, _>([p, q, f64::NEG_INFINITY])[..2],
+            [1.0, (-1.0f64).exp()]
+        );
+        assert_eq!(weights::<Log, _>([p, q, f64::NEG_INFINITY])[2], 0.0);
+    }
+}
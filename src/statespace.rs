@@ -0,0 +1,690 @@
+use super::*;
+
+#[doc = "State-space model with latent markov states"]
+pub trait Model {
+    type State: Clone;
+    type Obs;
+    fn initial<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Self::State;
+    fn transition<R: rand::Rng + ?Sized>(&self, x: &Self::State, gen: &mut R) -> Self::State;
+    fn transition_pdf(&self, x: &Self::State, next: &Self::State) -> f64;
+    fn likelihood(&self, x: &Self::State, y: &Self::Obs) -> f64;
+}
+
+fn categorical<R: rand::Rng + ?Sized>(ws: &[f64], gen: &mut R) -> usize {
+    let sum: f64 = ws.iter().sum();
+    assert!(sum > 0.0, "weights aren't positive");
+    let mut aux = gen.gen_range(0.0..sum);
+    ws.iter()
+        .position(|w| {
+            aux -= w;
+            aux < 0.0
+        })
+        .unwrap_or(ws.len() - 1)
+}
+
+#[doc = "Sequential Monte Carlo"]
+pub mod smc {
+    use super::*;
+
+    pub use conditional::Kernel as Conditional;
+    pub use gibbs::Sampler as ParticleGibbs;
+    pub use rbpf::filter as rao_blackwellized;
+
+    pub struct Filter<S> {
+        pub particles: Vec<Vec<S>>,
+        pub ancestors: Vec<Vec<usize>>,
+        pub weights: Vec<f64>,
+        pub log_evidence: f64,
+    }
+    impl<S: Clone> Filter<S> {
+        pub fn trajectory(&self, mut k: usize) -> Vec<S> {
+            let mut xs: Vec<S> = (0..self.particles.len())
+                .rev()
+                .map(|t| {
+                    let x = self.particles[t][k].clone();
+                    k = self.ancestors[t][k];
+                    x
+                })
+                .collect();
+            xs.reverse();
+            xs
+        }
+    }
+
+    #[doc = "Bootstrap particle filter"]
+    pub fn filter<M: Model>(model: &M, ys: &[M::Obs], n: usize) -> Filter<M::State> {
+        filter_with_rng(model, ys, n, &mut rand::thread_rng())
+    }
+
+    #[doc = "Bootstrap particle filter drawing from `gen`"]
+    pub fn filter_with_rng<M: Model, R: rand::Rng + ?Sized>(
+        model: &M,
+        ys: &[M::Obs],
+        n: usize,
+        gen: &mut R,
+    ) -> Filter<M::State> {
+        sweep(model, ys, n, None, false, gen)
+    }
+
+    // particle sweep, optionally conditioned on a reference trajectory
+    fn sweep<M: Model, R: rand::Rng + ?Sized>(
+        model: &M,
+        ys: &[M::Obs],
+        n: usize,
+        reference: Option<&[M::State]>,
+        ancestor: bool,
+        gen: &mut R,
+    ) -> Filter<M::State> {
+        assert!(n > 1, "too few particles");
+        assert!(!ys.is_empty(), "no observations");
+        let free = if reference.is_some() { n - 1 } else { n };
+
+        let (mut particles, mut ancestors): (Vec<Vec<M::State>>, _) = (vec![], vec![]);
+        let mut weights = vec![1.0; n];
+        let mut log_evidence = 0.0;
+        for (t, y) in ys.iter().enumerate() {
+            let (mut xs, mut ax): (Vec<M::State>, Vec<usize>) = match particles.last() {
+                None => (0..free).map(|i| (model.initial(gen), i)).unzip(),
+                Some(prev) => (0..free)
+                    .map(|_| {
+                        let a = categorical(&weights, gen);
+                        (model.transition(&prev[a], gen), a)
+                    })
+                    .unzip(),
+            };
+            if let Some(reference) = reference {
+                let a = match particles.last() {
+                    Some(prev) if ancestor => {
+                        let ws: Vec<f64> = (prev.iter().zip(weights.iter()))
+                            .map(|(x, w)| w * model.transition_pdf(x, &reference[t]))
+                            .collect();
+                        categorical(&ws, gen)
+                    }
+                    _ => n - 1,
+                };
+                xs.push(reference[t].clone());
+                ax.push(a);
+            }
+
+            weights = xs.iter().map(|x| model.likelihood(x, y)).collect();
+            let sum: f64 = weights.iter().sum();
+            log_evidence += (sum / n as f64).ln();
+            weights.iter_mut().for_each(|w| *w /= sum);
+
+            particles.push(xs);
+            ancestors.push(ax);
+        }
+
+        Filter {
+            particles,
+            ancestors,
+            weights,
+            log_evidence,
+        }
+    }
+
+    #[doc = "Conditional SMC update of a reference trajectory"]
+    pub mod conditional {
+        use super::*;
+
+        pub struct Kernel<'a, M: Model> {
+            pub model: &'a M,
+            pub ys: &'a [M::Obs],
+            pub particles: usize,
+            pub ancestor: bool,
+        }
+        impl<'a, M: Model> Kernel<'a, M> {
+            #[allow(unused)]
+            pub fn new(model: &'a M, ys: &'a [M::Obs], particles: usize) -> Self {
+                assert!(particles > 1, "too few particles");
+                Kernel {
+                    model,
+                    ys,
+                    particles,
+                    ancestor: true,
+                }
+            }
+        }
+        impl<'a, M: Model> sampler::Kernel<Vec<M::State>> for Kernel<'a, M> {
+            fn step<R: rand::Rng + ?Sized>(
+                &self,
+                reference: &Vec<M::State>,
+                gen: &mut R,
+            ) -> Vec<M::State> {
+                assert_eq!(self.ys.len(), reference.len(), "reference mismatch");
+                let filter = sweep(
+                    self.model,
+                    self.ys,
+                    self.particles,
+                    Some(reference),
+                    self.ancestor,
+                    gen,
+                );
+                filter.trajectory(categorical(&filter.weights, gen))
+            }
+        }
+    }
+
+    #[doc = "Particle Gibbs with Ancestor Sampling"]
+    pub mod gibbs {
+        use super::*;
+
+        pub struct Sampler {
+            pub particles: usize,
+        }
+        impl Sampler {
+            #[allow(unused)]
+            pub fn new(particles: usize) -> Self {
+                assert!(particles > 1, "too few particles");
+                Sampler { particles }
+            }
+
+            #[doc = "Alternate trajectory and parameter updates"]
+            pub fn iter<M: Model + Clone, U: FnMut(&[M::State]) -> M>(
+                &self,
+                model: M,
+                ys: Vec<M::Obs>,
+                update: U,
+            ) -> impl Iterator<Item = (M, Vec<M::State>)> {
+                self.iter_with_rng(model, ys, update, rand::thread_rng())
+            }
+
+            #[doc = "Alternate trajectory and parameter updates, drawing from `gen`"]
+            pub fn iter_with_rng<M: Model + Clone, U: FnMut(&[M::State]) -> M, R: rand::Rng>(
+                &self,
+                mut model: M,
+                ys: Vec<M::Obs>,
+                mut update: U,
+                mut gen: R,
+            ) -> impl Iterator<Item = (M, Vec<M::State>)> {
+                let particles = self.particles;
+                let filter = sweep(&model, &ys, particles, None, false, &mut gen);
+                let mut xs = filter.trajectory(categorical(&filter.weights, &mut gen));
+                std::iter::from_fn(move || {
+                    xs = smc::Conditional::new(&model, &ys, particles).step(&xs, &mut gen);
+                    model = update(&xs);
+                    Some((model.clone(), xs.clone()))
+                })
+            }
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[derive(Clone)]
+            struct Walk(f64);
+            impl Model for Walk {
+                type State = f64;
+                type Obs = f64;
+                fn initial<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> f64 {
+                    dist::normal(gen)
+                }
+                fn transition<R: rand::Rng + ?Sized>(&self, x: &f64, gen: &mut R) -> f64 {
+                    x + self.0 * dist::normal(gen)
+                }
+                fn transition_pdf(&self, x: &f64, next: &f64) -> f64 {
+                    (-(next - x).powi(2) / (2.0 * self.0.powi(2))).exp()
+                }
+                fn likelihood(&self, x: &f64, y: &f64) -> f64 {
+                    (-(y - x).powi(2) / 2.0).exp()
+                }
+            }
+
+            #[test]
+            fn walk() {
+                use rand::SeedableRng;
+                let gen = rand::rngs::StdRng::seed_from_u64(0);
+                let ys: Vec<f64> = (0..20).map(|t| t as f64 / 10.0).collect();
+                let draws: Vec<Vec<f64>> = smc::ParticleGibbs::new(16)
+                    .iter_with_rng(Walk(0.5), ys.clone(), |_| Walk(0.5), gen)
+                    .skip(100)
+                    .take(2000)
+                    .map(|(_, xs)| xs)
+                    .collect();
+                assert!(draws.iter().all(|xs| xs.len() == ys.len()));
+
+                // Rauch-Tung-Striebel smoother of the same linear gaussian walk
+                let (mut m, mut p) = (0.0, 1.0);
+                let (mut ms, mut ps) = (vec![], vec![]);
+                for (t, y) in ys.iter().enumerate() {
+                    if t > 0 {
+                        p += 0.25;
+                    }
+                    let k = p / (p + 1.0);
+                    m += k * (y - m);
+                    p *= 1.0 - k;
+                    ms.push(m);
+                    ps.push(p);
+                }
+                let mut smoothed = ms.clone();
+                for t in (0..ys.len() - 1).rev() {
+                    let g = ps[t] / (ps[t] + 0.25);
+                    smoothed[t] = ms[t] + g * (smoothed[t + 1] - ms[t]);
+                }
+                for (t, expected) in smoothed.iter().enumerate() {
+                    let mean = draws.iter().map(|xs| xs[t]).sum::<f64>() / draws.len() as f64;
+                    assert!((mean - expected).abs() < 0.1);
+                }
+            }
+        }
+    }
+
+    #[doc = "Rao-Blackwellized particle filter for conditionally linear gaussian models"]
+    pub mod rbpf {
+        use super::*;
+
+        #[doc = "Markov chain driving a linear gaussian substate"]
+        pub trait Model {
+            type State: Clone;
+            fn initial<R: rand::Rng + ?Sized>(&self, gen: &mut R) -> Self::State;
+            fn transition<R: rand::Rng + ?Sized>(
+                &self,
+                u: &Self::State,
+                gen: &mut R,
+            ) -> Self::State;
+            fn linear(&self, u: &Self::State) -> kalman::Linear;
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Particle<S> {
+            pub state: S,
+            pub mean: na::DVector<f64>,
+            pub cov: na::DMatrix<f64>,
+        }
+
+        #[doc = "Particle filter on the chain, Kalman filter on the substate"]
+        pub fn filter<M: Model>(
+            model: &M,
+            ys: &[na::DVector<f64>],
+            n: usize,
+        ) -> Filter<Particle<M::State>> {
+            filter_with_rng(model, ys, n, &mut rand::thread_rng())
+        }
+
+        #[doc = "Rao-Blackwellized filter drawing from `gen`"]
+        pub fn filter_with_rng<M: Model, R: rand::Rng + ?Sized>(
+            model: &M,
+            ys: &[na::DVector<f64>],
+            n: usize,
+            gen: &mut R,
+        ) -> Filter<Particle<M::State>> {
+            assert!(n > 1, "too few particles");
+            assert!(!ys.is_empty(), "no observations");
+
+            let (mut particles, mut ancestors): (Vec<Vec<Particle<M::State>>>, _) =
+                (vec![], vec![]);
+            let mut weights = vec![1.0; n];
+            let mut log_evidence = 0.0;
+            for y in ys {
+                let step = |state: M::State, prev: Option<&Particle<M::State>>| {
+                    let l = model.linear(&state);
+                    let (mut mean, mut cov) = match prev {
+                        None => (l.mean, l.cov),
+                        Some(x) => (&l.f * &x.mean, &l.f * &x.cov * l.f.transpose() + l.q),
+                    };
+                    let yhat = &l.h * &mean;
+                    let c = &cov * l.h.transpose();
+                    let s = &l.h * &c + l.r;
+                    let log_pdf = kalman::correct(&mut mean, &mut cov, y, yhat, s, c);
+                    (Particle { state, mean, cov }, log_pdf)
+                };
+                let (xs, (lws, ax)): (Vec<_>, (Vec<f64>, Vec<_>)) = match particles.last() {
+                    None => (0..n)
+                        .map(|i| {
+                            let (x, lw) = step(model.initial(gen), None);
+                            (x, (lw, i))
+                        })
+                        .unzip(),
+                    Some(prev) => (0..n)
+                        .map(|_| {
+                            let a = categorical(&weights, gen);
+                            let (x, lw) =
+                                step(model.transition(&prev[a].state, gen), Some(&prev[a]));
+                            (x, (lw, a))
+                        })
+                        .unzip(),
+                };
+
+                let max = lws.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+                weights = lws.iter().map(|lw| (lw - max).exp()).collect();
+                let sum: f64 = weights.iter().sum();
+                log_evidence += max + (sum / n as f64).ln();
+                weights.iter_mut().for_each(|w| *w /= sum);
+
+                particles.push(xs);
+                ancestors.push(ax);
+            }
+
+            Filter {
+                particles,
+                ancestors,
+                weights,
+                log_evidence,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        struct Fixed(kalman::Linear);
+        impl rbpf::Model for Fixed {
+            type State = ();
+            fn initial<R: rand::Rng + ?Sized>(&self, _: &mut R) {}
+            fn transition<R: rand::Rng + ?Sized>(&self, _: &(), _: &mut R) {}
+            fn linear(&self, _: &()) -> kalman::Linear {
+                self.0.clone()
+            }
+        }
+
+        #[test]
+        fn marginal() {
+            let eye = || na::DMatrix::identity(1, 1);
+            let linear =
+                kalman::Linear::new(na::DVector::zeros(1), eye(), eye(), eye(), eye(), eye());
+            let ys: Vec<na::DVector<f64>> = (0..10)
+                .map(|t| na::DVector::from_vec(vec![t as f64 / 10.0]))
+                .collect();
+            let exact = kalman::extended(&linear, &ys);
+            let filter = smc::rao_blackwellized(&Fixed(linear), &ys, 8);
+            assert!((exact.log_evidence - filter.log_evidence).abs() < 1e-9);
+        }
+    }
+}
+
+#[doc = "Kalman filtering of gaussian state-space models"]
+pub mod kalman {
+    use super::*;
+
+    pub use unscented::Filter as Unscented;
+
+    #[doc = "State-space model with additive gaussian noise"]
+    pub trait Gaussian {
+        fn initial(&self) -> (na::DVector<f64>, na::DMatrix<f64>);
+        fn transition(&self, x: &na::DVector<f64>) -> na::DVector<f64>;
+        fn observation(&self, x: &na::DVector<f64>) -> na::DVector<f64>;
+        fn transition_cov(&self) -> na::DMatrix<f64>;
+        fn observation_cov(&self) -> na::DMatrix<f64>;
+
+        fn transition_jacobian(&self, x: &na::DVector<f64>) -> na::DMatrix<f64> {
+            jacobian(|x| self.transition(x), x)
+        }
+        fn observation_jacobian(&self, x: &na::DVector<f64>) -> na::DMatrix<f64> {
+            jacobian(|x| self.observation(x), x)
+        }
+    }
+
+    // forward differences, column by column
+    fn jacobian<F: Fn(&na::DVector<f64>) -> na::DVector<f64>>(
+        f: F,
+        x: &na::DVector<f64>,
+    ) -> na::DMatrix<f64> {
+        let fx = f(x);
+        let columns: Vec<na::DVector<f64>> = (0..x.len())
+            .map(|j| {
+                let h = 1e-6 * (1.0 + x[j].abs());
+                let mut x = x.clone();
+                x[j] += h;
+                (f(&x) - &fx) / h
+            })
+            .collect();
+        na::DMatrix::from_columns(&columns)
+    }
+
+    #[doc = "Linear gaussian model `x' = f x + q, y = h x + r`"]
+    #[derive(Clone, Debug)]
+    pub struct Linear {
+        pub mean: na::DVector<f64>,
+        pub cov: na::DMatrix<f64>,
+        pub f: na::DMatrix<f64>,
+        pub h: na::DMatrix<f64>,
+        pub q: na::DMatrix<f64>,
+        pub r: na::DMatrix<f64>,
+    }
+    impl Linear {
+        #[allow(unused)]
+        pub fn new(
+            mean: na::DVector<f64>,
+            cov: na::DMatrix<f64>,
+            f: na::DMatrix<f64>,
+            h: na::DMatrix<f64>,
+            q: na::DMatrix<f64>,
+            r: na::DMatrix<f64>,
+        ) -> Self {
+            let n = mean.len();
+            assert_eq!(cov.shape(), (n, n), "covariance mismatch");
+            assert_eq!(f.shape(), (n, n), "transition mismatch");
+            assert_eq!(q.shape(), (n, n), "transition noise mismatch");
+            assert_eq!(h.ncols(), n, "observation mismatch");
+            assert_eq!(
+                r.shape(),
+                (h.nrows(), h.nrows()),
+                "observation noise mismatch"
+            );
+            Linear {
+                mean,
+                cov,
+                f,
+                h,
+                q,
+                r,
+            }
+        }
+    }
+    impl Gaussian for Linear {
+        fn initial(&self) -> (na::DVector<f64>, na::DMatrix<f64>) {
+            (self.mean.clone(), self.cov.clone())
+        }
+        fn transition(&self, x: &na::DVector<f64>) -> na::DVector<f64> {
+            &self.f * x
+        }
+        fn observation(&self, x: &na::DVector<f64>) -> na::DVector<f64> {
+            &self.h * x
+        }
+        fn transition_cov(&self) -> na::DMatrix<f64> {
+            self.q.clone()
+        }
+        fn observation_cov(&self) -> na::DMatrix<f64> {
+            self.r.clone()
+        }
+        fn transition_jacobian(&self, _: &na::DVector<f64>) -> na::DMatrix<f64> {
+            self.f.clone()
+        }
+        fn observation_jacobian(&self, _: &na::DVector<f64>) -> na::DMatrix<f64> {
+            self.h.clone()
+        }
+    }
+
+    #[doc = "Filtered means and covariances"]
+    #[derive(Clone, Debug)]
+    pub struct Estimate {
+        pub means: Vec<na::DVector<f64>>,
+        pub covs: Vec<na::DMatrix<f64>>,
+        pub log_evidence: f64,
+    }
+
+    // condition predicted moments on an observation, returning its log predictive density
+    pub(crate) fn correct(
+        m: &mut na::DVector<f64>,
+        p: &mut na::DMatrix<f64>,
+        y: &na::DVector<f64>,
+        yhat: na::DVector<f64>,
+        s: na::DMatrix<f64>,
+        c: na::DMatrix<f64>,
+    ) -> f64 {
+        let chol = s.cholesky().expect("innovation isn't positive definite");
+        let v = y - yhat;
+        let k = chol.solve(&c.transpose()).transpose();
+        let log_det: f64 = chol.l().diagonal().iter().map(|l| l.ln()).sum();
+        let log_pdf =
+            -(v.len() as f64 * (2.0 * std::f64::consts::PI).ln() + v.dot(&chol.solve(&v))) / 2.0
+                - log_det;
+        *m += &k * v;
+        *p -= k * c.transpose();
+        log_pdf
+    }
+
+    // predict-update recursion, given the predicted state and observation moments
+    fn recurse<P, O>(
+        ys: &[na::DVector<f64>],
+        initial: (na::DVector<f64>, na::DMatrix<f64>),
+        predict: P,
+        observe: O,
+    ) -> Estimate
+    where
+        P: Fn(&na::DVector<f64>, &na::DMatrix<f64>) -> (na::DVector<f64>, na::DMatrix<f64>),
+        O: Fn(
+            &na::DVector<f64>,
+            &na::DMatrix<f64>,
+        ) -> (na::DVector<f64>, na::DMatrix<f64>, na::DMatrix<f64>),
+    {
+        let (mut means, mut covs) = (vec![], vec![]);
+        let mut log_evidence = 0.0;
+        let (mut m, mut p) = initial;
+        for (t, y) in ys.iter().enumerate() {
+            if t > 0 {
+                let prior = predict(&m, &p);
+                m = prior.0;
+                p = prior.1;
+            }
+            let (yhat, s, c) = observe(&m, &p);
+            log_evidence += correct(&mut m, &mut p, y, yhat, s, c);
+            means.push(m.clone());
+            covs.push(p.clone());
+        }
+        Estimate {
+            means,
+            covs,
+            log_evidence,
+        }
+    }
+
+    #[doc = "Extended Kalman filter, exact for linear models"]
+    pub fn extended<M: Gaussian>(model: &M, ys: &[na::DVector<f64>]) -> Estimate {
+        recurse(
+            ys,
+            model.initial(),
+            |m, p| {
+                let f = model.transition_jacobian(m);
+                (
+                    model.transition(m),
+                    &f * p * f.transpose() + model.transition_cov(),
+                )
+            },
+            |m, p| {
+                let h = model.observation_jacobian(m);
+                let c = p * h.transpose();
+                (model.observation(m), &h * &c + model.observation_cov(), c)
+            },
+        )
+    }
+
+    #[doc = "Unscented Kalman filter"]
+    pub mod unscented {
+        use super::*;
+
+        pub struct Filter {
+            pub alpha: f64,
+            pub beta: f64,
+            pub kappa: f64,
+        }
+        impl Filter {
+            #[allow(unused)]
+            pub fn new(alpha: f64, beta: f64, kappa: f64) -> Self {
+                assert!(alpha > 0.0, "alpha isn't positive");
+                Filter { alpha, beta, kappa }
+            }
+
+            pub fn filter<M: Gaussian>(&self, model: &M, ys: &[na::DVector<f64>]) -> Estimate {
+                recurse(
+                    ys,
+                    model.initial(),
+                    |m, p| {
+                        let (xs, wm, wc) = self.points(m, p);
+                        let (m, p, _) = transform(&xs, &wm, &wc, |x| model.transition(x));
+                        (m, p + model.transition_cov())
+                    },
+                    |m, p| {
+                        let (xs, wm, wc) = self.points(m, p);
+                        let (yhat, s, c) = transform(&xs, &wm, &wc, |x| model.observation(x));
+                        (yhat, s + model.observation_cov(), c)
+                    },
+                )
+            }
+
+            // sigma points with mean and covariance weights
+            fn points(
+                &self,
+                m: &na::DVector<f64>,
+                p: &na::DMatrix<f64>,
+            ) -> (Vec<na::DVector<f64>>, Vec<f64>, Vec<f64>) {
+                let n = m.len() as f64;
+                let lambda = self.alpha.powi(2) * (n + self.kappa) - n;
+                let l = (p * (n + lambda))
+                    .cholesky()
+                    .expect("covariance isn't positive definite")
+                    .l();
+                let mut xs = vec![m.clone()];
+                for j in 0..m.len() {
+                    xs.push(m + l.column(j));
+                    xs.push(m - l.column(j));
+                }
+                let mut wm = vec![1.0 / (2.0 * (n + lambda)); xs.len()];
+                let mut wc = wm.clone();
+                wm[0] = lambda / (n + lambda);
+                wc[0] = wm[0] + 1.0 - self.alpha.powi(2) + self.beta;
+                (xs, wm, wc)
+            }
+        }
+
+        // propagated mean, covariance, and cross-covariance with the sigma points
+        fn transform<F: Fn(&na::DVector<f64>) -> na::DVector<f64>>(
+            xs: &[na::DVector<f64>],
+            wm: &[f64],
+            wc: &[f64],
+            f: F,
+        ) -> (na::DVector<f64>, na::DMatrix<f64>, na::DMatrix<f64>) {
+            let ys: Vec<na::DVector<f64>> = xs.iter().map(f).collect();
+            let mean = (ys.iter().zip(wm.iter()))
+                .fold(na::DVector::zeros(ys[0].len()), |acc, (y, w)| acc + y * *w);
+            let mut cov = na::DMatrix::zeros(mean.len(), mean.len());
+            let mut cross = na::DMatrix::zeros(xs[0].len(), mean.len());
+            for ((x, y), w) in xs.iter().zip(ys.iter()).zip(wc.iter()) {
+                let dy = y - &mean;
+                cov += &dy * dy.transpose() * *w;
+                cross += (x - &xs[0]) * dy.transpose() * *w;
+            }
+            (mean, cov, cross)
+        }
+
+        #[cfg(test)]
+        mod tests {
+            use super::*;
+
+            #[test]
+            fn linear() {
+                let eye = || na::DMatrix::identity(1, 1);
+                let model = Linear::new(
+                    na::DVector::zeros(1),
+                    eye(),
+                    eye(),
+                    eye(),
+                    eye() * 0.5,
+                    eye(),
+                );
+                let ys: Vec<na::DVector<f64>> = (0..10)
+                    .map(|t| na::DVector::from_vec(vec![t as f64 / 10.0]))
+                    .collect();
+                let exact = kalman::extended(&model, &ys);
+                let sigma = Unscented::new(1.0, 2.0, 0.0).filter(&model, &ys);
+                assert!((exact.covs[0][(0, 0)] - 0.5).abs() < 1e-12);
+                for (a, b) in exact.means.iter().zip(sigma.means.iter()) {
+                    assert!((a - b).norm() < 1e-9);
+                }
+                assert!((exact.log_evidence - sigma.log_evidence).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,684 @@
+use super::*;
+use std::sync::Arc;
+
+pub use permutation::Test as Permutation;
+
+#[doc = "Fisher-Yates shuffle in place"]
+pub fn shuffle<T, R: rand::Rng + ?Sized>(xs: &mut [T], gen: &mut R) {
+    for i in (1..xs.len()).rev() {
+        xs.swap(i, gen.gen_range(0..=i));
+    }
+}
+
+#[doc = "Counts of samples over a discrete domain"]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram<D: Discrete> {
+    pub support: Vec<D>,
+    pub counts: Vec<usize>,
+    pub total: usize,
+}
+impl<D: Discrete> Histogram<D> {
+    #[allow(unused)]
+    pub fn new() -> Self {
+        let support: Vec<D> = D::iter().collect();
+        Histogram {
+            counts: vec![0; support.len()],
+            support,
+            total: 0,
+        }
+    }
+
+    pub fn observe(&mut self, x: D) {
+        let i = (self.support.iter())
+            .position(|y| *y == x)
+            .expect("sample isn't in the domain");
+        self.counts[i] += 1;
+        self.total += 1;
+    }
+
+    pub fn count(&self, x: &D) -> usize {
+        (self.support.iter())
+            .position(|y| y == x)
+            .map_or(0, |i| self.counts[i])
+    }
+
+    #[doc = "Relative frequencies, in the order of the domain"]
+    pub fn frequencies(&self) -> Vec<f64> {
+        assert!(self.total > 0, "no samples");
+        (self.counts.iter())
+            .map(|c| *c as f64 / self.total as f64)
+            .collect()
+    }
+
+    #[doc = "Total variation distance to the normalized `pmf`"]
+    pub fn total_variation<P: Fn(&D) -> f64>(&self, pmf: P) -> f64 {
+        let ps: Vec<f64> = self.support.iter().map(pmf).collect();
+        let sum: f64 = ps.iter().sum();
+        assert!(sum > 0.0, "pmf isn't positive");
+        (self.frequencies().iter())
+            .zip(ps)
+            .map(|(f, p)| (f - p / sum).abs())
+            .sum::<f64>()
+            / 2.0
+    }
+}
+impl<D: Discrete> Extend<D> for Histogram<D> {
+    fn extend<I: IntoIterator<Item = D>>(&mut self, samples: I) {
+        samples.into_iter().for_each(|x| self.observe(x))
+    }
+}
+impl<D: Discrete> FromIterator<D> for Histogram<D> {
+    fn from_iter<I: IntoIterator<Item = D>>(samples: I) -> Self {
+        let mut histogram = Histogram::new();
+        histogram.extend(samples);
+        histogram
+    }
+}
+
+#[doc = "Running mean, variance, skewness and kurtosis by Welford's online update"]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Moments {
+    pub n: usize,
+    pub mean: f64,
+    m2: f64,
+    m3: f64,
+    m4: f64,
+}
+impl Moments {
+    #[allow(unused)]
+    pub fn new() -> Self {
+        Moments::default()
+    }
+
+    pub fn observe(&mut self, x: f64) {
+        let n0 = self.n as f64;
+        self.n += 1;
+        let n = self.n as f64;
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let term = delta * delta_n * n0;
+        self.mean += delta_n;
+        self.m4 += term * delta_n * delta_n * (n * n - 3.0 * n + 3.0)
+            + 6.0 * delta_n * delta_n * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+    }
+
+    #[doc = "Unbiased sample variance"]
+    pub fn variance(&self) -> f64 {
+        assert!(self.n > 1, "too few samples");
+        self.m2 / (self.n - 1) as f64
+    }
+
+    pub fn skewness(&self) -> f64 {
+        assert!(self.n > 0, "no samples");
+        (self.n as f64).sqrt() * self.m3 / self.m2.powf(1.5)
+    }
+
+    #[doc = "Excess kurtosis, zero for the gaussian"]
+    pub fn kurtosis(&self) -> f64 {
+        assert!(self.n > 0, "no samples");
+        self.n as f64 * self.m4 / (self.m2 * self.m2) - 3.0
+    }
+}
+impl Extend<f64> for Moments {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, samples: I) {
+        samples.into_iter().for_each(|x| self.observe(x))
+    }
+}
+impl FromIterator<f64> for Moments {
+    fn from_iter<I: IntoIterator<Item = f64>>(samples: I) -> Self {
+        let mut moments = Moments::new();
+        moments.extend(samples);
+        moments
+    }
+}
+
+#[doc = "Running quantiles in constant memory by the P² algorithm (Jain-Chlamtac)"]
+#[derive(Clone, Debug)]
+pub struct Quantiles {
+    pub probs: Vec<f64>,
+    markers: Vec<Markers>,
+    init: Vec<f64>,
+}
+impl Quantiles {
+    #[allow(unused)]
+    pub fn new(probs: &[f64]) -> Self {
+        assert!(
+            probs.iter().all(|p| 0.0 < *p && *p < 1.0),
+            "probability isn't in (0, 1)"
+        );
+        Quantiles {
+            probs: probs.to_vec(),
+            markers: vec![],
+            init: vec![],
+        }
+    }
+
+    pub fn observe(&mut self, x: f64) {
+        assert!(!x.is_nan(), "sample is nan");
+        if self.init.len() < 5 {
+            self.init.push(x);
+            if self.init.len() == 5 {
+                let xs = sorted(&self.init);
+                self.markers = (self.probs.iter()).map(|p| Markers::new(*p, &xs)).collect();
+            }
+        } else {
+            self.markers.iter_mut().for_each(|m| m.observe(x));
+        }
+    }
+
+    #[doc = "Current estimates, in the order of `probs`"]
+    pub fn estimates(&self) -> Vec<f64> {
+        assert!(!self.init.is_empty(), "no samples");
+        if self.init.len() < 5 {
+            let xs = sorted(&self.init);
+            let n = xs.len() as f64;
+            return (self.probs.iter())
+                .map(|p| xs[((p * n).ceil() as usize).clamp(1, xs.len()) - 1])
+                .collect();
+        }
+        self.markers.iter().map(|m| m.heights[2]).collect()
+    }
+}
+impl Extend<f64> for Quantiles {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, samples: I) {
+        samples.into_iter().for_each(|x| self.observe(x))
+    }
+}
+
+// five markers at the minimum, p/2, p, (1+p)/2 quantiles and the maximum
+#[derive(Clone, Debug)]
+struct Markers {
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+impl Markers {
+    fn new(p: f64, xs: &[f64]) -> Self {
+        Markers {
+            heights: [xs[0], xs[1], xs[2], xs[3], xs[4]],
+            positions: [0.0, 1.0, 2.0, 3.0, 4.0],
+            desired: [0.0, 2.0 * p, 4.0 * p, 2.0 + 2.0 * p, 4.0],
+            increments: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+        }
+    }
+
+    fn observe(&mut self, x: f64) {
+        let (q, n) = (&mut self.heights, &mut self.positions);
+        let k = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (0..4).rfind(|k| q[*k] <= x).unwrap()
+        };
+        n.iter_mut().skip(k + 1).for_each(|n| *n += 1.0);
+        (self.desired.iter_mut())
+            .zip(self.increments)
+            .for_each(|(d, i)| *d += i);
+
+        for i in 1..4 {
+            let d = self.desired[i] - n[i];
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = (i as f64 + d) as usize;
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+}
+
+#[doc = "Test statistic and its p-value"]
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+#[doc = "One-sample Kolmogorov-Smirnov test of `samples` against a continuous `cdf`"]
+pub fn ks_test<F: Fn(&f64) -> f64>(samples: &[f64], cdf: F) -> Outcome {
+    let xs = sorted(samples);
+    let n = xs.len() as f64;
+    let statistic = (xs.iter().enumerate())
+        .map(|(i, x)| {
+            let p = cdf(x);
+            (p - i as f64 / n).max((i + 1) as f64 / n - p)
+        })
+        .fold(0.0, f64::max);
+    Outcome {
+        statistic,
+        p_value: kolmogorov(n, statistic),
+    }
+}
+
+#[doc = "Two-sample Kolmogorov-Smirnov test that `xs` and `ys` share a distribution"]
+pub fn ks_test2(xs: &[f64], ys: &[f64]) -> Outcome {
+    let (xs, ys) = (sorted(xs), sorted(ys));
+    let (n, m) = (xs.len() as f64, ys.len() as f64);
+    let (mut i, mut j, mut statistic) = (0, 0, 0.0f64);
+    while i < xs.len() && j < ys.len() {
+        let z = xs[i].min(ys[j]);
+        while i < xs.len() && xs[i] <= z {
+            i += 1;
+        }
+        while j < ys.len() && ys[j] <= z {
+            j += 1;
+        }
+        statistic = statistic.max((i as f64 / n - j as f64 / m).abs());
+    }
+    Outcome {
+        statistic,
+        p_value: kolmogorov(n * m / (n + m), statistic),
+    }
+}
+
+#[doc = "Pearson's chi-square goodness-of-fit test of a histogram against the normalized `pmf`"]
+pub fn chi2_test<D: Discrete, P: Fn(&D) -> f64>(histogram: &Histogram<D>, pmf: P) -> Outcome {
+    assert!(histogram.total > 0, "no samples");
+    let ps: Vec<f64> = histogram.support.iter().map(pmf).collect();
+    let sum: f64 = ps.iter().sum();
+    assert!(sum > 0.0, "pmf isn't positive");
+    let n = histogram.total as f64;
+    let (mut statistic, mut bins) = (0.0, 0);
+    for (count, p) in histogram.counts.iter().zip(ps) {
+        let expected = n * p / sum;
+        if expected > 0.0 {
+            statistic += (*count as f64 - expected).powi(2) / expected;
+            bins += 1;
+        } else if *count > 0 {
+            statistic = f64::INFINITY;
+        }
+    }
+    assert!(bins > 1, "too few bins");
+    let p_value = match statistic.is_finite() {
+        true => 1.0 - dist::gamma_inc((bins - 1) as f64 / 2.0, statistic / 2.0),
+        false => 0.0,
+    };
+    Outcome { statistic, p_value }
+}
+
+fn sorted(samples: &[f64]) -> Vec<f64> {
+    assert!(!samples.is_empty(), "no samples");
+    let mut xs = samples.to_vec();
+    xs.sort_by(|a, b| a.partial_cmp(b).expect("sample is nan"));
+    xs
+}
+
+// asymptotic tail of the kolmogorov distribution, with Stephens' finite-sample correction
+fn kolmogorov(n: f64, d: f64) -> f64 {
+    let lambda = (n.sqrt() + 0.12 + 0.11 / n.sqrt()) * d;
+    if lambda < 0.2 {
+        return 1.0;
+    }
+    let q: f64 = (1..=100)
+        .map(|k| {
+            let sign = if k % 2 == 1 { 1.0 } else { -1.0 };
+            sign * (-2.0 * (k * k) as f64 * lambda * lambda).exp()
+        })
+        .sum();
+    (2.0 * q).clamp(0.0, 1.0)
+}
+
+#[doc = "Two-sample permutation tests"]
+pub mod permutation {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    pub struct Outcome {
+        pub statistic: f64,
+        pub p_value: f64,
+        pub permutations: usize,
+    }
+
+    #[doc = "Permutation test stopping early after `stop` exceedances (Besag-Clifford)"]
+    pub struct Test {
+        pub permutations: usize,
+        pub stop: usize,
+    }
+    impl Test {
+        #[allow(unused)]
+        pub fn new(permutations: usize) -> Self {
+            assert!(permutations > 0, "no permutations");
+            Test {
+                permutations,
+                stop: permutations + 1,
+            }
+        }
+
+        pub fn stop(self, stop: usize) -> Self {
+            assert!(stop > 0, "stop isn't positive");
+            Test { stop, ..self }
+        }
+
+        #[doc = "Relabel the pooled samples, large statistics being extreme"]
+        pub fn test<D, S>(&self, xs: &[D], ys: &[D], statistic: S) -> Outcome
+        where
+            D: Clone + Send + Sync + 'static,
+            S: Fn(&[D], &[D]) -> f64 + Send + Sync + 'static,
+        {
+            self.test_with_rng(xs, ys, statistic, &mut rand::thread_rng())
+        }
+
+        #[doc = "Relabel the pooled samples, seeding the worker streams from `gen`"]
+        pub fn test_with_rng<D, S, R>(
+            &self,
+            xs: &[D],
+            ys: &[D],
+            statistic: S,
+            gen: &mut R,
+        ) -> Outcome
+        where
+            D: Clone + Send + Sync + 'static,
+            S: Fn(&[D], &[D]) -> f64 + Send + Sync + 'static,
+            R: rand::Rng + ?Sized,
+        {
+            let seed: u64 = gen.gen();
+            let observed = statistic(xs, ys);
+            let split = xs.len();
+            let pooled: Arc<Vec<D>> = Arc::new(xs.iter().chain(ys.iter()).cloned().collect());
+            let statistic = Arc::new(statistic);
+            let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+            // fixed blocks of permutations on their own streams, so the outcome
+            // doesn't depend on how many threads run them
+            const BLOCK: usize = 64;
+            let (mut done, mut exceed, mut block) = (0, 0, 0);
+            while done < self.permutations && exceed < self.stop {
+                let blocks = ((self.permutations - done + BLOCK - 1) / BLOCK).min(threads);
+                let handles: Vec<_> = (block..block + blocks)
+                    .map(|index| {
+                        let (pooled, statistic) = (pooled.clone(), statistic.clone());
+                        std::thread::spawn(move || {
+                            let mut gen: rand::rngs::StdRng = rng::stream(seed, index as u64);
+                            let mut data = (*pooled).clone();
+                            (0..BLOCK)
+                                .map(|_| {
+                                    shuffle(&mut data, &mut gen);
+                                    statistic(&data[..split], &data[split..]) >= observed
+                                })
+                                .collect::<Vec<bool>>()
+                        })
+                    })
+                    .collect();
+                block += blocks;
+                for handle in handles {
+                    for hit in handle.join().unwrap() {
+                        if done < self.permutations && exceed < self.stop {
+                            done += 1;
+                            exceed += hit as usize;
+                        }
+                    }
+                }
+            }
+
+            let p_value = if exceed >= self.stop {
+                exceed as f64 / done as f64
+            } else {
+                (exceed + 1) as f64 / (done + 1) as f64
+            };
+            Outcome {
+                statistic: observed,
+                p_value,
+                permutations: done,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn shift() {
+            use rand::SeedableRng;
+            let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+            let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+            let difference = move |xs: &[f64], ys: &[f64]| (mean(xs) - mean(ys)).abs();
+            let xs: Vec<f64> = (0..10).map(|i| i as f64).collect();
+            let ys: Vec<f64> = (0..10).map(|i| i as f64 + 10.0).collect();
+            let permutation = Permutation::new(2000).stop(20);
+            let outcome = permutation.test_with_rng(&xs, &ys, difference, &mut gen);
+            assert!(outcome.p_value < 0.01);
+            let outcome = permutation.test_with_rng(&xs, &xs, difference, &mut gen);
+            assert!(outcome.p_value > 0.5);
+            assert!(outcome.permutations < 2000);
+        }
+
+        #[test]
+        fn reproducible() {
+            use rand::SeedableRng;
+            let difference = |xs: &[f64], ys: &[f64]| (xs[0] - ys[0]).abs();
+            let xs: Vec<f64> = (0..10).map(|i| i as f64).collect();
+            let run = || {
+                let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+                Permutation::new(1000).test_with_rng(&xs, &xs, difference, &mut gen)
+            };
+            assert_eq!(run().p_value, run().p_value);
+        }
+    }
+}
+
+#[doc = "Sequential tests with anytime-valid guarantees"]
+pub mod sequential {
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Decision {
+        Continue,
+        Accept,
+        Reject,
+    }
+
+    #[doc = "Wald's sequential probability ratio test of H0 against H1"]
+    #[derive(Clone, Debug)]
+    pub struct Sprt {
+        pub lower: f64,
+        pub upper: f64,
+        pub llr: f64,
+    }
+    impl Sprt {
+        #[allow(unused)]
+        pub fn new(alpha: f64, beta: f64) -> Self {
+            assert!(0.0 < alpha && alpha < 1.0, "alpha isn't in (0, 1)");
+            assert!(0.0 < beta && beta < 1.0, "beta isn't in (0, 1)");
+            Sprt {
+                lower: (beta / (1.0 - alpha)).ln(),
+                upper: ((1.0 - beta) / alpha).ln(),
+                llr: 0.0,
+            }
+        }
+
+        #[doc = "Accumulate `ln p1(x) / p0(x)` for one observation"]
+        pub fn observe(&mut self, llr: f64) -> Decision {
+            self.llr += llr;
+            if self.llr >= self.upper {
+                Decision::Reject
+            } else if self.llr <= self.lower {
+                Decision::Accept
+            } else {
+                Decision::Continue
+            }
+        }
+
+        #[doc = "Observe a success or failure for H0 `p = p0` against H1 `p = p1`"]
+        pub fn bernoulli(&mut self, p0: f64, p1: f64, x: bool) -> Decision {
+            self.observe(match x {
+                true => (p1 / p0).ln(),
+                false => ((1.0 - p1) / (1.0 - p0)).ln(),
+            })
+        }
+    }
+
+    #[doc = "Normal-mixture confidence sequence for the mean of sub-gaussian observations"]
+    #[derive(Clone, Debug)]
+    pub struct Confidence {
+        pub alpha: f64,
+        pub sigma: f64,
+        pub rho: f64,
+        pub n: usize,
+        pub sum: f64,
+    }
+    impl Confidence {
+        #[allow(unused)]
+        pub fn new(alpha: f64, sigma: f64) -> Self {
+            assert!(0.0 < alpha && alpha < 1.0, "alpha isn't in (0, 1)");
+            assert!(sigma > 0.0, "sigma isn't positive");
+            Confidence {
+                alpha,
+                sigma,
+                rho: 100.0 * sigma.powi(2),
+                n: 0,
+                sum: 0.0,
+            }
+        }
+
+        #[doc = "Mixture precision, tightening the sequence around `rho / sigma²` observations"]
+        pub fn rho(self, rho: f64) -> Self {
+            assert!(rho > 0.0, "rho isn't positive");
+            Confidence { rho, ..self }
+        }
+
+        pub fn observe(&mut self, x: f64) {
+            self.n += 1;
+            self.sum += x;
+        }
+
+        #[doc = "E-value against the null mean `mu`"]
+        pub fn e_value(&self, mu: f64) -> f64 {
+            let v = self.n as f64 * self.sigma.powi(2);
+            let s = self.sum - self.n as f64 * mu;
+            (self.rho / (v + self.rho)).sqrt() * (s * s / (2.0 * (v + self.rho))).exp()
+        }
+
+        #[doc = "Interval covering the mean at all times with probability `1 - alpha`"]
+        pub fn interval(&self) -> (f64, f64) {
+            assert!(self.n > 0, "no observations");
+            let (n, v) = (self.n as f64, self.n as f64 * self.sigma.powi(2));
+            let radius = ((v + self.rho) * ((v + self.rho) / (self.rho * self.alpha.powi(2))).ln())
+                .sqrt()
+                / n;
+            (self.sum / n - radius, self.sum / n + radius)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::dist;
+        use rand::{Rng, SeedableRng};
+
+        #[test]
+        fn stopping() {
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let rejected = (0..200)
+                .filter(|_| {
+                    let mut sprt = Sprt::new(0.01, 0.01);
+                    let decision = (0..10000)
+                        .map(|_| sprt.bernoulli(0.5, 0.8, gen.gen_range(0.0..1.0) < 0.8))
+                        .find(|d| *d != Decision::Continue);
+                    decision == Some(Decision::Reject)
+                })
+                .count();
+            assert!(rejected >= 190);
+
+            // the sequence may miss the mean at some time in at most an alpha fraction of runs
+            let (mut missed, mut evidence) = (0, 0.0);
+            for _ in 0..200 {
+                let mut confidence = Confidence::new(0.05, 1.0);
+                let mut covered = true;
+                for _ in 0..200 {
+                    confidence.observe(1.0 + dist::normal(&mut gen));
+                    let (lo, hi) = confidence.interval();
+                    covered &= lo < 1.0 && 1.0 < hi;
+                }
+                missed += !covered as usize;
+                evidence += confidence.e_value(0.0).ln() / 200.0;
+            }
+            assert!(missed <= 20);
+            assert!(evidence > 20f64.ln());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use modular::Z;
+
+    #[test]
+    fn histogram() {
+        use rand::SeedableRng;
+        let pmf = |x: &Z<4>| [1.0, 2.0, 3.0, 4.0][x.0];
+        let gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let histogram: Histogram<Z<4>> = (univar::Icdf::new().sample_with_rng(pmf, gen))
+            .take(20000)
+            .collect();
+        assert_eq!(histogram.total, 20000);
+        assert_eq!(histogram.counts.iter().sum::<usize>(), 20000);
+        assert!(histogram.total_variation(pmf) < 0.02);
+        assert!(histogram.total_variation(|_| 1.0) > 0.15);
+        assert!(chi2_test(&histogram, pmf).p_value > 0.001);
+        assert!(chi2_test(&histogram, |_| 1.0).p_value < 0.001);
+        assert_eq!(chi2_test(&histogram, |x| x.0 as f64).p_value, 0.0);
+    }
+
+    #[test]
+    fn moments() {
+        let moments: Moments = [1.0, 2.0, 3.0, 4.0, 10.0].into_iter().collect();
+        assert_eq!(moments.n, 5);
+        assert!((moments.mean - 4.0).abs() < 1e-12);
+        assert!((moments.variance() - 12.5).abs() < 1e-12);
+        assert!((moments.skewness() - 1.1384199576606167).abs() < 1e-12);
+        assert!((moments.kurtosis() + 0.212).abs() < 1e-12);
+
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let moments: Moments = (0..100000).map(|_| dist::normal(&mut gen)).collect();
+        assert!(moments.mean.abs() < 0.02);
+        assert!((moments.variance() - 1.0).abs() < 0.02);
+        assert!(moments.skewness().abs() < 0.05);
+        assert!(moments.kurtosis().abs() < 0.1);
+    }
+
+    #[test]
+    fn quantiles() {
+        let mut quantiles = Quantiles::new(&[0.025, 0.5, 0.975]);
+        quantiles.extend([3.0, 1.0, 2.0]);
+        assert_eq!(quantiles.estimates(), vec![1.0, 2.0, 3.0]);
+
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        quantiles.extend((0..100000).map(|_| dist::normal(&mut gen)));
+        let estimates = quantiles.estimates();
+        assert!((estimates[0] + 1.96).abs() < 0.05);
+        assert!(estimates[1].abs() < 0.02);
+        assert!((estimates[2] - 1.96).abs() < 0.05);
+    }
+
+    #[test]
+    fn kolmogorov_smirnov() {
+        use rand::SeedableRng;
+        let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+        let cdf = |x: &f64| dist::erfc(-x / 2f64.sqrt()) / 2.0;
+        let xs: Vec<f64> = (0..2000).map(|_| dist::normal(&mut gen)).collect();
+        let ys: Vec<f64> = (0..2000).map(|_| dist::normal(&mut gen)).collect();
+        let shifted: Vec<f64> = ys.iter().map(|y| y + 0.3).collect();
+        assert!(ks_test(&xs, cdf).p_value > 0.001);
+        assert!(ks_test(&shifted, cdf).p_value < 0.001);
+        assert!(ks_test2(&xs, &ys).p_value > 0.001);
+        assert!(ks_test2(&xs, &shifted).p_value < 0.001);
+        assert!((ks_test2(&xs, &xs).statistic).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,524 @@
+use super::*;
+
+pub use arma::Process as Arma;
+pub use changepoint::Detector as Bocpd;
+pub use sv::Model as StochasticVolatility;
+
+#[doc = "Autoregressive moving-average process"]
+pub mod arma {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    pub struct Process {
+        pub ar: Vec<f64>,
+        pub ma: Vec<f64>,
+        pub sigma: f64,
+    }
+    impl Process {
+        #[allow(unused)]
+        pub fn new(ar: Vec<f64>, ma: Vec<f64>, sigma: f64) -> Self {
+            assert!(sigma > 0.0, "sigma isn't positive");
+            Process { ar, ma, sigma }
+        }
+
+        pub fn is_stationary(&self) -> bool {
+            to_pacf(&self.ar).iter().all(|r| r.abs() < 1.0)
+        }
+
+        pub fn is_invertible(&self) -> bool {
+            let ma: Vec<f64> = self.ma.iter().map(|θ| -θ).collect();
+            to_pacf(&ma).iter().all(|r| r.abs() < 1.0)
+        }
+
+        pub fn simulate(&self, n: usize) -> nd::Array1<f64> {
+            self.simulate_with_rng(n, &mut rand::thread_rng())
+        }
+
+        pub fn simulate_with_rng<R: rand::Rng + ?Sized>(
+            &self,
+            n: usize,
+            gen: &mut R,
+        ) -> nd::Array1<f64> {
+            let burn = 100 + self.ar.len() + self.ma.len();
+            let (mut xs, mut es) = (vec![0.0; burn + n], vec![0.0; burn + n]);
+            for t in 0..burn + n {
+                es[t] = self.sigma * dist::normal(gen);
+                xs[t] = es[t] + self.residual(t, &xs, &es);
+            }
+            nd::Array1::from(xs.split_off(burn))
+        }
+
+        pub fn log_likelihood(&self, xs: &[f64]) -> f64 {
+            let mut es = vec![0.0; xs.len()];
+            for t in 0..xs.len() {
+                es[t] = xs[t] - self.residual(t, xs, &es);
+            }
+            let sse: f64 = es.iter().map(|e| e * e).sum();
+            -(xs.len() as f64) * self.sigma.ln() - sse / (2.0 * self.sigma.powi(2))
+        }
+
+        // conditional mean of x[t] given the past, with pre-sample values fixed at zero
+        fn residual(&self, t: usize, xs: &[f64], es: &[f64]) -> f64 {
+            let ar = (self.ar.iter().enumerate())
+                .filter(|(i, _)| t > *i)
+                .map(|(i, φ)| φ * xs[t - 1 - i]);
+            let ma = (self.ma.iter().enumerate())
+                .filter(|(j, _)| t > *j)
+                .map(|(j, θ)| θ * es[t - 1 - j]);
+            ar.chain(ma).sum()
+        }
+    }
+
+    #[doc = "Map partial autocorrelations in (-1, 1) to stationary AR coefficients"]
+    pub fn from_pacf(pacf: &[f64]) -> Vec<f64> {
+        pacf.iter().fold(vec![], |φ, &r| {
+            let k = φ.len();
+            let mut next: Vec<f64> = (0..k).map(|j| φ[j] - r * φ[k - 1 - j]).collect();
+            next.push(r);
+            next
+        })
+    }
+
+    #[doc = "Map AR coefficients back to partial autocorrelations"]
+    pub fn to_pacf(ar: &[f64]) -> Vec<f64> {
+        let mut φ = ar.to_vec();
+        let mut pacf = vec![0.0; ar.len()];
+        for k in (0..ar.len()).rev() {
+            let r = φ[k];
+            pacf[k] = r;
+            if r.abs() >= 1.0 {
+                break;
+            }
+            φ = (0..k)
+                .map(|j| (φ[j] + r * φ[k - 1 - j]) / (1.0 - r * r))
+                .collect();
+        }
+        pacf
+    }
+
+    #[doc = "Decode unconstrained parameters `[ar; ma; log sigma]` into a process"]
+    pub fn decode<D: num::ToPrimitive>(z: &nd::Array1<D>, p: usize, q: usize) -> Process {
+        assert_eq!(z.len(), p + q + 1, "parameter length mismatch");
+        let z: Vec<f64> = z.iter().map(|z| z.to_f64().unwrap()).collect();
+        let ar: Vec<f64> = z[..p].iter().map(|z| z.tanh()).collect();
+        let ma: Vec<f64> = z[p..p + q].iter().map(|z| z.tanh()).collect();
+        Process {
+            ar: from_pacf(&ar),
+            ma: from_pacf(&ma).iter().map(|θ| -θ).collect(),
+            sigma: z[p + q].exp(),
+        }
+    }
+
+    #[doc = "Log-posterior over unconstrained parameters, uniform on partial autocorrelations, for sampling on `scale::Log`"]
+    pub fn posterior<D: num::ToPrimitive>(
+        xs: nd::Array1<f64>,
+        p: usize,
+        q: usize,
+    ) -> impl Fn(&nd::Array1<D>) -> f64 {
+        let xs = xs.to_vec();
+        move |z| {
+            let ln_jacobian: f64 = (z.iter().take(p + q))
+                .map(|z| (1.0 - z.to_f64().unwrap().tanh().powi(2)).ln())
+                .sum();
+            decode(z, p, q).log_likelihood(&xs) + ln_jacobian
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn pacf() {
+            let ar = from_pacf(&[0.5, -0.3, 0.2]);
+            to_pacf(&ar)
+                .iter()
+                .zip([0.5, -0.3, 0.2].iter())
+                .for_each(|(x, y)| assert!((x - y).abs() < 1e-12));
+            assert!(Process::new(ar, vec![], 1.0).is_stationary());
+        }
+
+        #[test]
+        fn recover() {
+            use rand::SeedableRng;
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let xs = Process::new(vec![0.6], vec![0.3], 1.0).simulate_with_rng(2000, &mut gen);
+            let posterior = posterior(xs, 1, 1);
+            // long series stay finite on the log scale
+            assert!(posterior(&nd::Array1::from(vec![0.0, 0.0, 0.0])).is_finite());
+
+            let grid = || (-19..20).map(|i| i as f64 / 20.0);
+            let (_, ar, ma) = grid()
+                .flat_map(|ar| grid().map(move |ma| (ar, ma)))
+                .map(|(ar, ma)| {
+                    let z = nd::Array1::from(vec![f64::atanh(ar), f64::atanh(-ma), 0.0]);
+                    (posterior(&z), ar, ma)
+                })
+                .fold((f64::NEG_INFINITY, 0.0, 0.0), |best, x| {
+                    if x.0 > best.0 {
+                        x
+                    } else {
+                        best
+                    }
+                });
+            assert!((ar - 0.6).abs() < 0.1);
+            assert!((ma - 0.3).abs() < 0.1);
+        }
+    }
+}
+
+#[doc = "Stochastic volatility with latent AR(1) log-variance"]
+pub mod sv {
+    use super::*;
+
+    // normal mixture approximating log χ²₁ by Kim, Shephard & Chib: (weight, mean, variance)
+    const MIXTURE: [(f64, f64, f64); 7] = [
+        (0.00730, -10.12999, 5.79596),
+        (0.10556, -3.97281, 2.61369),
+        (0.00002, -8.56686, 5.17950),
+        (0.04395, 2.77786, 0.16735),
+        (0.34001, 0.61942, 0.64009),
+        (0.24566, 1.79518, 0.34023),
+        (0.25750, -1.08819, 1.26261),
+    ];
+    const SHIFT: f64 = -1.2704;
+    const OFFSET: f64 = 0.001;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Model {
+        pub mu: f64,
+        pub phi: f64,
+        pub sigma: f64,
+    }
+    impl Model {
+        #[allow(unused)]
+        pub fn new(mu: f64, phi: f64, sigma: f64) -> Self {
+            assert!(phi.abs() < 1.0, "phi isn't stationary");
+            assert!(sigma > 0.0, "sigma isn't positive");
+            Model { mu, phi, sigma }
+        }
+
+        pub fn simulate(&self, n: usize) -> (nd::Array1<f64>, nd::Array1<f64>) {
+            self.simulate_with_rng(n, &mut rand::thread_rng())
+        }
+
+        pub fn simulate_with_rng<R: rand::Rng + ?Sized>(
+            &self,
+            n: usize,
+            gen: &mut R,
+        ) -> (nd::Array1<f64>, nd::Array1<f64>) {
+            let mut h = self.mu + self.sigma / (1.0 - self.phi.powi(2)).sqrt() * dist::normal(gen);
+            let (mut hs, mut ys) = (vec![], vec![]);
+            for _ in 0..n {
+                hs.push(h);
+                ys.push((h / 2.0).exp() * dist::normal(gen));
+                h = self.mu + self.phi * (h - self.mu) + self.sigma * dist::normal(gen);
+            }
+            (nd::Array1::from(hs), nd::Array1::from(ys))
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct State {
+        pub model: Model,
+        pub h: nd::Array1<f64>,
+    }
+
+    #[doc = "Mixture-offset Gibbs sampler over parameters and log-variances"]
+    pub struct Sampler {
+        pub ys: nd::Array1<f64>,
+    }
+    impl Sampler {
+        #[allow(unused)]
+        pub fn new(ys: nd::Array1<f64>) -> Self {
+            assert!(ys.len() > 1, "series too short");
+            Sampler { ys }
+        }
+
+        pub fn iter(&self) -> impl Iterator<Item = State> {
+            self.iter_with_rng(rand::thread_rng())
+        }
+
+        pub fn iter_with_rng<R: rand::Rng>(&self, mut gen: R) -> impl Iterator<Item = State> {
+            let ys: Vec<f64> = self.ys.iter().map(|y| (y * y + OFFSET).ln()).collect();
+            let mut model = Model::new(ys.iter().sum::<f64>() / ys.len() as f64, 0.9, 0.3);
+            let mut hs = ys.clone();
+            std::iter::from_fn(move || {
+                let ss = indicators(&ys, &hs, &mut gen);
+                hs = states(&ys, &ss, &model, &mut gen);
+                model = parameters(&hs, &model, &mut gen);
+                Some(State {
+                    model: model.clone(),
+                    h: nd::Array1::from(hs.clone()),
+                })
+            })
+        }
+    }
+
+    fn indicators(ys: &[f64], hs: &[f64], gen: &mut impl rand::Rng) -> Vec<usize> {
+        let weights = |r: f64| {
+            MIXTURE.map(|(q, m, v)| q * (-(r - m - SHIFT).powi(2) / (2.0 * v)).exp() / v.sqrt())
+        };
+        ys.iter()
+            .zip(hs.iter())
+            .map(|(y, h)| {
+                let ws = weights(y - h);
+                let mut aux = gen.gen_range(0.0..ws.iter().sum::<f64>());
+                ws.iter()
+                    .position(|w| {
+                        aux -= w;
+                        aux < 0.0
+                    })
+                    .unwrap_or(ws.len() - 1)
+            })
+            .collect()
+    }
+
+    // forward filtering, backward sampling on the linearized state space
+    fn states(ys: &[f64], ss: &[usize], model: &Model, gen: &mut impl rand::Rng) -> Vec<f64> {
+        let Model { mu, phi, sigma } = *model;
+        let (mut ms, mut cs) = (vec![], vec![]);
+        let (mut a, mut p) = (mu, sigma.powi(2) / (1.0 - phi.powi(2)));
+        for (y, &s) in ys.iter().zip(ss.iter()) {
+            let (_, m, v) = MIXTURE[s];
+            let k = p / (p + v);
+            ms.push(a + k * (y - m - SHIFT - a));
+            cs.push((1.0 - k) * p);
+            a = mu + phi * (ms[ms.len() - 1] - mu);
+            p = phi.powi(2) * cs[cs.len() - 1] + sigma.powi(2);
+        }
+
+        let n = ys.len();
+        let mut hs = vec![0.0; n];
+        hs[n - 1] = ms[n - 1] + cs[n - 1].sqrt() * dist::normal(gen);
+        for t in (0..n - 1).rev() {
+            let p = phi.powi(2) * cs[t] + sigma.powi(2);
+            let j = phi * cs[t] / p;
+            let m = ms[t] + j * (hs[t + 1] - mu - phi * (ms[t] - mu));
+            hs[t] = m + (cs[t] - j * j * p).max(0.0).sqrt() * dist::normal(gen);
+        }
+        hs
+    }
+
+    fn parameters(hs: &[f64], model: &Model, gen: &mut impl rand::Rng) -> Model {
+        let Model { mut phi, sigma, .. } = *model;
+        let n = hs.len() as f64;
+
+        let prec = (1.0 - phi.powi(2)) + (n - 1.0) * (1.0 - phi).powi(2);
+        let sum = (1.0 - phi.powi(2)) * hs[0]
+            + (1.0 - phi) * hs.windows(2).map(|h| h[1] - phi * h[0]).sum::<f64>();
+        let mu = sum / prec + sigma / prec.sqrt() * dist::normal(gen);
+
+        let sxx: f64 = hs[..hs.len() - 1].iter().map(|h| (h - mu).powi(2)).sum();
+        let sxy: f64 = hs.windows(2).map(|h| (h[0] - mu) * (h[1] - mu)).sum();
+        let proposal = sxy / sxx + sigma / sxx.sqrt() * dist::normal(gen);
+        if proposal.abs() < 1.0 {
+            let initial = |phi: f64| {
+                (1.0 - phi.powi(2)).sqrt()
+                    * (-(1.0 - phi.powi(2)) * (hs[0] - mu).powi(2) / (2.0 * sigma.powi(2))).exp()
+            };
+            if gen.gen_range(0.0..1.0) < initial(proposal) / initial(phi) {
+                phi = proposal;
+            }
+        }
+
+        let sse = (1.0 - phi.powi(2)) * (hs[0] - mu).powi(2)
+            + hs.windows(2)
+                .map(|h| (h[1] - mu - phi * (h[0] - mu)).powi(2))
+                .sum::<f64>();
+        let sigma = (sse / 2.0 / dist::gamma(gen, n / 2.0)).sqrt();
+
+        Model { mu, phi, sigma }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn recover() {
+            use rand::SeedableRng;
+            let mut gen = rand::rngs::StdRng::seed_from_u64(0);
+            let truth = Model::new(-1.0, 0.95, 0.2);
+            let (hs, ys) = truth.simulate_with_rng(1000, &mut gen);
+            let draws: Vec<State> = (Sampler::new(ys).iter_with_rng(gen))
+                .skip(500)
+                .take(1500)
+                .collect();
+            let mean = |f: &dyn Fn(&State) -> f64| draws.iter().map(f).sum::<f64>() / 1500.0;
+            assert!((mean(&|s| s.model.mu) + 1.0).abs() < 0.5);
+            assert!((mean(&|s| s.model.phi) - 0.95).abs() < 0.1);
+            // smoothed log-variances beat the stationary mean as a guess
+            let mse = (0..hs.len())
+                .map(|t| (mean(&|s| s.h[t]) - hs[t]).powi(2))
+                .sum::<f64>();
+            let var = hs.iter().map(|h| (h + 1.0).powi(2)).sum::<f64>();
+            assert!(mse < var);
+        }
+    }
+}
+
+#[doc = "Bayesian online change-point detection (Adams & MacKay)"]
+pub mod changepoint {
+    use super::*;
+
+    #[doc = "Posterior predictive of a run, updated one observation at a time"]
+    pub trait Predictive: Clone {
+        fn pdf(&self, x: f64) -> f64;
+        fn observe(&self, x: f64) -> Self;
+    }
+
+    #[doc = "Gaussian with unknown mean and precision under a normal-gamma prior"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct NormalGamma {
+        pub mu: f64,
+        pub kappa: f64,
+        pub alpha: f64,
+        pub beta: f64,
+    }
+    impl NormalGamma {
+        #[allow(unused)]
+        pub fn new(mu: f64, kappa: f64, alpha: f64, beta: f64) -> Self {
+            assert!(kappa > 0.0, "kappa isn't positive");
+            assert!(alpha > 0.0, "alpha isn't positive");
+            assert!(beta > 0.0, "beta isn't positive");
+            NormalGamma {
+                mu,
+                kappa,
+                alpha,
+                beta,
+            }
+        }
+    }
+    impl Predictive for NormalGamma {
+        fn pdf(&self, x: f64) -> f64 {
+            let nu = 2.0 * self.alpha;
+            let scale2 = self.beta * (self.kappa + 1.0) / (self.alpha * self.kappa);
+            let z2 = (x - self.mu).powi(2) / scale2;
+            (dist::ln_gamma((nu + 1.0) / 2.0)
+                - dist::ln_gamma(nu / 2.0)
+                - (nu * std::f64::consts::PI * scale2).ln() / 2.0
+                - (nu + 1.0) / 2.0 * (1.0 + z2 / nu).ln())
+            .exp()
+        }
+        fn observe(&self, x: f64) -> Self {
+            let kappa = self.kappa + 1.0;
+            NormalGamma {
+                mu: (self.kappa * self.mu + x) / kappa,
+                kappa,
+                alpha: self.alpha + 0.5,
+                beta: self.beta + self.kappa * (x - self.mu).powi(2) / (2.0 * kappa),
+            }
+        }
+    }
+
+    #[doc = "Poisson counts with unknown rate under a gamma prior"]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PoissonGamma {
+        pub shape: f64,
+        pub rate: f64,
+    }
+    impl Predictive for PoissonGamma {
+        fn pdf(&self, x: f64) -> f64 {
+            let (a, b, k) = (self.shape, self.rate, x.round());
+            (dist::ln_gamma(a + k) - dist::ln_gamma(a) - dist::ln_gamma(k + 1.0)
+                + a * (b / (b + 1.0)).ln()
+                - k * (b + 1.0).ln())
+            .exp()
+        }
+        fn observe(&self, x: f64) -> Self {
+            PoissonGamma {
+                shape: self.shape + x.round(),
+                rate: self.rate + 1.0,
+            }
+        }
+    }
+
+    #[doc = "Run-length posterior under a constant hazard"]
+    #[derive(Clone, Debug)]
+    pub struct Detector<P: Predictive> {
+        pub prior: P,
+        pub hazard: f64,
+        pub threshold: f64,
+        runs: Vec<(f64, P)>,
+    }
+    impl<P: Predictive> Detector<P> {
+        #[allow(unused)]
+        pub fn new(prior: P, hazard: f64) -> Self {
+            assert!(0.0 < hazard && hazard < 1.0, "hazard isn't in (0, 1)");
+            Detector {
+                runs: vec![(1.0, prior.clone())],
+                prior,
+                hazard,
+                threshold: 1e-10,
+            }
+        }
+
+        #[doc = "Drop run lengths whose probability falls below `threshold`"]
+        pub fn threshold(self, threshold: f64) -> Self {
+            assert!(threshold >= 0.0, "threshold is negative");
+            Detector { threshold, ..self }
+        }
+
+        #[doc = "Update on one observation, returning the run-length distribution"]
+        pub fn observe<D: num::ToPrimitive>(&mut self, x: D) -> Vec<f64> {
+            let x = x.to_f64().unwrap();
+            let predictive: Vec<f64> = self.runs.iter().map(|(p, m)| p * m.pdf(x)).collect();
+            let change = self.hazard * predictive.iter().sum::<f64>();
+            let mut runs = vec![(change, self.prior.clone())];
+            runs.extend(
+                (self.runs.iter().zip(predictive))
+                    .map(|((_, m), p)| ((1.0 - self.hazard) * p, m.observe(x))),
+            );
+
+            let evidence: f64 = runs.iter().map(|(p, _)| p).sum();
+            assert!(evidence > 0.0, "observation has zero predictive density");
+            runs.iter_mut().for_each(|(p, _)| *p /= evidence);
+            while runs.len() > 1 && runs[runs.len() - 1].0 < self.threshold {
+                runs.pop();
+            }
+            self.runs = runs;
+            self.probabilities()
+        }
+
+        pub fn probabilities(&self) -> Vec<f64> {
+            self.runs.iter().map(|(p, _)| *p).collect()
+        }
+
+        #[doc = "Most probable current run length"]
+        pub fn run_length(&self) -> usize {
+            (self.runs.iter().enumerate())
+                .max_by(|(_, a), (_, b)| a.0.partial_cmp(&b.0).unwrap())
+                .map_or(0, |(r, _)| r)
+        }
+
+        #[doc = "Most probable run lengths along a stream of samples"]
+        pub fn detect<D: num::ToPrimitive, I: IntoIterator<Item = D>>(
+            mut self,
+            xs: I,
+        ) -> impl Iterator<Item = usize> {
+            xs.into_iter().map(move |x| {
+                self.observe(x);
+                self.run_length()
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn shift() {
+            use rand::SeedableRng;
+            let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+            let xs: Vec<f64> = (0..200)
+                .map(|t| if t < 100 { 0.0 } else { 5.0 } + dist::normal(&mut gen))
+                .collect();
+            let detector = Bocpd::new(NormalGamma::new(0.0, 0.1, 1.0, 1.0), 0.01);
+            let runs: Vec<usize> = detector.detect(xs).collect();
+            assert!(runs[99] > 80);
+            assert!((95..=105).contains(&(199 - runs[199])));
+        }
+    }
+}
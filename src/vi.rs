@@ -0,0 +1,208 @@
+use super::*;
+use family::ExponentialFamily;
+
+pub use natural::Gradient as NaturalGradient;
+pub use score::Estimator as ScoreFunction;
+
+#[doc = "Natural-gradient updates for exponential-family approximations"]
+pub mod natural {
+    use super::*;
+
+    pub struct Gradient {
+        pub step: f64,
+    }
+    impl Gradient {
+        #[allow(unused)]
+        pub fn new(step: f64) -> Self {
+            assert!(0.0 < step && step <= 1.0, "step isn't in (0, 1]");
+            Gradient { step }
+        }
+
+        #[doc = "Approximations of `prior × likelihood`, given the gradient of `E_q[log likelihood]` in expectation parameters"]
+        pub fn iter<F: ExponentialFamily, G: FnMut(&F) -> Vec<f64>>(
+            &self,
+            prior: F,
+            mut gradient: G,
+        ) -> impl Iterator<Item = F> {
+            let step = self.step;
+            let eta = prior.natural();
+            let mut lambda = eta.clone();
+            std::iter::from_fn(move || {
+                let g = gradient(&F::from_natural(&lambda));
+                assert_eq!(g.len(), eta.len(), "gradient mismatch");
+                lambda = (lambda.iter().zip(eta.iter()).zip(g))
+                    .map(|((l, e), g)| (1.0 - step) * l + step * (e + g))
+                    .collect();
+                Some(F::from_natural(&lambda))
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use family::Gaussian;
+
+        #[test]
+        fn conjugate() {
+            let ys = [0.5, 1.5, 1.0];
+            let sum: f64 = ys.iter().sum();
+            let n = ys.len() as f64;
+            let q = NaturalGradient::new(0.5)
+                .iter(
+                    Gaussian {
+                        mu: 0.0,
+                        sigma: 1.0,
+                    },
+                    |_| vec![sum, -n / 2.0],
+                )
+                .nth(50)
+                .unwrap();
+            assert!((q.mu - sum / (1.0 + n)).abs() < 1e-9);
+            assert!((q.sigma - (1.0 / (1.0 + n)).sqrt()).abs() < 1e-9);
+        }
+    }
+}
+
+#[doc = "Score-function (REINFORCE) gradient estimators"]
+pub mod score {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug)]
+    pub enum Baseline {
+        Zero,
+        Constant(f64),
+        LeaveOneOut,
+        Regression,
+    }
+
+    pub struct Estimator {
+        pub samples: usize,
+        pub baseline: Baseline,
+    }
+    impl Estimator {
+        #[allow(unused)]
+        pub fn new(samples: usize) -> Self {
+            assert!(samples > 1, "too few samples");
+            Estimator {
+                samples,
+                baseline: Baseline::LeaveOneOut,
+            }
+        }
+
+        pub fn baseline(self, baseline: Baseline) -> Self {
+            Estimator { baseline, ..self }
+        }
+
+        #[doc = "Gradient of `E_q[f]` in natural parameters"]
+        pub fn gradient<F: ExponentialFamily, G: FnMut(&F::Domain) -> f64>(
+            &self,
+            q: &F,
+            f: G,
+        ) -> Vec<f64> {
+            self.gradient_with_rng(q, f, &mut rand::thread_rng())
+        }
+
+        #[doc = "Gradient of `E_q[f]` in natural parameters, drawing from `gen`"]
+        pub fn gradient_with_rng<F, G, R>(&self, q: &F, f: G, gen: &mut R) -> Vec<f64>
+        where
+            F: ExponentialFamily,
+            G: FnMut(&F::Domain) -> f64,
+            R: rand::Rng + ?Sized,
+        {
+            let (fs, scores) = self.draw(q, f, gen);
+            self.combine(&fs, &scores)
+        }
+
+        #[doc = "Gradient of `E_q[f]` in expectation parameters, preconditioned by the sample fisher"]
+        pub fn natural_gradient<F: ExponentialFamily, G: FnMut(&F::Domain) -> f64>(
+            &self,
+            q: &F,
+            f: G,
+        ) -> Vec<f64> {
+            self.natural_gradient_with_rng(q, f, &mut rand::thread_rng())
+        }
+
+        #[doc = "Gradient of `E_q[f]` in expectation parameters, drawing from `gen`"]
+        pub fn natural_gradient_with_rng<F, G, R>(&self, q: &F, f: G, gen: &mut R) -> Vec<f64>
+        where
+            F: ExponentialFamily,
+            G: FnMut(&F::Domain) -> f64,
+            R: rand::Rng + ?Sized,
+        {
+            let (fs, scores) = self.draw(q, f, gen);
+            let d = scores[0].len();
+            let fisher = na::DMatrix::from_fn(d, d, |i, j| {
+                scores.iter().map(|s| s[i] * s[j]).sum::<f64>() / fs.len() as f64
+            });
+            let g = na::DVector::from_vec(self.combine(&fs, &scores));
+            let g = (fisher.cholesky())
+                .expect("fisher isn't positive definite")
+                .solve(&g);
+            g.iter().cloned().collect()
+        }
+
+        // objective values and scores `T(x) - E[T]` at draws from q
+        fn draw<F: ExponentialFamily, G: FnMut(&F::Domain) -> f64, R: rand::Rng + ?Sized>(
+            &self,
+            q: &F,
+            mut f: G,
+            gen: &mut R,
+        ) -> (Vec<f64>, Vec<Vec<f64>>) {
+            let mu = q.expectation();
+            (0..self.samples)
+                .map(|_| {
+                    let x = q.sample(gen);
+                    let score = (F::statistics(&x).iter())
+                        .zip(mu.iter())
+                        .map(|(t, m)| t - m)
+                        .collect();
+                    (f(&x), score)
+                })
+                .unzip()
+        }
+
+        fn combine(&self, fs: &[f64], scores: &[Vec<f64>]) -> Vec<f64> {
+            let n = fs.len() as f64;
+            let sum: f64 = fs.iter().sum();
+            (0..scores[0].len())
+                .map(|k| {
+                    let regression = fs.iter().zip(scores.iter()).map(|(f, s)| f * s[k] * s[k]);
+                    let c =
+                        regression.sum::<f64>() / scores.iter().map(|s| s[k] * s[k]).sum::<f64>();
+                    (fs.iter().zip(scores.iter()))
+                        .map(|(f, s)| {
+                            let b = match self.baseline {
+                                Baseline::Zero => 0.0,
+                                Baseline::Constant(b) => b,
+                                Baseline::LeaveOneOut => (sum - f) / (n - 1.0),
+                                Baseline::Regression => c,
+                            };
+                            (f - b) * s[k]
+                        })
+                        .sum::<f64>()
+                        / n
+                })
+                .collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use family::Bernoulli;
+
+        #[test]
+        fn bernoulli() {
+            use rand::SeedableRng;
+            let mut gen = rand::rngs::SmallRng::seed_from_u64(0);
+            let q = Bernoulli { p: 0.3 };
+            let f = |x: &bool| if *x { 1.0 } else { 0.0 };
+            for baseline in [Baseline::Zero, Baseline::LeaveOneOut, Baseline::Regression] {
+                let estimator = ScoreFunction::new(20000).baseline(baseline);
+                let g = estimator.gradient_with_rng(&q, f, &mut gen);
+                assert!((g[0] - 0.21).abs() < 0.02);
+            }
+        }
+    }
+}